@@ -1,4 +1,5 @@
-use bollard::container::{Config, CreateContainerOptions, ListContainersOptions, LogsOptions, RemoveContainerOptions, StartContainerOptions};
+use bollard::container::{Config, CreateContainerOptions, ListContainersOptions, LogsOptions, NetworkingConfig, RemoveContainerOptions, StartContainerOptions};
+use bollard::network::ConnectNetworkOptions;
 use bollard::exec::{CreateExecOptions, StartExecResults};
 use bollard::image::CreateImageOptions;
 use bollard::Docker;
@@ -7,8 +8,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::fs;
-use tauri::State;
-use tokio::sync::Mutex;
+use std::sync::Arc;
+use tauri::{Emitter, Manager, State};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 // Enum para tipos de bases de datos
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -81,6 +83,41 @@ impl DatabaseType {
             DatabaseType::MariaDB => format!("mariadb:{}", version),
         }
     }
+
+    /// Parse the value of the `db_type` label written by `create_database`
+    pub fn from_label(label: &str) -> Option<DatabaseType> {
+        match label.trim().to_lowercase().as_str() {
+            "postgresql" | "postgres" => Some(DatabaseType::PostgreSQL),
+            "mysql" => Some(DatabaseType::MySQL),
+            "mongodb" | "mongo" => Some(DatabaseType::MongoDB),
+            "redis" => Some(DatabaseType::Redis),
+            "mariadb" => Some(DatabaseType::MariaDB),
+            _ => None,
+        }
+    }
+
+    /// Guess the engine from an image reference such as `postgres:16` or `library/mariadb:11.2`
+    pub fn from_image(image: &str) -> Option<DatabaseType> {
+        let repo = image.split(':').next().unwrap_or(image);
+        let repo = repo.rsplit('/').next().unwrap_or(repo);
+        match repo {
+            r if r.starts_with("postgres") || r.starts_with("postgis") => Some(DatabaseType::PostgreSQL),
+            r if r.starts_with("mariadb") => Some(DatabaseType::MariaDB),
+            r if r.starts_with("mysql") => Some(DatabaseType::MySQL),
+            r if r.starts_with("mongo") => Some(DatabaseType::MongoDB),
+            r if r.starts_with("redis") => Some(DatabaseType::Redis),
+            _ => None,
+        }
+    }
+}
+
+/// Detect the engine of a container: the `db_type` label wins, the image name is the fallback
+/// for containers that were not created by this app.
+fn detect_database_type(labels: Option<&HashMap<String, String>>, image: Option<&str>) -> Option<DatabaseType> {
+    labels
+        .and_then(|l| l.get("db_type"))
+        .and_then(|t| DatabaseType::from_label(t))
+        .or_else(|| image.and_then(DatabaseType::from_image))
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -100,6 +137,9 @@ pub struct DatabaseConfig {
     pub env: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none", rename = "restartPolicy")]
     pub restart_policy: Option<String>,
+    /// Enable statement statistics (pg_stat_statements / performance_schema)
+    #[serde(default, rename = "queryInsights")]
+    pub query_insights: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -151,8 +191,65 @@ pub struct LogEntry {
     pub message: String,
 }
 
+/// Shared backend state
+///
+/// The bollard client is cheap to clone and safe to use concurrently, so commands take
+/// their own copy with `docker()` and run in parallel. The `RwLock` is only held for the
+/// instant it takes to clone or swap the client, never across an `.await`.
+/// Operations that mutate the same container are serialized with `lock_container`.
 pub struct AppState {
-    docker: Mutex<Docker>,
+    docker: std::sync::RwLock<Docker>,
+    container_locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Wakes long-lived streams (Docker events) so they move to the new client
+    docker_replaced: tokio::sync::Notify,
+}
+
+impl AppState {
+    pub fn new(docker: Docker) -> Self {
+        AppState {
+            docker: std::sync::RwLock::new(docker),
+            container_locks: std::sync::Mutex::new(HashMap::new()),
+            docker_replaced: tokio::sync::Notify::new(),
+        }
+    }
+
+    /// Current Docker client
+    pub fn docker(&self) -> Docker {
+        self.docker.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Swap the Docker client atomically (used by `reconnect_docker`).
+    /// Commands already running keep the client they started with; the events
+    /// listener is told to reconnect with the new one.
+    pub fn replace_docker(&self, docker: Docker) {
+        *self.docker.write().unwrap_or_else(|e| e.into_inner()) = docker;
+        self.docker_replaced.notify_waiters();
+    }
+
+    /// Serialize operations on one container (by ID or name)
+    ///
+    /// Two commands touching different containers never wait on each other; a second
+    /// `stop`/`remove`/`update_container_port` on the same container waits for the first.
+    /// The name, short ID or full ID the caller has is resolved to the full container ID,
+    /// so they all share one lock; a container that does not exist yet (being created) is
+    /// locked by its name.
+    pub async fn lock_container(&self, container: &str) -> OwnedMutexGuard<()> {
+        let key = match self.docker().inspect_container(container, None).await {
+            Ok(info) => info.id.unwrap_or_else(|| container.to_string()),
+            Err(_) => container.trim_start_matches('/').to_string(),
+        };
+        self.lock_key(&key).await
+    }
+
+    async fn lock_key(&self, key: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.container_locks.lock().unwrap_or_else(|e| e.into_inner());
+            // Limpiar locks que nadie está usando
+            locks.retain(|k, l| k == key || Arc::strong_count(l) > 1);
+            locks.entry(key.to_string()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
 }
 
 // Estructura para enviar info de tipos de BD al frontend
@@ -168,7 +265,7 @@ pub struct DatabaseTypeInfo {
 
 #[tauri::command]
 async fn check_docker(state: State<'_, AppState>) -> Result<bool, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     match docker.ping().await {
         Ok(_) => Ok(true),
         Err(_) => Ok(false), // NUNCA devolver Err, solo Ok(false)
@@ -227,7 +324,7 @@ fn get_database_types() -> Result<Vec<DatabaseTypeInfo>, String> {
 
 #[tauri::command]
 async fn list_containers(state: State<'_, AppState>) -> Result<Vec<ContainerInfo>, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     
     // SIN FILTROS - Muestra TODOS los contenedores de Docker
     let containers = docker.list_containers(Some(ListContainersOptions::<String> { 
@@ -259,7 +356,7 @@ pub struct ImageInfo {
 
 #[tauri::command]
 async fn list_images(state: State<'_, AppState>) -> Result<Vec<ImageInfo>, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     
     let images = docker.list_images::<String>(None)
         .await.map_err(|e| e.to_string())?;
@@ -301,7 +398,7 @@ async fn list_images(state: State<'_, AppState>) -> Result<Vec<ImageInfo>, Strin
 
 #[tauri::command]
 async fn remove_image(state: State<'_, AppState>, image_id: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     
     println!("🗑️ Eliminando imagen: {}", image_id);
     
@@ -316,8 +413,12 @@ async fn remove_image(state: State<'_, AppState>, image_id: String) -> Result<St
 #[tauri::command]
 async fn create_database(state: State<'_, AppState>, config: DatabaseConfig) -> Result<String, String> {
     println!("📦 Creando base de datos: {} ({})", config.name, config.db_type.to_string());
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     let image = config.db_type.get_image_name(&config.version);
+    let container_name = format!("{}-{}", config.db_type.to_string(), config.name);
+    
+    // Dos creaciones con el mismo nombre no deben pisarse mientras se descarga la imagen
+    let _guard = state.lock_container(&container_name).await;
     
     println!("🔍 Validando puerto y nombre...");
     // Validar que el puerto no esté en uso
@@ -339,7 +440,6 @@ async fn create_database(state: State<'_, AppState>, config: DatabaseConfig) ->
     }
     
     // Validar que el nombre no esté en uso
-    let container_name = format!("{}-{}", config.db_type.to_string(), config.name);
     for container in &containers {
         if let Some(names) = &container.names {
             for name in names {
@@ -484,6 +584,15 @@ async fn create_database(state: State<'_, AppState>, config: DatabaseConfig) ->
         container_json.as_object_mut().unwrap().insert("Cmd".to_string(), json!(["redis-server", "--requirepass", &config.password]));
     }
     
+    // Estadísticas de consultas (pg_stat_statements / performance_schema)
+    if config.query_insights && supports_query_insights(&config.db_type) {
+        let cmd = query_insights_cmd(&config.db_type, &[], true);
+        if !cmd.is_empty() {
+            container_json.as_object_mut().unwrap().insert("Cmd".to_string(), json!(cmd));
+        }
+        container_json["Labels"]["query_insights"] = json!("true");
+    }
+    
     let container_config: Config<String> = serde_json::from_value(container_json)
         .map_err(|e| format!("Error en configuración: {}", e))?;
     
@@ -503,7 +612,8 @@ async fn create_database(state: State<'_, AppState>, config: DatabaseConfig) ->
 
 #[tauri::command]
 async fn start_container(state: State<'_, AppState>, container_id: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     docker.start_container(&container_id, None::<StartContainerOptions<String>>)
         .await.map_err(|e| e.to_string())?;
     Ok("Iniciado".to_string())
@@ -511,21 +621,24 @@ async fn start_container(state: State<'_, AppState>, container_id: String) -> Re
 
 #[tauri::command]
 async fn stop_container(state: State<'_, AppState>, container_id: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     docker.stop_container(&container_id, None).await.map_err(|e| e.to_string())?;
     Ok("Detenido".to_string())
 }
 
 #[tauri::command]
 async fn restart_container(state: State<'_, AppState>, container_id: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     docker.restart_container(&container_id, None).await.map_err(|e| e.to_string())?;
     Ok("Reiniciado".to_string())
 }
 
 #[tauri::command]
 async fn rename_container(state: State<'_, AppState>, container_id: String, new_name: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     
     // Validar que el nuevo nombre no esté vacío
     if new_name.trim().is_empty() {
@@ -565,7 +678,8 @@ async fn update_container_port(
     container_id: String, 
     new_port: u16
 ) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     
     println!("🔧 Updating container port: {} -> {}", container_id, new_port);
     
@@ -634,6 +748,7 @@ async fn update_container_port(
     let new_container_config = json!({
         "Image": image,
         "Env": env,
+        "Cmd": config.cmd,
         "ExposedPorts": exposed_ports,
         "Labels": labels,
         "HostConfig": new_host_config
@@ -670,7 +785,8 @@ async fn update_container_port(
 
 #[tauri::command]
 async fn remove_container(state: State<'_, AppState>, container_id: String, remove_volumes: bool) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
     
     // Verificar que el contenedor existe
     let containers = docker.list_containers(Some(ListContainersOptions::<String> { 
@@ -707,7 +823,7 @@ async fn remove_container(state: State<'_, AppState>, container_id: String, remo
 
 #[tauri::command]
 async fn get_logs(state: State<'_, AppState>, container_id: String) -> Result<Vec<LogEntry>, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     let mut stream = docker.logs(&container_id, Some(LogsOptions::<String> { stdout: true, stderr: true, tail: "100".to_string(), timestamps: true, ..Default::default() }));
     let mut logs = Vec::new();
     
@@ -720,25 +836,74 @@ async fn get_logs(state: State<'_, AppState>, container_id: String) -> Result<Ve
 }
 
 #[tauri::command]
-async fn exec_sql(state: State<'_, AppState>, container_id: String, database: String, username: String, sql: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
-    let exec = docker.create_exec(&container_id, CreateExecOptions { cmd: Some(vec!["psql", "-U", &username, "-d", &database, "-c", &sql]), attach_stdout: Some(true), attach_stderr: Some(true), ..Default::default() })
-        .await.map_err(|e| e.to_string())?;
-    
-    let start = docker.start_exec(&exec.id, None).await.map_err(|e| e.to_string())?;
-    let mut output = String::new();
-    
-    if let bollard::exec::StartExecResults::Attached { output: mut stream, .. } = start {
-        while let Some(msg) = stream.next().await {
-            if let Ok(log) = msg { output.push_str(&log.to_string()); }
+#[allow(clippy::too_many_arguments)]
+async fn exec_sql(
+    state: State<'_, AppState>,
+    library: State<'_, QueryLibraryState>,
+    running: State<'_, RunningQueriesState>,
+    container_id: String,
+    database: String,
+    username: String,
+    sql: String,
+    query_id: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    let docker = state.docker();
+    let query_id = match query_id.filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
         }
+        None => new_query_id(),
+    };
+    let timeout_secs = timeout_secs.unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS).max(1);
+    let container_name = docker
+        .inspect_container(&container_id, None)
+        .await
+        .ok()
+        .and_then(|c| c.name)
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_else(|| container_id.clone());
+    let target = DbTarget {
+        container_id: container_id.clone(),
+        container_name: container_name.clone(),
+        db_type: DatabaseType::PostgreSQL,
+        user: username.clone(),
+        password: String::new(),
+        database: database.clone(),
+    };
+
+    let cmd: Vec<String> = ["psql", "-U", &username, "-d", &database, "-c", &sql].iter().map(|s| s.to_string()).collect();
+    let env = query_tracking_env(&target.db_type, &query_id);
+    let result = run_tracked_query(&docker, &running, &target, &query_id, &sql, cmd, env, timeout_secs).await?;
+
+    let mut output = result.combined;
+    let success = result.outcome == QueryOutcome::Completed
+        && result.output.exit_code.map(|c| c == 0).unwrap_or_else(|| !output.contains("ERROR:"));
+    if let Some(message) = result.outcome.message(timeout_secs) {
+        output.push_str(&format!("\nERROR:  {}\n", message));
     }
+
+    // Guardar en el historial de consultas
+    library.record(QueryHistoryEntry {
+        container_id: container_id.clone(),
+        container_name,
+        db_type: DatabaseType::PostgreSQL.to_string(),
+        database: database.clone(),
+        statement: sql.clone(),
+        duration_ms: result.duration_ms,
+        row_count: if success { psql_row_count(&output) } else { None },
+        success,
+        error: (!success).then(|| output.trim().to_string()),
+        ..Default::default()
+    });
+
     Ok(output)
 }
 
 #[tauri::command]
 async fn backup_db(state: State<'_, AppState>, container_id: String, database: String, username: String) -> Result<String, String> {
-    let docker = state.docker.lock().await;
+    let docker = state.docker();
     let file = format!("/tmp/backup_{}_{}.sql", database, chrono::Local::now().timestamp());
     let exec = docker.create_exec(&container_id, CreateExecOptions { cmd: Some(vec!["pg_dump", "-U", &username, "-d", &database, "-f", &file]), attach_stdout: Some(true), attach_stderr: Some(true), ..Default::default() })
         .await.map_err(|e| e.to_string())?;
@@ -804,8 +969,7 @@ async fn reconnect_docker(state: State<'_, AppState>) -> Result<String, String>
                     println!("[Reconnect] Ping successful, replacing old connection");
                     
                     // Replace the old Docker client with the new one
-                    let mut docker = state.docker.lock().await;
-                    *docker = new_docker;
+                    state.replace_docker(new_docker);
                     
                     println!("[Reconnect] ✅ Docker reconnected successfully");
                     Ok("Docker reconnected successfully".to_string())
@@ -904,8 +1068,29 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .manage(AppState { docker: Mutex::new(docker) })
+        .plugin(tauri_plugin_notification::init())
+        .manage(AppState::new(docker))
         .manage(MigrationState { migrated: Mutex::new(Vec::new()) })
+        .manage(StatsState::new())
+        .manage(DatabaseMetricsState::default())
+        .manage(RunningQueriesState::default())
+        .manage(SqlSessionsState::default())
+        .manage(DockerEventsState { connected: std::sync::atomic::AtomicBool::new(false) })
+        .setup(|app| {
+            let data_dir = app_data_dir(app.handle());
+            app.manage(MetricsState::load(data_dir.join("metrics")));
+            app.manage(AlertsState::load(data_dir.join("alerts")));
+            app.manage(ExporterState::load(data_dir.join("exporter")));
+            app.manage(QueryLibraryState::load(data_dir.join("queries")));
+
+            spawn_docker_events_listener(app.handle().clone());
+            spawn_metrics_sampler(app.handle().clone());
+            spawn_alert_evaluator(app.handle().clone());
+            spawn_sql_session_reaper(app.handle().clone());
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move { restart_exporter(&handle).await });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             check_docker,
             reconnect_docker,
@@ -944,10 +1129,86 @@ pub fn run() {
             deploy_compose_file,
             list_compose_projects,
             stop_compose_project,
-            remove_compose_project
+            remove_compose_project,
+            get_container_details,
+            get_docker_events_status,
+            get_stats_history,
+            get_metrics_settings,
+            update_metrics_settings,
+            list_alert_rules,
+            save_alert_rule,
+            delete_alert_rule,
+            get_alert_history,
+            clear_alert_history,
+            get_database_metrics,
+            get_container_metrics,
+            get_exporter_settings,
+            update_exporter_settings,
+            list_sessions,
+            cancel_session_query,
+            terminate_session,
+            get_lock_graph,
+            get_top_queries,
+            reset_query_stats,
+            set_query_insights,
+            explain_query,
+            list_databases,
+            get_database_schema,
+            browse_table,
+            insert_table_row,
+            update_table_row,
+            delete_table_row,
+            scan_redis_keys,
+            get_redis_value,
+            edit_redis_key,
+            delete_redis_keys,
+            set_redis_ttl,
+            get_redis_biggest_keys,
+            list_mongo_collections,
+            find_mongo_documents,
+            insert_mongo_document,
+            replace_mongo_document,
+            delete_mongo_document,
+            list_mongo_indexes,
+            create_mongo_index,
+            drop_mongo_index,
+            run_mongo_aggregation,
+            search_query_history,
+            clear_query_history,
+            list_saved_queries,
+            save_query,
+            delete_saved_query,
+            render_saved_query,
+            export_saved_queries,
+            import_saved_queries,
+            cancel_query,
+            list_running_queries,
+            open_sql_session,
+            execute_in_session,
+            begin_session_transaction,
+            commit_session_transaction,
+            rollback_session_transaction,
+            get_sql_session,
+            list_sql_sessions,
+            close_sql_session,
+            split_sql_script,
+            run_sql_script,
+            export_data,
+            import_data,
+            transfer_data,
+            diff_schemas,
+            diff_data
         ])
-        .run(tauri::generate_context!())
-        .expect("error running app");
+        .build(tauri::generate_context!())
+        .expect("error building app")
+        .run(|app, event| {
+            // El bucket abierto de métricas se perdería al cerrar sin volcarlo
+            if let tauri::RunEvent::Exit = event {
+                if let Some(metrics) = app.try_state::<MetricsState>() {
+                    metrics.flush();
+                }
+            }
+        });
 }
 
 #[cfg(test)]
@@ -1644,6 +1905,1251 @@ mod tests {
         // Este test solo verifica que podemos consultar imágenes
         // (siempre retorna algo, aunque sea vacío)
     }
+
+    #[test]
+    fn test_mask_env_var() {
+        let secret = mask_env_var("POSTGRES_PASSWORD=supersecret");
+        assert!(secret.masked);
+        assert_eq!(secret.key, "POSTGRES_PASSWORD");
+        assert_ne!(secret.value, "supersecret");
+
+        let plain = mask_env_var("POSTGRES_DB=app=prod");
+        assert!(!plain.masked);
+        assert_eq!(plain.value, "app=prod");
+
+        // Un secreto vacío no se marca como enmascarado
+        assert!(!mask_env_var("MYSQL_ROOT_PASSWORD=").masked);
+    }
+
+    #[test]
+    fn test_detect_database_type() {
+        let mut labels = HashMap::new();
+        labels.insert("db_type".to_string(), "mariadb".to_string());
+        assert_eq!(detect_database_type(Some(&labels), Some("mysql:8.0")), Some(DatabaseType::MariaDB));
+        assert_eq!(detect_database_type(None, Some("docker.io/library/postgres:16")), Some(DatabaseType::PostgreSQL));
+        assert_eq!(detect_database_type(None, Some("bitnami/redis:7.2")), Some(DatabaseType::Redis));
+        assert_eq!(detect_database_type(None, Some("nginx:latest")), None);
+    }
+
+    #[tokio::test]
+    async fn test_lock_container_is_per_container() {
+        let docker = Docker::connect_with_http("http://localhost:2375", 1, bollard::API_DEFAULT_VERSION).unwrap();
+        let state = AppState::new(docker);
+
+        let _guard = state.lock_key("db-a").await;
+
+        // Otro contenedor no espera
+        let other = tokio::time::timeout(Duration::from_millis(100), state.lock_key("db-b")).await;
+        assert!(other.is_ok(), "Un contenedor distinto no debería bloquearse");
+
+        // El mismo contenedor sí espera
+        let same = tokio::time::timeout(Duration::from_millis(100), state.lock_key("db-a")).await;
+        assert!(same.is_err(), "El mismo contenedor debería quedar serializado");
+    }
+
+    #[test]
+    fn test_container_stats_from_sample() {
+        let cpu = |total: u64, system: u64| json!({
+            "cpu_usage": {"total_usage": total, "usage_in_usermode": 0, "usage_in_kernelmode": 0, "percpu_usage": null},
+            "system_cpu_usage": system,
+            "online_cpus": 2,
+            "throttling_data": {"periods": 0, "throttled_periods": 0, "throttled_time": 0}
+        });
+        let network = |rx: u64, tx: u64| json!({
+            "rx_bytes": rx, "tx_bytes": tx, "rx_packets": 0, "tx_packets": 0,
+            "rx_errors": 0, "tx_errors": 0, "rx_dropped": 0, "tx_dropped": 0
+        });
+        let sample: bollard::container::Stats = serde_json::from_value(json!({
+            "read": "2024-05-01T10:00:01Z",
+            "preread": "2024-05-01T10:00:00Z",
+            "num_procs": 0,
+            "pids_stats": {"current": 12, "limit": null},
+            "networks": {"eth0": network(1000, 2000), "eth1": network(500, 0)},
+            "memory_stats": {"usage": 268_435_456u64, "limit": 1_073_741_824u64},
+            "blkio_stats": {"io_service_bytes_recursive": [
+                {"major": 8, "minor": 0, "op": "Read", "value": 4096},
+                {"major": 8, "minor": 0, "op": "Write", "value": 8192},
+                {"major": 8, "minor": 16, "op": "read", "value": 1}
+            ]},
+            "cpu_stats": cpu(400_000_000, 20_000_000_000),
+            "precpu_stats": cpu(200_000_000, 18_000_000_000),
+            "storage_stats": {}
+        }))
+        .unwrap();
+
+        let stats = container_stats_from_sample("abc123", "postgres-app", &sample);
+        assert_eq!(stats.container_id, "abc123");
+        assert_eq!(stats.container_name, "postgres-app");
+        // (2e8 / 2e9) * 2 CPUs * 100
+        assert_eq!(stats.cpu_percentage, 20.0);
+        assert_eq!(stats.memory_percentage, 25.0);
+        assert_eq!((stats.network_rx_bytes, stats.network_tx_bytes), (1500, 2000));
+        assert_eq!((stats.block_read_bytes, stats.block_write_bytes), (4097, 8192));
+
+        // Sin delta de sistema (primera muestra) la CPU queda en 0
+        let mut first = sample.clone();
+        first.precpu_stats = first.cpu_stats.clone();
+        assert_eq!(container_stats_from_sample("abc123", "postgres-app", &first).cpu_percentage, 0.0);
+    }
+
+    #[test]
+    fn test_metrics_tier_downsampling() {
+        let mut tier = MetricsTier::new(60, 3_600);
+        for (t, cpu) in [(0, 10.0), (20, 20.0), (40, 30.0), (60, 50.0)] {
+            tier.push(MetricSample { timestamp: t, cpu_percentage: cpu, network_rx_bytes: t as f64, ..Default::default() });
+        }
+
+        let points = tier.range(0, 120);
+        assert_eq!(points.len(), 2);
+        // Gauges promediados dentro del bucket, contadores con el último valor
+        assert_eq!(points[0].cpu_percentage, 20.0);
+        assert_eq!(points[0].network_rx_bytes, 40.0);
+        assert_eq!(points[1].timestamp, 60);
+
+        // El bucket abierto se guarda en disco y sigue acumulando tras recargar
+        let mut reloaded: MetricsTier = serde_json::from_str(&serde_json::to_string(&tier).unwrap()).unwrap();
+        reloaded.push(MetricSample { timestamp: 80, cpu_percentage: 70.0, ..Default::default() });
+        assert_eq!(reloaded.range(60, 120)[0].cpu_percentage, 60.0);
+
+        // La retención descarta buckets antiguos
+        tier.push(MetricSample { timestamp: 7_200, ..Default::default() });
+        tier.push(MetricSample { timestamp: 7_260, ..Default::default() });
+        assert!(tier.samples.iter().all(|s| s.timestamp >= 7_200 - 3_600));
+    }
+
+    #[test]
+    fn test_metrics_series_tier_selection() {
+        let series = MetricsSeries::new("abc", "postgresql-app");
+        let now = 1_000_000;
+        assert_eq!(series.tier_for(now - 1_800, now).unwrap().resolution_secs, 10);
+        assert_eq!(series.tier_for(now - 7_200, now).unwrap().resolution_secs, 60);
+        assert_eq!(series.tier_for(now - 604_800, now).unwrap().resolution_secs, 600);
+        assert_eq!(series.tier_for(now - 10_000_000, now).unwrap().resolution_secs, 600);
+    }
+
+    #[test]
+    fn test_alert_engine_debounce_and_hysteresis() {
+        let rule = AlertRule {
+            id: "cpu".to_string(),
+            name: "High CPU".to_string(),
+            enabled: true,
+            container: None,
+            metric: AlertMetric::CpuPercentage,
+            threshold: 80.0,
+            for_minutes: 2,
+            notify: false,
+        };
+        let obs = |cpu: f64| AlertObservation {
+            container_id: "abc123".to_string(),
+            container_name: "postgresql-app".to_string(),
+            cpu_percentage: Some(cpu),
+            ..Default::default()
+        };
+        let mut engine = AlertEngine::default();
+        let rules = vec![rule];
+
+        // Aún no se cumple el "for 2 minutes"
+        assert!(engine.evaluate(&rules, &[obs(95.0)], 0).is_empty());
+        assert!(engine.evaluate(&rules, &[obs(90.0)], 60).is_empty());
+        let fired = engine.evaluate(&rules, &[obs(85.0)], 120);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].state, "firing");
+
+        // Dentro de la banda de histéresis sigue activa
+        assert!(engine.evaluate(&rules, &[obs(75.0)], 150).is_empty());
+        let resolved = engine.evaluate(&rules, &[obs(50.0)], 180);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].state, "resolved");
+    }
+
+    #[test]
+    fn test_alert_engine_restores_firing_alerts() {
+        let rules: Vec<AlertRule> = vec![serde_json::from_value(json!({"id": "exit", "name": "Exit", "metric": "container_exit"})).unwrap()];
+        let dead = |id: &str| AlertObservation { container_id: id.to_string(), container_name: id.to_string(), abnormal_exit: Some(137), ..Default::default() };
+
+        let mut engine = AlertEngine::default();
+        assert_eq!(engine.evaluate(&rules, &[dead("abc")], 0).len(), 1);
+        let firing = engine.firing();
+        assert_eq!(firing, vec![FiringAlert { rule_id: "exit".to_string(), container_id: "abc".to_string() }]);
+
+        // Tras reiniciar la app el contenedor sigue caído: no se repite la alerta
+        let mut restarted = AlertEngine::with_firing(&firing);
+        assert!(restarted.evaluate(&rules, &[dead("abc")], 10).is_empty());
+        assert_eq!(restarted.evaluate(&rules, &[dead("def")], 20).len(), 1);
+    }
+
+    #[test]
+    fn test_alert_rule_scope() {
+        let mut rule: AlertRule = serde_json::from_value(json!({
+            "name": "Exit", "metric": "container_exit", "container": "mysql-shop"
+        })).unwrap();
+        assert!(rule.enabled && rule.notify);
+        assert!(rule.applies_to("f00", "mysql-shop"));
+        assert!(!rule.applies_to("f00", "postgresql-app"));
+        rule.container = None;
+        assert!(rule.applies_to("f00", "postgresql-app"));
+    }
+
+    #[test]
+    fn test_db_target_from_config() {
+        let env = vec!["MYSQL_ROOT_PASSWORD=secret".to_string(), "MYSQL_DATABASE=shop".to_string()];
+        let target = db_target_from_config("id", "mysql-shop", DatabaseType::MySQL, &env, &[]);
+        assert_eq!((target.user.as_str(), target.password.as_str(), target.database.as_str()), ("root", "secret", "shop"));
+
+        let cmd: Vec<String> = ["redis-server", "--requirepass", "p@ss"].iter().map(|s| s.to_string()).collect();
+        let target = db_target_from_config("id", "redis-cache", DatabaseType::Redis, &[], &cmd);
+        assert_eq!(target.password, "p@ss");
+
+        let target = db_target_from_config("id", "postgresql-app", DatabaseType::PostgreSQL, &[], &[]);
+        assert_eq!((target.user.as_str(), target.database.as_str()), ("postgres", "postgres"));
+    }
+
+    #[test]
+    fn test_parse_database_metrics() {
+        let pg = parse_pg_metrics(r#"{"connections": 3, "max_connections": 100, "xact_commit": 90, "xact_rollback": 10, "blks_hit": 990, "blks_read": 10, "deadlocks": 0, "uptime": 12.5}"#).unwrap();
+        assert_eq!(pg.operations_total, Some(100));
+        assert_eq!(pg.cache_hit_ratio, Some(99.0));
+
+        let mysql = parse_mysql_metrics("Threads_connected\t4\nQuestions\t1200\nCom_select\t800\nmax_connections\t151\n");
+        assert_eq!(mysql.connections, Some(4));
+        assert_eq!(mysql.max_connections, Some(151));
+        assert_eq!(mysql.op_counters.get("select"), Some(&800));
+
+        let redis = parse_redis_metrics("# Stats\r\nkeyspace_hits:75\r\nkeyspace_misses:25\r\nused_memory:1024\r\n");
+        assert_eq!(redis.cache_hit_ratio, Some(75.0));
+        assert_eq!(redis.memory_used_bytes, Some(1024));
+
+        let mongo = parse_mongo_metrics("{\"uptime\": 5, \"connections\": 2, \"available\": 98, \"resident_mb\": 1, \"opcounters\": {\"query\": 3, \"insert\": 4}}").unwrap();
+        assert_eq!(mongo.max_connections, Some(100));
+        assert_eq!(mongo.operations_total, Some(7));
+    }
+
+    #[test]
+    fn test_render_open_metrics() {
+        assert_eq!(health_from_status("Up 3 minutes (healthy)").as_deref(), Some("healthy"));
+        assert_eq!(health_from_status("Up 5 seconds (health: starting)").as_deref(), Some("starting"));
+        assert_eq!(health_from_status("Exited (1) 2 hours ago"), None);
+
+        let entry = ExporterEntry {
+            container_id: "0123456789abcdef".to_string(),
+            container_name: "postgresql-app".to_string(),
+            database_name: "app".to_string(),
+            db_type: "postgresql".to_string(),
+            state: "running".to_string(),
+            health: Some("healthy".to_string()),
+            stats: Some(ContainerStats {
+                container_id: "0123456789abcdef".to_string(),
+                container_name: "postgresql-app".to_string(),
+                cpu_percentage: 12.5,
+                memory_usage: 1024,
+                memory_limit: 2048,
+                memory_percentage: 50.0,
+                network_rx_bytes: 10,
+                network_tx_bytes: 20,
+                block_read_bytes: 30,
+                block_write_bytes: 40,
+                timestamp: "2024-01-01T00:00:00Z".to_string(),
+            }),
+        };
+        let text = render_open_metrics(&[entry]);
+        let labels = r#"container_id="0123456789ab",container_name="postgresql-app",database_name="app",db_type="postgresql""#;
+        assert!(text.contains(&format!("docker_db_up{{{}}} 1", labels)));
+        assert!(text.contains(&format!("docker_db_cpu_percentage{{{}}} 12.5", labels)));
+        assert!(text.contains(&format!("docker_db_network_rx_bytes_total{{{}}} 10", labels)));
+        assert!(text.contains(&format!("docker_db_health{{{},docker_db_health=\"healthy\"}} 1", labels)));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_parse_sessions() {
+        let sessions = parse_sessions_json(
+            " coalesce \n----------\n [{\"id\":\"42\",\"user\":\"postgres\",\"database\":\"app\",\"state\":\"active\",\"duration_secs\":3.5,\"query\":\"SELECT pg_sleep(10)\"}, \
+             {\"id\":\"43\",\"user\":\"app\",\"database\":\"app\",\"state\":\"idle\",\"duration_secs\":null,\"query\":null}]\n",
+        )
+        .unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "42");
+        assert_eq!(sessions[0].duration_secs, Some(3.5));
+        assert_eq!(sessions[1].duration_secs, None);
+        assert!(parse_sessions_json("NULL\n").unwrap().is_empty());
+
+        let redis = parse_redis_client_list(
+            "id=5 addr=172.17.0.1:50312 name=worker age=120 idle=30 flags=N db=0 cmd=get user=default\n\
+             id=6 addr=127.0.0.1:40000 name= age=0 idle=0 flags=N db=0 cmd=client|list user=default\n",
+        );
+        assert_eq!(redis.len(), 1);
+        assert_eq!(redis[0].state.as_deref(), Some("idle"));
+        assert_eq!(redis[0].duration_secs, Some(30.0));
+
+        assert!(validate_session_id(&DatabaseType::PostgreSQL, "123").is_ok());
+        assert!(validate_session_id(&DatabaseType::MySQL, "1; DROP TABLE x").is_err());
+        assert!(validate_session_id(&DatabaseType::MongoDB, "shard01:1234").is_ok());
+    }
+
+    #[test]
+    fn test_build_blocking_tree() {
+        let edge = |waiting: &str, blocking: &str| LockWaitEdge {
+            waiting: waiting.to_string(),
+            blocking: blocking.to_string(),
+            lock_mode: Some("RowExclusiveLock".to_string()),
+            relation: Some("users".to_string()),
+            wait_secs: Some(1.0),
+        };
+        let sessions = vec![DbSession { id: "10".to_string(), query: Some("ALTER TABLE users ...".to_string()), ..Default::default() }];
+
+        // 10 bloquea a 20, que bloquea a 30
+        let graph = build_blocking_tree(&sessions, &[edge("20", "10"), edge("30", "20")]);
+        assert!(!graph.deadlock);
+        assert_eq!(graph.waiting_sessions, 2);
+        assert_eq!(graph.roots.len(), 1);
+        assert_eq!(graph.roots[0].session.query.as_deref(), Some("ALTER TABLE users ..."));
+        assert_eq!(graph.roots[0].blocked[0].session.id, "20");
+        assert_eq!(graph.roots[0].blocked[0].blocked[0].session.id, "30");
+
+        let cycle = build_blocking_tree(&[], &[edge("1", "2"), edge("2", "1")]);
+        assert!(cycle.deadlock);
+        assert_eq!(cycle.roots.len(), 1);
+        assert_eq!(cycle.roots[0].session.id, "1");
+        assert_eq!(cycle.roots[0].blocked[0].session.id, "2");
+        assert!(cycle.roots[0].blocked[0].blocked.is_empty());
+
+        // 30 espera a 10 y a 20: su subárbol se coloca una sola vez
+        let diamond = build_blocking_tree(&[], &[edge("30", "10"), edge("30", "20"), edge("40", "30")]);
+        assert_eq!(diamond.roots.len(), 2);
+        assert_eq!(diamond.roots[0].blocked[0].blocked[0].session.id, "40");
+        assert!(diamond.roots[1].blocked.is_empty());
+
+        let edges = parse_lock_waits_json(
+            "[{\"waiting\":\"20\",\"blocking\":\"10\",\"lock_mode\":\"RowExclusiveLock\",\"relation\":\"users\",\"wait_secs\":1.5}, \
+             {\"waiting\":\"30\",\"blocking\":\"20\",\"lock_mode\":null,\"relation\":\"transactionid\",\"wait_secs\":0.5}]\n",
+        )
+        .unwrap();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(build_blocking_tree(&[], &edges).waiting_sessions, 2);
+        assert!(parse_lock_waits_json("NULL\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_insights_cmd() {
+        let pg = query_insights_cmd(&DatabaseType::PostgreSQL, &[], true);
+        assert_eq!(pg, vec!["postgres", "-c", PG_STAT_STATEMENTS_PRELOAD, "-c", PG_STAT_STATEMENTS_TRACK]);
+        // Activar dos veces no duplica argumentos y desactivar conserva el resto
+        assert_eq!(query_insights_cmd(&DatabaseType::PostgreSQL, &pg, true), pg);
+        let custom: Vec<String> = ["postgres", "-c", "max_connections=200"].iter().map(|s| s.to_string()).collect();
+        let enabled = query_insights_cmd(&DatabaseType::PostgreSQL, &custom, true);
+        assert_eq!(query_insights_cmd(&DatabaseType::PostgreSQL, &enabled, false), custom);
+
+        assert_eq!(query_insights_cmd(&DatabaseType::MariaDB, &[], true), vec![MARIADB_PERFORMANCE_SCHEMA]);
+        assert!(query_insights_cmd(&DatabaseType::MariaDB, &[MARIADB_PERFORMANCE_SCHEMA.to_string()], false).is_empty());
+    }
+
+    #[test]
+    fn test_top_query_stats_order() {
+        let stat = |query: &str, calls: u64, total: f64| QueryStat {
+            query: query.to_string(),
+            calls,
+            total_time_ms: total,
+            mean_time_ms: total / calls as f64,
+            ..Default::default()
+        };
+        let stats = vec![stat("a", 100, 50.0), stat("b", 1, 80.0), stat("c", 10, 10.0)];
+        let top = top_query_stats(stats.clone(), QueryStatsOrder::TotalTime, 2);
+        assert_eq!(top.iter().map(|s| s.query.as_str()).collect::<Vec<_>>(), vec!["b", "a"]);
+        let top = top_query_stats(stats, QueryStatsOrder::Calls, 10);
+        assert_eq!(top[0].query, "a");
+
+        let parsed = parse_query_stats_json(
+            "[{\"query\":\"SELECT $1\",\"database\":\"app\",\"user\":\"postgres\",\"calls\":3,\"total_time_ms\":1.5,\"mean_time_ms\":0.5,\"rows\":3}, \
+             {\"query\":\"UPDATE t SET a = $1\",\"database\":\"app\",\"user\":null,\"calls\":1,\"total_time_ms\":9,\"mean_time_ms\":9,\"rows\":0}]\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].total_time_ms, 9.0);
+        assert!(parse_query_stats_json("NULL\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_pg_plan_hotspots() {
+        let output = r#"[{"Plan": {"Node Type": "Hash Join", "Total Cost": 500.0, "Plan Rows": 10, "Actual Rows": 5000, "Actual Total Time": 12.5, "Actual Loops": 1,
+            "Plans": [{"Node Type": "Seq Scan", "Relation Name": "orders", "Schema": "public", "Plan Rows": 50000, "Actual Rows": 50000, "Actual Loops": 1, "Shared Hit Blocks": 10, "Shared Read Blocks": 90, "Filter": "(status = 'open')"},
+                      {"Node Type": "Index Scan", "Relation Name": "users", "Index Name": "users_pkey", "Plan Rows": 1, "Actual Rows": 1, "Actual Loops": 1}]},
+            "Planning Time": 0.2, "Execution Time": 13.1}]"#;
+        let plan = parse_pg_plan(output, true).unwrap();
+        assert_eq!(plan.execution_time_ms, Some(13.1));
+        assert_eq!(plan.hotspot_count, 2);
+        assert!(plan.root.hotspots[0].starts_with("Row estimate off by 500x"));
+        let scan = &plan.root.children[0];
+        assert_eq!(scan.relation.as_deref(), Some("public.orders"));
+        assert_eq!(scan.shared_read_blocks, Some(90));
+        assert!(scan.hotspots[0].contains("Full scan of public.orders"));
+        assert!(plan.root.children[1].hotspots.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mysql_plan() {
+        let output = r#"{"query_block": {"select_id": 1, "cost_info": {"query_cost": "2051.25"},
+            "ordering_operation": {"using_filesort": true, "nested_loop": [
+                {"table": {"table_name": "orders", "access_type": "ALL", "rows_examined_per_scan": 20000, "attached_condition": "(`orders`.`status` = 'open')"}},
+                {"table": {"table_name": "users", "access_type": "eq_ref", "key": "PRIMARY", "rows_examined_per_scan": 1}}]}}}"#;
+        let plan = parse_mysql_plan(output, &DatabaseType::MySQL, false).unwrap();
+        assert_eq!(plan.root.total_cost, Some(2051.25));
+        let sort = &plan.root.children[0];
+        assert_eq!(sort.node_type, "Sort");
+        assert_eq!(sort.hotspots, vec!["Using filesort"]);
+        let tables = &sort.children[0].children;
+        assert_eq!(tables[0].node_type, "Full Table Scan");
+        assert_eq!(tables[1].index.as_deref(), Some("PRIMARY"));
+        assert_eq!(plan.hotspot_count, 2);
+    }
+
+    #[test]
+    fn test_parse_mysql_analyze_tree() {
+        let output = "-> Nested loop inner join  (cost=9051 rows=20000) (actual time=0.1..25.5 rows=20000 loops=1)
+    -> Filter: (o.`status` = 'open')  (cost=2051 rows=2000) (actual time=0.05..9.8 rows=20000 loops=1)
+        -> Table scan on o  (cost=2051 rows=20000) (actual time=0.04..8.1 rows=20000 loops=1)
+    -> Single-row index lookup on u using PRIMARY (id=o.user_id)  (cost=0.25 rows=1) (actual time=0.001..0.001 rows=1 loops=20000)
+";
+        let plan = parse_mysql_analyze_tree(output).unwrap();
+        assert!(plan.analyzed);
+        assert_eq!(plan.execution_time_ms, Some(25.5));
+        assert_eq!(plan.root.children.len(), 2);
+        let filter = &plan.root.children[0];
+        assert_eq!(filter.node_type, "Filter");
+        assert_eq!(filter.condition.as_deref(), Some("(o.`status` = 'open')"));
+        assert!(filter.hotspots[0].starts_with("Row estimate off by 10x"));
+        let scan = &filter.children[0];
+        assert_eq!(scan.node_type, "Full Table Scan");
+        assert_eq!(scan.relation.as_deref(), Some("o"));
+        let lookup = &plan.root.children[1];
+        assert_eq!(lookup.index.as_deref(), Some("PRIMARY"));
+        assert_eq!(lookup.loops, Some(20000.0));
+        assert_eq!(lookup.actual_time_ms, Some(20.0));
+        assert_eq!(plan.hotspot_count, 2);
+
+        assert_eq!(parse_server_version("8.0.35-log"), Some((8, 0, 35)));
+        assert!(parse_server_version("5.7.44").unwrap() < (8, 0, 18));
+    }
+
+    #[test]
+    fn test_parse_pg_schema() {
+        let output = r#"{"schemas": ["app", "public"], "tables": [{"schema": "public", "name": "orders", "kind": "table", "row_estimate": 120, "size_bytes": 8192,
+            "columns": [{"name": "id", "data_type": "integer", "nullable": false, "default": "nextval('orders_id_seq'::regclass)", "ordinal": 1},
+                        {"name": "user_id", "data_type": "integer", "nullable": true, "default": null, "ordinal": 2}],
+            "indexes": [{"name": "orders_pkey", "columns": ["id"], "unique": true, "primary": true, "definition": "CREATE UNIQUE INDEX orders_pkey ON public.orders USING btree (id)"}],
+            "foreign_keys": [{"name": "orders_user_id_fkey", "columns": ["user_id"], "referenced_schema": "public", "referenced_table": "users", "referenced_columns": ["id"], "on_update": "a", "on_delete": "c"}]}]}"#;
+        let schema = parse_pg_schema("shop", output).unwrap();
+        assert_eq!(schema.schemas.len(), 2);
+        assert!(schema.schemas[0].tables.is_empty());
+        let orders = &schema.schemas[1].tables[0];
+        assert_eq!(orders.primary_key, vec!["id"]);
+        assert!(!orders.columns[0].nullable);
+        assert_eq!(orders.foreign_keys[0].on_delete.as_deref(), Some("CASCADE"));
+    }
+
+    #[test]
+    fn test_parse_mysql_schema() {
+        let output = [
+            r#"[{"name": "orders", "kind": "table", "row_estimate": 10, "size_bytes": 32768}]"#,
+            r#"[{"table": "orders", "name": "user_id", "data_type": "int", "nullable": "YES", "default": null, "ordinal": 2}, {"table": "orders", "name": "id", "data_type": "int", "nullable": "NO", "default": null, "ordinal": 1}]"#,
+            r#"[{"table": "orders", "name": "PRIMARY", "column": "id", "seq": 1, "non_unique": 0, "method": "BTREE"}, {"table": "orders", "name": "idx_user", "column": "user_id", "seq": 1, "non_unique": 1, "method": "BTREE"}]"#,
+            "NULL",
+        ]
+        .join("\n");
+        let schema = parse_mysql_schema("shop", &DatabaseType::MySQL, &output).unwrap();
+        let orders = &schema.schemas[0].tables[0];
+        assert_eq!(orders.schema, "shop");
+        assert_eq!(orders.columns[0].name, "id");
+        assert!(orders.columns[1].nullable);
+        assert_eq!(orders.primary_key, vec!["id"]);
+        assert!(orders.indexes.iter().any(|i| i.name == "idx_user" && !i.unique));
+        assert!(orders.foreign_keys.is_empty());
+
+        assert_eq!(quote_sql_literal("it's", &DatabaseType::PostgreSQL), "'it''s'");
+        assert_eq!(quote_sql_literal("a\\b", &DatabaseType::MySQL), "'a\\\\b'");
+    }
+
+    fn test_table_meta(db_type: &DatabaseType) -> TableMeta {
+        TableMeta {
+            qualified_name: format!("{}.{}", quote_ident("public", db_type), quote_ident("orders", db_type)),
+            columns: vec![
+                TableColumn { name: "id".to_string(), data_type: "integer".to_string() },
+                TableColumn { name: "status".to_string(), data_type: "text".to_string() },
+                TableColumn { name: "created_at".to_string(), data_type: "timestamp with time zone".to_string() },
+            ],
+            primary_key: vec!["id".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_build_table_select() {
+        let meta = test_table_meta(&DatabaseType::PostgreSQL);
+        let request: TablePageRequest = serde_json::from_value(json!({
+            "table": "orders",
+            "filters": [{"column": "status", "op": "ilike", "value": "%open%"}, {"column": "id", "op": "gt", "value": 10}],
+            "sort": [{"column": "created_at", "descending": true}],
+            "after": ["2024-01-01T00:00:00Z", 42]
+        })).unwrap();
+        let (sql, order) = build_table_select(&DatabaseType::PostgreSQL, &meta, &request, 50).unwrap();
+        assert_eq!(
+            sql.sql,
+            "SELECT * FROM \"public\".\"orders\" WHERE \"status\"::text ILIKE $1::text AND \"id\" > CAST($2::text AS integer) \
+             AND (\"created_at\", \"id\") < (CAST($3::text AS timestamp with time zone), CAST($4::text AS integer)) \
+             ORDER BY \"created_at\" DESC, \"id\" DESC LIMIT 51"
+        );
+        assert_eq!(sql.params, vec![Some("%open%".to_string()), Some("10".to_string()), Some("2024-01-01T00:00:00Z".to_string()), Some("42".to_string())]);
+        assert_eq!(order, vec!["created_at", "id"]);
+
+        // Identificadores desconocidos no llegan al SQL
+        let bad: TablePageRequest = serde_json::from_value(json!({"table": "orders", "sort": [{"column": "id; DROP TABLE orders"}]})).unwrap();
+        assert!(build_table_select(&DatabaseType::PostgreSQL, &meta, &bad, 50).is_err());
+    }
+
+    #[test]
+    fn test_build_row_mutation() {
+        let meta = test_table_meta(&DatabaseType::MySQL);
+        let key = json!({"id": 7}).as_object().unwrap().clone();
+        let changes = json!({"status": "it's done"}).as_object().unwrap().clone();
+        let sql = build_row_mutation(&DatabaseType::MySQL, &meta, &RowMutation::Update(&key, &changes)).unwrap();
+        assert_eq!(sql.sql, "UPDATE `public`.`orders` SET `status` = ? WHERE `id` = ?");
+        assert_eq!(sql.params, vec![Some("it's done".to_string()), Some("7".to_string())]);
+        let defaults = serde_json::Map::new();
+        let sql = build_row_mutation(&DatabaseType::MySQL, &meta, &RowMutation::Insert(&defaults)).unwrap();
+        assert_eq!(sql.sql, "INSERT INTO `public`.`orders` () VALUES ()");
+
+        let meta = test_table_meta(&DatabaseType::PostgreSQL);
+        let sql = build_row_mutation(&DatabaseType::PostgreSQL, &meta, &RowMutation::Insert(&defaults)).unwrap();
+        assert!(sql.sql.starts_with("WITH changed AS (INSERT INTO \"public\".\"orders\" DEFAULT VALUES RETURNING *)"));
+        let sql = build_row_mutation(&DatabaseType::PostgreSQL, &meta, &RowMutation::Delete(&key)).unwrap();
+        assert_eq!(sql.sql, "WITH changed AS (DELETE FROM \"public\".\"orders\" WHERE \"id\" = CAST($1::text AS integer) RETURNING *) SELECT row_to_json(changed)::text FROM changed");
+
+        let no_key = serde_json::Map::new();
+        assert!(build_row_mutation(&DatabaseType::PostgreSQL, &meta, &RowMutation::Delete(&no_key)).is_err());
+    }
+
+    #[test]
+    fn test_parse_redis_replies() {
+        let page = parse_redis_scan_reply(r#"{"cursor":"17","keys":[{"key":"session:1","type":"hash","pttl":-1,"length":3,"memory":96},{"key":"cache:a","type":"string","pttl":4500,"length":12}]}"#).unwrap();
+        assert!(!page.done);
+        assert_eq!(page.keys.len(), 2);
+        assert_eq!(page.keys[0].ttl_ms, None);
+        assert_eq!(page.keys[1].ttl_ms, Some(4500));
+        assert_eq!(page.keys[1].memory_bytes, None);
+
+        // Tabla vacía de Lua => {}
+        let empty = parse_redis_scan_reply(r#"{"cursor":"0","keys":{}}"#).unwrap();
+        assert!(empty.done && empty.keys.is_empty());
+
+        let value = parse_redis_value_reply("big", r#"{"type":"string","pttl":-1,"total":2000000,"value":"abc"}"#).unwrap();
+        assert!(value.truncated);
+        let list = parse_redis_value_reply("queue", r#"{"type":"list","pttl":-1,"total":250,"value":["a","b"],"next":"100"}"#).unwrap();
+        assert_eq!(list.next_cursor.as_deref(), Some("100"));
+        assert_eq!(list.value, json!(["a", "b"]));
+        assert!(parse_redis_value_reply("missing", r#"{"type":"none","pttl":-2}"#).is_err());
+    }
+
+    #[test]
+    fn test_redis_edit_args() {
+        let edit: RedisEdit = serde_json::from_value(json!({"op": "zset_add", "member": "alice", "score": 1.5})).unwrap();
+        assert_eq!(redis_edit_args("board", &edit).unwrap(), vec!["ZADD", "board", "1.5", "alice"]);
+        let edit: RedisEdit = serde_json::from_value(json!({"op": "set_string", "value": "hello world"})).unwrap();
+        assert_eq!(redis_edit_args("greeting", &edit).unwrap(), vec!["SET", "greeting", "hello world", "KEEPTTL"]);
+        let edit: RedisEdit = serde_json::from_value(json!({"op": "stream_add", "fields": [["event", "login"]]})).unwrap();
+        assert_eq!(redis_edit_args("events", &edit).unwrap(), vec!["XADD", "events", "*", "event", "login"]);
+        assert!(redis_edit_args("events", &RedisEdit::StreamAdd { fields: vec![] }).is_err());
+
+        let key = |name: &str, memory: u64| RedisKeyInfo { key: name.to_string(), key_type: "string".to_string(), ttl_ms: None, length: None, memory_bytes: Some(memory) };
+        let mut biggest = vec![key("a", 10)];
+        keep_biggest_keys(&mut biggest, vec![key("b", 50), key("c", 5), key("d", 30)], 2);
+        assert_eq!(biggest.iter().map(|k| k.key.as_str()).collect::<Vec<_>>(), vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_mongo_find_script() {
+        let request = MongoFindRequest {
+            database: "shop".to_string(),
+            collection: "orders".to_string(),
+            filter: Some(r#"{"status": "it's \"open\""}"#.to_string()),
+            sort: Some(r#"{"created": -1}"#.to_string()),
+            skip: Some(20),
+            ..Default::default()
+        };
+        let script = mongo_find_script(&request, 10).unwrap();
+        assert!(script.contains(r#"db.getSiblingDB("shop")"#));
+        assert!(script.contains(r#"ejson.parse("{\"status\": \"it's \\\"open\\\"\"}")"#));
+        assert!(script.contains(".skip(20).limit(11)"));
+        // Los documentos editables salen en EJSON canónico para conservar los tipos numéricos
+        assert!(script.contains("print(ejson.canonical({documents: docs, total: total}));"));
+
+        let bad = MongoFindRequest { filter: Some("{status: 'open'}".to_string()), ..request.clone() };
+        assert!(mongo_find_script(&bad, 10).is_err());
+        assert!(ejson_literal(Some(r#"{"$match": {}}"#), "pipeline", true).is_err());
+        assert_eq!(ejson_literal(None, "pipeline", true).unwrap(), r#""[]""#);
+    }
+
+    #[test]
+    fn test_parse_mongo_document_page() {
+        let output = "Warning: something\n{\"documents\":[{\"_id\":{\"$oid\":\"65a\"}},{\"_id\":2},{\"_id\":3}],\"total\":{\"$numberLong\":\"250000\"}}\n";
+        let page = parse_mongo_document_page(output, 2).unwrap();
+        assert_eq!(page.documents.len(), 2);
+        assert!(page.has_more);
+        assert_eq!(page.total, 250_000);
+        assert!(!page.total_exact);
+
+        let canonical = "{\"documents\":[{\"_id\":{\"$numberInt\":\"1\"},\"n\":{\"$numberLong\":\"7\"}}],\"total\":{\"$numberInt\":\"1\"}}";
+        let page = parse_mongo_document_page(canonical, 10).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.documents[0]["n"], json!({"$numberLong": "7"}));
+    }
+
+    #[test]
+    fn test_psql_row_count() {
+        assert_eq!(psql_row_count(" id \n----\n  1\n  2\n(2 rows)\n\n"), Some(2));
+        assert_eq!(psql_row_count("INSERT 0 5\n"), Some(5));
+        assert_eq!(psql_row_count("UPDATE 1"), Some(1));
+        assert_eq!(psql_row_count("CREATE TABLE\n"), None);
+    }
+
+    #[test]
+    fn test_query_history_and_templates() {
+        let entry = |statement: &str, container: &str, success: bool| QueryHistoryEntry {
+            container_name: container.to_string(),
+            container_id: format!("{}-id", container),
+            statement: statement.to_string(),
+            success,
+            ..Default::default()
+        };
+        let history: std::collections::VecDeque<_> =
+            vec![entry("SELECT 1", "pg", true), entry("select * from users", "pg", false), entry("SELECT * FROM users", "mysql", true)].into();
+        let found = filter_query_history(&history, &QueryHistoryFilter { text: Some("USERS".to_string()), ..Default::default() });
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].container_name, "mysql");
+        let found = filter_query_history(&history, &QueryHistoryFilter { container: Some("pg".to_string()), success: Some(true), ..Default::default() });
+        assert_eq!(found.len(), 1);
+
+        let query = SavedQuery {
+            statement: "SELECT * FROM {{table}} LIMIT {{limit}}".to_string(),
+            parameters: vec![
+                QueryParameter { name: "table".to_string(), ..Default::default() },
+                QueryParameter { name: "limit".to_string(), default: Some("10".to_string()), ..Default::default() },
+            ],
+            db_type: Some("postgresql".to_string()),
+            ..Default::default()
+        };
+        let values: HashMap<String, String> = [("table".to_string(), "orders".to_string())].into_iter().collect();
+        assert_eq!(render_query_template(&query, &values).unwrap(), "SELECT * FROM orders LIMIT 10");
+        assert!(render_query_template(&query, &HashMap::new()).is_err());
+        assert!(query.in_scope(Some("any"), Some("PostgreSQL")));
+        assert!(!query.in_scope(None, Some("mysql")));
+    }
+
+    #[test]
+    fn test_query_library_persistence() {
+        let dir = std::env::temp_dir().join(format!("ddm-queries-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let library = QueryLibraryState::load(dir.clone());
+        library.record(QueryHistoryEntry { statement: "SELECT 1".to_string(), ..Default::default() });
+        library.record(QueryHistoryEntry { statement: "SELECT 2".to_string(), ..Default::default() });
+        let ids: Vec<String> = library.history().iter().map(|e| e.id.clone()).collect();
+        assert_ne!(ids[0], ids[1]);
+
+        // Cada entrada se añade como una línea; una línea cortada al final se ignora
+        let mut file = fs::OpenOptions::new().append(true).open(dir.join("history.jsonl")).unwrap();
+        std::io::Write::write_all(&mut file, b"{\"statement\": \"SEL").unwrap();
+        let reloaded = QueryLibraryState::load(dir.clone());
+        assert_eq!(reloaded.history().len(), 2);
+        assert_eq!(reloaded.history()[1].statement, "SELECT 2");
+        reloaded.record(QueryHistoryEntry { statement: "SELECT 3".to_string(), ..Default::default() });
+        assert_eq!(QueryLibraryState::load(dir.clone()).history().len(), 3);
+
+        let removed = reloaded.update_history(|history| {
+            let before = history.len();
+            history.clear();
+            before
+        });
+        assert_eq!(removed, Ok(3));
+        assert!(QueryLibraryState::load(dir.clone()).history().is_empty());
+
+        let saved = reloaded.update_saved(|saved| {
+            saved.push(SavedQuery { id: unique_id("sq"), name: "a".to_string(), ..Default::default() });
+            Ok(saved.len())
+        });
+        assert_eq!(saved, Ok(1));
+        assert!(reloaded.update_saved(|_| Err::<(), _>("rejected".to_string())).is_err());
+        assert_eq!(QueryLibraryState::load(dir.clone()).saved().len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_query_tracking() {
+        let env = query_tracking_env(&DatabaseType::PostgreSQL, "q2");
+        assert_eq!(env, vec!["DDM_QUERY_ID=q2".to_string(), "PGAPPNAME=ddm-q2".to_string()]);
+        assert_eq!(query_tracking_env(&DatabaseType::MySQL, "q1"), vec!["DDM_QUERY_ID=q1".to_string()]);
+
+        assert!(validate_query_id("editor-1_a").is_ok());
+        assert!(validate_query_id("x'; DROP").is_err());
+        assert!(validate_query_id("").is_err());
+    }
+
+    #[test]
+    fn test_next_transaction_state() {
+        let all = usize::MAX;
+        let pg = DatabaseType::PostgreSQL;
+        let state = next_transaction_state(&pg, TransactionState::Idle, "begin; update t set a = 1", all);
+        assert_eq!(state, TransactionState::InTransaction);
+        let state = next_transaction_state(&pg, state, "select 1/0", 0);
+        assert_eq!(state, TransactionState::Failed);
+        assert_eq!(next_transaction_state(&pg, state, "ROLLBACK", all), TransactionState::Idle);
+        assert_eq!(
+            next_transaction_state(&pg, TransactionState::InTransaction, "ROLLBACK TO SAVEPOINT a", all),
+            TransactionState::InTransaction
+        );
+        assert_eq!(next_transaction_state(&pg, TransactionState::Idle, "SELECT 1/0", 0), TransactionState::Idle);
+        // Se detiene en la sentencia que falla: el COMMIT no llegó a ejecutarse
+        assert_eq!(next_transaction_state(&pg, TransactionState::Idle, "BEGIN; SELECT 1/0; COMMIT", 1), TransactionState::Failed);
+        // Ni el cuerpo de un DO ni un literal cuentan como sentencias de transacción
+        assert_eq!(
+            next_transaction_state(&pg, TransactionState::InTransaction, "DO $$ BEGIN PERFORM 1; END $$", all),
+            TransactionState::InTransaction
+        );
+        assert_eq!(next_transaction_state(&pg, TransactionState::InTransaction, "SELECT 'a; commit'", all), TransactionState::InTransaction);
+        assert_eq!(next_transaction_state(&pg, TransactionState::InTransaction, "/* fin */ COMMIT", all), TransactionState::Idle);
+
+        let mysql = DatabaseType::MySQL;
+        let state = next_transaction_state(&mysql, TransactionState::Idle, "-- load\nSTART TRANSACTION;\nINSERT INTO t VALUES (1);", all);
+        assert_eq!(state, TransactionState::InTransaction);
+        assert_eq!(next_transaction_state(&mysql, state, "INSERT INTO t VALUES ('x')", 0), TransactionState::InTransaction);
+        assert_eq!(next_transaction_state(&mysql, state, "INSERT INTO t VALUES ('x'); COMMIT", 0), TransactionState::InTransaction);
+        assert_eq!(next_transaction_state(&mysql, state, "CREATE TABLE u (id int)", all), TransactionState::Idle);
+    }
+
+    #[test]
+    fn test_split_sql_statements_postgres() {
+        let script = "-- schema\nCREATE TABLE t (a text);\n\nINSERT INTO t VALUES ('a;b'), (E'it\\'s;'), ('x''y;');\n\
+            CREATE FUNCTION f() RETURNS int AS $body$\nBEGIN\n  RETURN 1; -- ;\nEND;\n$body$ LANGUAGE plpgsql;\n\
+            /* multi; /* nested; */ line */ SELECT $1::int, 2;\nDO $$ BEGIN PERFORM 1; END $$\n-- trailing comment only;\n";
+        let statements = split_sql_statements(script, &DatabaseType::PostgreSQL);
+        let sql: Vec<&str> = statements.iter().map(|s| s.sql.as_str()).collect();
+        assert_eq!(sql.len(), 5, "{:?}", sql);
+        assert_eq!(sql[0], "-- schema\nCREATE TABLE t (a text)");
+        assert_eq!(statements[0].line, 2);
+        assert_eq!(sql[1], "INSERT INTO t VALUES ('a;b'), (E'it\\'s;'), ('x''y;')");
+        assert!(sql[2].starts_with("CREATE FUNCTION") && sql[2].ends_with("LANGUAGE plpgsql"));
+        assert_eq!(statements[2].line, 5);
+        assert!(sql[3].ends_with("SELECT $1::int, 2"));
+        assert_eq!(sql[4], "DO $$ BEGIN PERFORM 1; END $$\n-- trailing comment only;");
+    }
+
+    #[test]
+    fn test_split_sql_statements_mysql() {
+        let script = "# setup\nSET @a = 'x\\';y';\nDELIMITER //\nCREATE PROCEDURE p()\nBEGIN\n  SELECT 1; SELECT `a;b`;\nEND//\nDELIMITER ;\n\
+            /*!40101 SET NAMES utf8 */;\nSELECT 1 --not a comment\n;";
+        let statements = split_sql_statements(script, &DatabaseType::MySQL);
+        let sql: Vec<&str> = statements.iter().map(|s| s.sql.as_str()).collect();
+        assert_eq!(sql.len(), 4, "{:?}", sql);
+        assert_eq!(sql[0], "# setup\nSET @a = 'x\\';y'");
+        assert_eq!(sql[1], "CREATE PROCEDURE p()\nBEGIN\n  SELECT 1; SELECT `a;b`;\nEND");
+        assert_eq!(statements[1].line, 4);
+        assert_eq!(sql[2], "/*!40101 SET NAMES utf8 */");
+        assert_eq!(sql[3], "SELECT 1 --not a comment");
+
+        // Líneas que empiezan con caracteres multibyte no rompen la detección de DELIMITER
+        let statements = split_sql_statements("-- Creación de tablas\nÁrbol;\nñandú;", &DatabaseType::MySQL);
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1].sql, "ñandú");
+    }
+
+    fn test_export_request(format: ExportFormat, path: &std::path::Path) -> ExportRequest {
+        serde_json::from_value(json!({
+            "source": {"type": "table", "table": "orders"},
+            "format": format,
+            "path": path.to_string_lossy(),
+            "csv": {"delimiter": ";"},
+            "rows_per_insert": 2
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_unique_column_names() {
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(unique_column_names(names(&["id", "name", "id", "id_2", "id"])), names(&["id", "name", "id_3", "id_2", "id_4"]));
+        assert_eq!(typed_export_value(json!("{\"a\": [1]}"), ExportColumnKind::Json), json!({"a": [1]}));
+        assert_eq!(typed_export_value(json!("12345678901234567890.123"), ExportColumnKind::Text), json!("12345678901234567890.123"));
+    }
+
+    #[test]
+    fn test_export_sinks() {
+        let dir = std::env::temp_dir().join(format!("ddm-export-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let columns = vec![
+            ExportColumn { name: "id".to_string(), kind: ExportColumnKind::Integer },
+            ExportColumn { name: "note".to_string(), kind: ExportColumnKind::Text },
+        ];
+        let rows = vec![
+            vec![typed_export_value(json!("1"), ExportColumnKind::Integer), json!("a;b")],
+            vec![json!(2), serde_json::Value::Null],
+            vec![json!(3), json!("it's")],
+        ];
+        let write = |format: ExportFormat, file: &str| {
+            let path = dir.join(file);
+            let mut sink = create_export_sink(&test_export_request(format, &path), &columns, &DatabaseType::PostgreSQL, "orders").unwrap();
+            for row in rows.clone() {
+                sink.write_row(row).unwrap();
+            }
+            sink.finish().unwrap();
+            path
+        };
+
+        let csv = fs::read_to_string(write(ExportFormat::Csv, "out.csv")).unwrap();
+        assert_eq!(csv, "id;note\n1;\"a;b\"\n2;\n3;it's\n");
+        let jsonl = fs::read_to_string(write(ExportFormat::Jsonl, "out.jsonl")).unwrap();
+        assert_eq!(jsonl.lines().next().unwrap(), r#"{"id":1,"note":"a;b"}"#);
+        let sql = fs::read_to_string(write(ExportFormat::Sql, "out.sql")).unwrap();
+        assert_eq!(
+            sql,
+            "INSERT INTO \"orders\" (\"id\", \"note\") VALUES\n(1, 'a;b'),\n(2, NULL);\nINSERT INTO \"orders\" (\"id\", \"note\") VALUES\n(3, 'it''s');\n"
+        );
+        let parquet = fs::read(write(ExportFormat::Parquet, "out.parquet")).unwrap();
+        assert_eq!(&parquet[..4], b"PAR1");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_type_inference() {
+        let types = |values: &[serde_json::Value]| values.iter().filter_map(infer_import_type).fold(None, |t, v| Some(widen_import_type(t, v)));
+        assert_eq!(types(&[json!("1"), json!("-20"), serde_json::Value::Null]), Some(ImportColumnType::Integer));
+        assert_eq!(types(&[json!("1"), json!("2.5")]), Some(ImportColumnType::Float));
+        assert_eq!(types(&[json!("00123"), json!("42")]), Some(ImportColumnType::Text));
+        assert_eq!(types(&[json!("2024-01-31"), json!("2024-02-01 10:30:00")]), Some(ImportColumnType::Timestamp));
+        assert_eq!(types(&[json!("TRUE"), json!(false)]), Some(ImportColumnType::Boolean));
+        assert_eq!(types(&[json!({"a": 1}), json!([1])]), Some(ImportColumnType::Json));
+        assert_eq!(types(&[serde_json::Value::Null]), None);
+
+        assert_eq!(ImportColumnType::from_column_type("int(11) unsigned"), ImportColumnType::Integer);
+        assert_eq!(ImportColumnType::from_column_type("tinyint(1)"), ImportColumnType::Boolean);
+        assert_eq!(ImportColumnType::from_column_type("interval"), ImportColumnType::Text);
+        assert_eq!(ImportColumnType::from_column_type("timestamp with time zone"), ImportColumnType::Timestamp);
+        assert_eq!(ImportColumnType::from_column_type("numeric(10,2)"), ImportColumnType::Float);
+    }
+
+    #[test]
+    fn test_import_cells() {
+        assert_eq!(import_cell_text(&json!(" 42 "), ImportColumnType::Integer), Ok(Some("42".to_string())));
+        assert_eq!(import_cell_text(&json!("yes"), ImportColumnType::Boolean), Ok(Some("1".to_string())));
+        assert_eq!(import_cell_text(&json!({"a": 1}), ImportColumnType::Json), Ok(Some(r#"{"a":1}"#.to_string())));
+        assert_eq!(import_cell_text(&serde_json::Value::Null, ImportColumnType::Date), Ok(None));
+        assert_eq!(import_cell_text(&json!("abc"), ImportColumnType::Float), Err("'abc' is not a valid number".to_string()));
+        assert!(import_cell_text(&json!("2024-13-01"), ImportColumnType::Date).is_err());
+
+        let rows = vec![(2, vec![Some("a\tb\\c\nd".to_string()), None])];
+        assert_eq!(String::from_utf8(copy_batch_data(&rows)).unwrap(), "a\\tb\\\\c\\nd\t\\N\n");
+        assert_eq!(mysql_warning_row("Incorrect integer value: 'x' for column 'id' at row 3"), Some(3));
+        assert_eq!(mysql_warning_row("Duplicate entry '1' for key 'PRIMARY'"), None);
+        assert_eq!(import_document_value(json!("12"), ImportColumnType::Integer), json!(12));
+    }
+
+    #[test]
+    fn test_import_reader() {
+        let path = std::env::temp_dir().join(format!("ddm-import-test-{}.csv", std::process::id()));
+        fs::write(&path, "id;name\n1;Ana\n2\n3;\n").unwrap();
+        let request: ImportRequest = serde_json::from_value(json!({
+            "table": "people",
+            "path": path.to_string_lossy(),
+            "format": "csv",
+            "csv": {"delimiter": ";"},
+            "column_map": {"name": "full_name"}
+        }))
+        .unwrap();
+        let records: Vec<ImportRecord> = ImportReader::open(&request, &[]).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].fields.as_ref().unwrap().get("name"), Some(&json!("Ana")));
+        assert_eq!(records[1].line, 3);
+        assert_eq!(records[1].fields.as_ref().unwrap_err(), "Expected 2 fields, found 1");
+        assert_eq!(records[2].fields.as_ref().unwrap().get("name"), Some(&serde_json::Value::Null));
+
+        let (columns, rows) = scan_import_file(&request, &[]).unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!(columns, vec![("id".to_string(), ImportColumnType::Integer), ("full_name".to_string(), ImportColumnType::Text)]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_table_pattern_matches() {
+        assert!(table_pattern_matches("users", "users"));
+        assert!(!table_pattern_matches("users", "users_old"));
+        assert!(table_pattern_matches("tmp_*", "tmp_orders"));
+        assert!(table_pattern_matches("*_log", "audit_log"));
+        assert!(table_pattern_matches("a*b*c", "axxbyyc"));
+        assert!(!table_pattern_matches("a*b*c", "axxcyyb"));
+        assert!(table_pattern_matches("*", "anything"));
+    }
+
+    #[test]
+    fn test_map_transfer_type() {
+        let pg = DatabaseType::PostgreSQL;
+        let my = DatabaseType::MySQL;
+        assert_eq!(map_transfer_type("tinyint(1)", &my, &pg, false), "BOOLEAN");
+        assert_eq!(map_transfer_type("int(10) unsigned", &my, &pg, false), "BIGINT");
+        assert_eq!(map_transfer_type("decimal(10,2)", &my, &pg, false), "NUMERIC(10,2)");
+        assert_eq!(map_transfer_type("enum('a','b')", &my, &pg, false), "TEXT");
+        assert_eq!(map_transfer_type("longblob", &my, &pg, false), "BYTEA");
+        assert_eq!(map_transfer_type("datetime(3)", &my, &pg, false), "TIMESTAMP(3)");
+        assert_eq!(map_transfer_type("character varying(40)", &pg, &my, false), "VARCHAR(40)");
+        assert_eq!(map_transfer_type("text", &pg, &my, true), "VARCHAR(255)");
+        assert_eq!(map_transfer_type("timestamp with time zone", &pg, &my, false), "DATETIME(6)");
+        assert_eq!(map_transfer_type("integer[]", &pg, &my, false), "JSON");
+        assert_eq!(map_transfer_type("numeric", &pg, &my, false), "DECIMAL(65,30)");
+        assert_eq!(map_transfer_type("jsonb", &pg, &pg, false), "jsonb");
+
+        let bytes = TableColumn { name: "data".to_string(), data_type: "bytea".to_string() };
+        assert_eq!(transfer_select_expr(&bytes, &pg, &my), "encode(\"data\", 'hex') AS \"data\"");
+        let price = TableColumn { name: "price".to_string(), data_type: "numeric(12,2)".to_string() };
+        assert_eq!(transfer_select_expr(&price, &pg, &pg), "\"price\"::text AS \"price\"");
+        let tags = TableColumn { name: "tags".to_string(), data_type: "text[]".to_string() };
+        assert_eq!(transfer_select_expr(&tags, &pg, &pg), "\"tags\"::text AS \"tags\"");
+        assert_eq!(transfer_select_expr(&tags, &pg, &my), "array_to_json(\"tags\")::text AS \"tags\"");
+        let period = TableColumn { name: "period".to_string(), data_type: "tstzrange".to_string() };
+        assert_eq!(transfer_select_expr(&period, &pg, &pg), "\"period\"::text AS \"period\"");
+        let active = TableColumn { name: "active".to_string(), data_type: "boolean".to_string() };
+        assert_eq!(transfer_select_expr(&active, &pg, &my), "\"active\"");
+        assert_eq!(transfer_cell(&json!(true), false, &my), Some("1".to_string()));
+        assert_eq!(transfer_cell(&json!("0aff"), true, &pg), Some("\\x0aff".to_string()));
+        assert_eq!(transfer_cell(&json!({"a": 1}), false, &pg), Some(r#"{"a":1}"#.to_string()));
+    }
+
+    fn diff_column(name: &str, data_type: &str, nullable: bool, default: Option<&str>) -> ColumnInfo {
+        ColumnInfo { name: name.to_string(), data_type: data_type.to_string(), nullable, default: default.map(|d| d.to_string()), ordinal: 0, extra: None }
+    }
+
+    #[test]
+    fn test_plan_schema_migration_postgres() {
+        let users = TableInfo {
+            schema: "public".to_string(),
+            name: "users".to_string(),
+            kind: "table".to_string(),
+            columns: vec![
+                diff_column("id", "bigint", false, Some("nextval('users_id_seq'::regclass)")),
+                diff_column("email", "text", false, None),
+            ],
+            indexes: vec![IndexInfo {
+                name: "users_email_idx".to_string(),
+                columns: vec!["email".to_string()],
+                unique: true,
+                primary: false,
+                definition: Some("CREATE UNIQUE INDEX users_email_idx ON public.users USING btree (email)".to_string()),
+            }],
+            ..Default::default()
+        };
+        let mut old_users = users.clone();
+        old_users.columns = vec![diff_column("id", "integer", false, Some("nextval('users_id_seq'::regclass)")), diff_column("legacy", "text", true, None)];
+        old_users.indexes.clear();
+        let orders = TableInfo {
+            schema: "public".to_string(),
+            name: "orders".to_string(),
+            kind: "table".to_string(),
+            columns: vec![diff_column("user_id", "bigint", true, None)],
+            foreign_keys: vec![ForeignKeyInfo {
+                name: "orders_user_fk".to_string(),
+                columns: vec!["user_id".to_string()],
+                referenced_schema: Some("public".to_string()),
+                referenced_table: "users".to_string(),
+                referenced_columns: vec!["id".to_string()],
+                on_update: None,
+                on_delete: Some("CASCADE".to_string()),
+            }],
+            ..Default::default()
+        };
+        let view = |definition: &str| ViewInfo { schema: "public".to_string(), name: "active".to_string(), kind: "view".to_string(), definition: definition.to_string() };
+
+        let source = SchemaSnapshot {
+            schemas: vec!["public".to_string()],
+            tables: vec![users, orders],
+            objects: SchemaObjects { views: vec![view(" SELECT 1;")], ..Default::default() },
+        };
+        let target = SchemaSnapshot {
+            schemas: vec!["public".to_string()],
+            tables: vec![old_users],
+            objects: SchemaObjects { views: vec![view(" SELECT 2;")], ..Default::default() },
+        };
+        let plan = plan_schema_migration(&source, &target, &DatabaseType::PostgreSQL, true);
+        let ddl = plan.script("");
+
+        let find = |name: &str| plan.changes.iter().find(|c| c.name == name).map(|c| c.change);
+        assert_eq!(find("id"), Some(SchemaChangeKind::Modified));
+        assert_eq!(find("email"), Some(SchemaChangeKind::Added));
+        assert_eq!(find("legacy"), Some(SchemaChangeKind::Removed));
+        assert_eq!(find("orders"), Some(SchemaChangeKind::Added));
+        assert_eq!(find("active"), Some(SchemaChangeKind::Modified));
+        assert!(ddl.contains("ALTER TABLE \"public\".\"users\" ALTER COLUMN \"id\" TYPE bigint USING \"id\"::bigint;"));
+        assert!(ddl.contains("ALTER TABLE \"public\".\"users\" DROP COLUMN \"legacy\";"));
+        assert!(ddl.contains("CREATE UNIQUE INDEX users_email_idx ON public.users USING btree (email);"));
+        assert!(ddl.contains("CREATE TABLE \"public\".\"orders\" (\n    \"user_id\" bigint\n);"));
+        assert!(ddl.contains("REFERENCES \"public\".\"users\" (\"id\") ON DELETE CASCADE;"));
+        // Las vistas se borran antes de tocar las tablas y se recrean al final
+        let drop_view = ddl.find("DROP VIEW IF EXISTS").unwrap();
+        let alter = ddl.find("ALTER COLUMN").unwrap();
+        let create_view = ddl.find("CREATE VIEW \"public\".\"active\" AS\nSELECT 1;").unwrap();
+        assert!(drop_view < alter && alter < create_view);
+        assert!(plan.warnings.iter().any(|w| w.contains("users.legacy")));
+        assert!(plan.warnings.iter().any(|w| w.contains("users.email")));
+
+        let plan = plan_schema_migration(&source, &target, &DatabaseType::PostgreSQL, false);
+        assert!(!plan.script("").contains("DROP COLUMN"));
+        assert_eq!(plan_schema_migration(&source, &source, &DatabaseType::PostgreSQL, true).changes.len(), 0);
+    }
+
+    #[test]
+    fn test_plan_schema_migration_dependencies() {
+        let pg_table = |name: &str, columns: Vec<ColumnInfo>, references: Option<&str>| TableInfo {
+            schema: "public".to_string(),
+            name: name.to_string(),
+            kind: "table".to_string(),
+            columns,
+            foreign_keys: references
+                .map(|referenced| ForeignKeyInfo {
+                    name: format!("{}_fk", name),
+                    columns: vec!["id".to_string()],
+                    referenced_schema: Some("public".to_string()),
+                    referenced_table: referenced.to_string(),
+                    referenced_columns: vec!["id".to_string()],
+                    on_update: None,
+                    on_delete: None,
+                })
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let identity = ColumnInfo { extra: Some("GENERATED ALWAYS AS IDENTITY".to_string()), ..diff_column("id", "bigint", false, None) };
+        let routine = |result: &str| RoutineInfo {
+            schema: "public".to_string(),
+            name: "total".to_string(),
+            kind: "function".to_string(),
+            signature: "total()".to_string(),
+            definition: format!("CREATE OR REPLACE FUNCTION public.total()\n RETURNS {}\nAS $$ SELECT 1 $$ LANGUAGE sql", result),
+            result: Some(result.to_string()),
+        };
+        let view = ViewInfo { schema: "public".to_string(), name: "summary".to_string(), kind: "view".to_string(), definition: " SELECT id FROM users;".to_string() };
+
+        let source = SchemaSnapshot {
+            schemas: vec!["public".to_string()],
+            tables: vec![pg_table("users", vec![identity], None)],
+            objects: SchemaObjects { views: vec![view.clone()], routines: vec![routine("bigint")], ..Default::default() },
+        };
+        let target = SchemaSnapshot {
+            schemas: vec!["public".to_string()],
+            tables: vec![
+                pg_table("users", vec![diff_column("id", "integer", false, None)], None),
+                pg_table("a", vec![diff_column("id", "integer", false, None)], Some("b")),
+                pg_table("b", vec![diff_column("id", "integer", false, None)], Some("a")),
+            ],
+            objects: SchemaObjects { views: vec![view], routines: vec![routine("integer")], ..Default::default() },
+        };
+        let plan = plan_schema_migration(&source, &target, &DatabaseType::PostgreSQL, true);
+        let ddl = plan.script("");
+        let position = |statement: &str| ddl.find(statement).unwrap_or_else(|| panic!("missing {}\n{}", statement, ddl));
+
+        assert!(ddl.contains("ALTER TABLE \"public\".\"users\" ALTER COLUMN \"id\" ADD GENERATED ALWAYS AS IDENTITY;"));
+        // Las claves entre tablas eliminadas se borran antes que las tablas
+        assert!(position("ALTER TABLE \"public\".\"a\" DROP CONSTRAINT \"a_fk\";") < position("DROP TABLE \"public\".\"a\";"));
+        assert!(position("ALTER TABLE \"public\".\"b\" DROP CONSTRAINT \"b_fk\";") < position("DROP TABLE \"public\".\"a\";"));
+        // La vista no cambia, pero lee una tabla alterada: se recrea alrededor del cambio
+        assert!(position("DROP VIEW IF EXISTS \"public\".\"summary\";") < position("ALTER COLUMN \"id\" TYPE bigint"));
+        assert!(position("ALTER COLUMN \"id\" TYPE bigint") < position("CREATE VIEW \"public\".\"summary\" AS"));
+        assert!(!plan.changes.iter().any(|c| c.name == "summary"));
+        // Otro tipo de resultado obliga a borrar la función; se crea antes de tocar las tablas
+        assert!(position("DROP FUNCTION \"public\".\"total\"();") < position("CREATE OR REPLACE FUNCTION public.total()"));
+        assert!(position("-- Create routines") < position("-- Alter columns"));
+        // Sin tablas alteradas ni borradas las vistas sin cambios se quedan como están
+        assert!(!plan_schema_migration(&source, &source, &DatabaseType::PostgreSQL, true).script("").contains("VIEW"));
+
+        // Una restricción UNIQUE que solo existe en destino se borra como restricción, no como índice
+        let mut users = source.tables[0].clone();
+        users.indexes.push(IndexInfo {
+            name: "users_id_key".to_string(),
+            columns: vec!["id".to_string()],
+            unique: true,
+            primary: false,
+            definition: Some("CREATE UNIQUE INDEX users_id_key ON public.users USING btree (id)".to_string()),
+        });
+        let constraint = ConstraintInfo {
+            schema: "public".to_string(),
+            table: "users".to_string(),
+            name: "users_id_key".to_string(),
+            kind: "u".to_string(),
+            definition: "UNIQUE (id)".to_string(),
+        };
+        let unique_target = SchemaSnapshot {
+            schemas: vec!["public".to_string()],
+            tables: vec![users],
+            objects: SchemaObjects { constraints: vec![constraint], ..Default::default() },
+        };
+        let ddl = plan_schema_migration(&source, &unique_target, &DatabaseType::PostgreSQL, true).script("");
+        assert!(ddl.contains("ALTER TABLE \"public\".\"users\" DROP CONSTRAINT \"users_id_key\";"));
+        assert!(!ddl.contains("DROP INDEX"));
+    }
+
+    #[test]
+    fn test_plan_schema_migration_mysql() {
+        let table = |columns: Vec<ColumnInfo>, indexes: Vec<IndexInfo>| SchemaSnapshot {
+            schemas: Vec::new(),
+            tables: vec![TableInfo { name: "items".to_string(), kind: "table".to_string(), columns, indexes, ..Default::default() }],
+            objects: SchemaObjects::default(),
+        };
+        let primary = IndexInfo { name: "PRIMARY".to_string(), columns: vec!["id".to_string()], unique: true, primary: true, definition: Some("BTREE".to_string()) };
+        let fulltext = IndexInfo { name: "ft_title".to_string(), columns: vec!["title".to_string()], unique: false, primary: false, definition: Some("FULLTEXT".to_string()) };
+        let mut source = table(
+            vec![diff_column("id", "int", false, None), diff_column("title", "varchar(200)", false, Some("untitled"))],
+            vec![primary.clone(), fulltext],
+        );
+        source.objects.routines.push(RoutineInfo {
+            name: "total".to_string(),
+            kind: "function".to_string(),
+            signature: "total".to_string(),
+            definition: "CREATE FUNCTION `total`() RETURNS int\nDETERMINISTIC CONTAINS SQL\nRETURN 1".to_string(),
+            ..Default::default()
+        });
+        let target = table(vec![diff_column("id", "int", false, None), diff_column("title", "varchar(100)", true, None)], vec![primary.clone()]);
+
+        let ddl = plan_schema_migration(&source, &target, &DatabaseType::MySQL, true).script("");
+        assert!(ddl.contains("ALTER TABLE `items` MODIFY COLUMN `title` varchar(200) NOT NULL DEFAULT 'untitled';"));
+        assert!(ddl.contains("CREATE FULLTEXT INDEX `ft_title` ON `items` (`title`);"));
+        assert!(!ddl.contains("PRIMARY KEY"));
+        assert!(ddl.contains("DELIMITER $$\nCREATE FUNCTION `total`() RETURNS int\nDETERMINISTIC CONTAINS SQL\nRETURN 1$$\nDELIMITER ;"));
+
+        // Ampliar una columna AUTO_INCREMENT no debe perder el atributo
+        let auto_id = |data_type: &str| ColumnInfo { extra: Some("AUTO_INCREMENT".to_string()), ..diff_column("id", data_type, false, None) };
+        let ddl = plan_schema_migration(&table(vec![auto_id("bigint")], vec![primary.clone()]), &table(vec![auto_id("int")], vec![primary.clone()]), &DatabaseType::MySQL, true).script("");
+        assert!(ddl.contains("ALTER TABLE `items` MODIFY COLUMN `id` bigint AUTO_INCREMENT NOT NULL;"));
+        let empty = SchemaSnapshot { schemas: Vec::new(), tables: Vec::new(), objects: SchemaObjects::default() };
+        let ddl = plan_schema_migration(&table(vec![auto_id("bigint")], vec![primary]), &empty, &DatabaseType::MySQL, true).script("");
+        assert!(ddl.contains("CREATE TABLE `items` (\n    `id` bigint AUTO_INCREMENT NOT NULL,\n    PRIMARY KEY (`id`)\n);"));
+        assert!(!ddl.contains("ADD PRIMARY KEY"));
+
+        assert_eq!(mysql_column_extra("auto_increment", Some("")), Some("AUTO_INCREMENT".to_string()));
+        assert_eq!(mysql_column_extra("DEFAULT_GENERATED on update CURRENT_TIMESTAMP", Some("")), Some("ON UPDATE CURRENT_TIMESTAMP".to_string()));
+        assert_eq!(mysql_column_extra("on update current_timestamp()", None), Some("ON UPDATE CURRENT_TIMESTAMP()".to_string()));
+        assert_eq!(mysql_column_extra("STORED GENERATED", Some("(`price` * `qty`)")), Some("GENERATED ALWAYS AS ((`price` * `qty`)) STORED".to_string()));
+        assert_eq!(mysql_column_extra("DEFAULT_GENERATED", Some("")), None);
+        assert_eq!(pg_default_sequence("nextval('users_id_seq'::regclass)"), Some("users_id_seq"));
+        assert_eq!(pg_default_sequence("now()"), None);
+    }
+
+    #[test]
+    fn test_compare_chunk_rows() {
+        let row = |value: serde_json::Value| value.as_object().cloned().unwrap();
+        let key = vec!["id".to_string()];
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let source = vec![row(json!({"id": 1, "name": "a"})), row(json!({"id": 2, "name": "b"})), row(json!({"id": 3, "name": null}))];
+        let target = vec![row(json!({"id": 1, "name": "a"})), row(json!({"id": 3, "name": "c"})), row(json!({"id": 4, "name": "d"}))];
+
+        let differences = compare_chunk_rows(&key, &columns, source, target);
+        let kinds: Vec<RowDiffKind> = differences.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec![RowDiffKind::MissingInTarget, RowDiffKind::Changed, RowDiffKind::ExtraInTarget]);
+        assert_eq!(differences[0].key.get("id"), Some(&json!(2)));
+        assert_eq!(differences[1].columns, vec!["name".to_string()]);
+        assert_eq!(differences[1].target.as_ref().and_then(|t| t.get("name")), Some(&json!("c")));
+        assert!(differences[2].source.is_none());
+        assert!(compare_chunk_rows(&key, &columns, vec![row(json!({"id": 1, "name": "a"}))], vec![row(json!({"id": 1, "name": "a"}))]).is_empty());
+    }
+
+    #[test]
+    fn test_data_diff_range_sql() {
+        let meta = TableMeta {
+            qualified_name: "\"public\".\"events\"".to_string(),
+            columns: vec![
+                TableColumn { name: "day".to_string(), data_type: "date".to_string() },
+                TableColumn { name: "seq".to_string(), data_type: "integer".to_string() },
+            ],
+            primary_key: vec!["day".to_string(), "seq".to_string()],
+        };
+        let key = meta.primary_key.clone();
+        let lower = vec![json!("2024-01-01"), json!(7)];
+        let upper = vec![json!("2024-02-01"), json!(1)];
+
+        let mut sql = SqlBuilder::new(&DatabaseType::PostgreSQL);
+        push_key_range(&mut sql, &meta, &key, Some(&lower), Some(&upper)).unwrap();
+        assert_eq!(
+            sql.sql,
+            " WHERE (\"day\", \"seq\") > (CAST($1::text AS date), CAST($2::text AS integer)) AND (\"day\", \"seq\") <= (CAST($3::text AS date), CAST($4::text AS integer))"
+        );
+        assert_eq!(sql.params, vec![Some("2024-01-01".to_string()), Some("7".to_string()), Some("2024-02-01".to_string()), Some("1".to_string())]);
+
+        let mut sql = SqlBuilder::new(&DatabaseType::MySQL);
+        push_key_range(&mut sql, &meta, &key, None, Some(&upper)).unwrap();
+        assert_eq!(sql.sql, " WHERE (`day`, `seq`) <= (?, ?)");
+        let mut sql = SqlBuilder::new(&DatabaseType::MySQL);
+        push_key_range(&mut sql, &meta, &key, None, None).unwrap();
+        assert!(sql.sql.is_empty());
+
+        let (row_hash, _) = data_diff_checksum_sql(&DatabaseType::MySQL, &["a".to_string(), "b".to_string()]);
+        assert_eq!(row_hash, "MD5(CONCAT_WS(CHAR(31), ISNULL(`a`), `a`, ISNULL(`b`), `b`))");
+        let (row_hash, aggregate) = data_diff_checksum_sql(&DatabaseType::PostgreSQL, &["a".to_string()]);
+        assert_eq!(row_hash, "md5(ROW(\"a\")::text)");
+        assert!(aggregate.contains("string_agg(h, '' ORDER BY h)"));
+    }
+
+    #[test]
+    fn test_running_query_guard() {
+        let running = RunningQueriesState::default();
+        let target = DbTarget {
+            container_id: "abc".to_string(),
+            container_name: "db".to_string(),
+            db_type: DatabaseType::PostgreSQL,
+            user: "postgres".to_string(),
+            password: String::new(),
+            database: "app".to_string(),
+        };
+        let register = || -> Result<(), String> {
+            let _query = running.register("q1", &target, "SELECT pg_sleep(60)", 0)?;
+            assert!(running.register("q1", &target, "SELECT 1", 0).is_err());
+            assert!(running.cancel("q1"));
+            Err("connection refused".to_string())
+        };
+        assert!(register().is_err());
+        // El `?` temprano no deja la consulta registrada
+        assert!(running.list().is_empty());
+        assert!(!running.cancel("q1"));
+    }
+
+    #[test]
+    fn test_binding_host() {
+        assert_eq!(binding_host(None), "127.0.0.1");
+        assert_eq!(binding_host(Some("0.0.0.0")), "127.0.0.1");
+        assert_eq!(binding_host(Some("::")), "::1");
+        assert_eq!(binding_host(Some("192.168.1.20")), "192.168.1.20");
+    }
+
+    #[test]
+    fn test_events_resume() {
+        let event = |id: &str, time: i64| DockerEvent {
+            kind: "container".to_string(),
+            action: "start".to_string(),
+            id: id.to_string(),
+            name: None,
+            detail: None,
+            attributes: HashMap::new(),
+            time,
+        };
+        let mut resume = EventsResume::default();
+        assert!(resume.first_time(&event("a", 10), 10_000_000_001));
+        assert!(resume.first_time(&event("b", 10), 10_000_000_002));
+        // Al reconectar con `since` = 10 se repiten los eventos de ese segundo
+        assert!(!resume.first_time(&event("a", 10), 10_000_000_001));
+        assert!(resume.first_time(&event("a", 10), 10_500_000_000));
+        assert!(resume.first_time(&event("a", 11), 11_000_000_000));
+        assert_eq!(resume.second, Some(11));
+    }
+
+    #[test]
+    fn test_is_lifecycle_event() {
+        assert!(is_lifecycle_event("container", "start"));
+        assert!(is_lifecycle_event("container", "health_status: unhealthy"));
+        assert!(is_lifecycle_event("volume", "destroy"));
+        // Los exec que lanzamos nosotros mismos no deben llegar al frontend
+        assert!(!is_lifecycle_event("container", "exec_start: psql -c SELECT 1"));
+        assert!(!is_lifecycle_event("daemon", "reload"));
+    }
 }
 
 // ============================================================================
@@ -1808,7 +3314,8 @@ async fn migrate_database(
     migration_state: State<'_, MigrationState>,
 ) -> Result<String, String> {
     
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
+    let _guard = docker_state.lock_container(&format!("migrated-{}", database_name)).await;
     
     // 0. Detectar versión de PostgreSQL del servidor local
     println!("[MIGRATE] Detecting PostgreSQL version...");
@@ -2331,7 +3838,8 @@ async fn remove_migrated_database(
     migration_state: State<'_, MigrationState>,
     docker_state: State<'_, AppState>,
 ) -> Result<String, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
+    let _guard = docker_state.lock_container(&container_id).await;
     
     // Detener y eliminar contenedor
     let _ = docker.stop_container(&container_id, None).await;
@@ -2367,7 +3875,7 @@ pub struct VolumeInfo {
 
 #[tauri::command]
 async fn list_volumes(docker_state: State<'_, AppState>) -> Result<Vec<VolumeInfo>, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     
     // Listar todos los volúmenes
     let volumes_response = docker
@@ -2434,7 +3942,7 @@ async fn remove_volume(
     force: bool,
     docker_state: State<'_, AppState>,
 ) -> Result<String, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     
     use bollard::volume::RemoveVolumeOptions;
     
@@ -2451,7 +3959,7 @@ async fn remove_volume(
 
 #[tauri::command]
 async fn prune_volumes(docker_state: State<'_, AppState>) -> Result<String, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     
     use bollard::volume::PruneVolumesOptions;
     use std::collections::HashMap;
@@ -2481,7 +3989,7 @@ async fn backup_volume(
 ) -> Result<String, String> {
     use bollard::container::{Config, CreateContainerOptions};
     
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     
     // Crear un contenedor temporal para hacer backup del volumen
     let container_name = format!("backup-{}", chrono::Utc::now().timestamp());
@@ -2560,7 +4068,7 @@ async fn restore_volume(
     use bollard::container::{Config, CreateContainerOptions};
     use bollard::volume::CreateVolumeOptions;
     
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     
     // Crear el volumen si no existe
     let _ = docker
@@ -2684,111 +4192,196 @@ pub struct ContainerStats {
     timestamp: String,
 }
 
-#[tauri::command]
-async fn get_container_stats(
-    container_id: String,
-    docker_state: State<'_, AppState>,
-) -> Result<ContainerStats, String> {
-    use bollard::container::StatsOptions;
-    
-    let docker = docker_state.docker.lock().await;
-    
-    // Obtener info del contenedor
-    let container_info = docker
-        .inspect_container(&container_id, None)
-        .await
-        .map_err(|e| format!("Failed to inspect container: {}", e))?;
-    
-    let container_name = container_info
-        .name
-        .unwrap_or_else(|| container_id.clone())
-        .trim_start_matches('/')
-        .to_string();
+// Cuántos contenedores se consultan a la vez en get_all_containers_stats
+const STATS_MAX_CONCURRENCY: usize = 8;
+// Un stream sin lecturas durante este tiempo se cierra solo
+const STATS_STREAM_IDLE_SECS: u64 = 120;
+// Espera máxima para la primera muestra de un stream recién abierto
+const STATS_FIRST_SAMPLE_TIMEOUT_SECS: u64 = 5;
+
+/// A live `docker stats` stream for one container
+struct StatsStream {
+    receiver: tokio::sync::watch::Receiver<Option<ContainerStats>>,
+    last_read: Arc<std::sync::Mutex<std::time::Instant>>,
+}
+
+/// Long-lived stats streams, one per running container
+///
+/// One-shot stats have an empty `precpu_stats`, so CPU can't be computed from them.
+/// Instead we keep a streaming subscription per container and always serve the most
+/// recent sample, whose CPU is the delta between two real daemon samples (~1s apart).
+/// Streams end on their own when the container stops or nobody reads them for a while.
+pub struct StatsState {
+    streams: std::sync::Mutex<HashMap<String, StatsStream>>,
+}
+
+impl StatsState {
+    pub fn new() -> Self {
+        StatsState { streams: std::sync::Mutex::new(HashMap::new()) }
+    }
+
+    /// Receiver for the container's stream, opening the stream if needed
+    fn subscribe(&self, docker: &Docker, container_id: &str, container_name: &str) -> tokio::sync::watch::Receiver<Option<ContainerStats>> {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Olvidar los streams cuyo task ya terminó (contenedor parado o inactivo)
+        streams.retain(|_, s| s.receiver.has_changed().is_ok());
+
+        if let Some(stream) = streams.get(container_id) {
+            *stream.last_read.lock().unwrap_or_else(|e| e.into_inner()) = std::time::Instant::now();
+            return stream.receiver.clone();
+        }
+
+        let (sender, receiver) = tokio::sync::watch::channel(None);
+        let last_read = Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
+        streams.insert(container_id.to_string(), StatsStream { receiver: receiver.clone(), last_read: last_read.clone() });
+
+        let docker = docker.clone();
+        let container_id = container_id.to_string();
+        let container_name = container_name.to_string();
+        tokio::spawn(async move {
+            use bollard::container::StatsOptions;
+
+            let mut stream = docker.stats(&container_id, Some(StatsOptions { stream: true, one_shot: false }));
+            while let Some(Ok(stats)) = stream.next().await {
+                // La primera muestra del stream no trae precpu_stats: no sirve para calcular CPU
+                if stats.precpu_stats.system_cpu_usage.unwrap_or(0) == 0 {
+                    continue;
+                }
+                if sender.send(Some(container_stats_from_sample(&container_id, &container_name, &stats))).is_err() {
+                    break;
+                }
+                let idle = last_read.lock().unwrap_or_else(|e| e.into_inner()).elapsed();
+                if idle.as_secs() > STATS_STREAM_IDLE_SECS {
+                    break;
+                }
+            }
+        });
+
+        receiver
+    }
+}
+
+impl Default for StatsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn container_stats_from_sample(container_id: &str, container_name: &str, stats: &bollard::container::Stats) -> ContainerStats {
+    // Calcular CPU percentage
+    let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
+        - stats.precpu_stats.cpu_usage.total_usage as f64;
+    let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
+        - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
+    let cpu_percentage = if system_delta > 0.0 && cpu_delta > 0.0 {
+        let num_cpus = stats.cpu_stats.online_cpus
+            .or_else(|| stats.cpu_stats.cpu_usage.percpu_usage.as_ref().map(|p| p.len() as u64))
+            .unwrap_or(1) as f64;
+        (cpu_delta / system_delta) * num_cpus * 100.0
+    } else {
+        0.0
+    };
     
-    // Obtener stats (stream=false para un solo snapshot)
-    let mut stats_stream = docker.stats(
-        &container_id,
-        Some(StatsOptions {
-            stream: false,
-            one_shot: true,
-        }),
-    );
+    // Memory stats
+    let memory_usage = stats.memory_stats.usage.unwrap_or(0);
+    let memory_limit = stats.memory_stats.limit.unwrap_or(0);
+    let memory_percentage = if memory_limit > 0 {
+        (memory_usage as f64 / memory_limit as f64) * 100.0
+    } else {
+        0.0
+    };
     
-    if let Some(stats_result) = stats_stream.next().await {
-        let stats = stats_result.map_err(|e| format!("Failed to get stats: {}", e))?;
-        
-        // Calcular CPU percentage
-        let cpu_delta = stats.cpu_stats.cpu_usage.total_usage as f64
-            - stats.precpu_stats.cpu_usage.total_usage as f64;
-        let system_delta = stats.cpu_stats.system_cpu_usage.unwrap_or(0) as f64
-            - stats.precpu_stats.system_cpu_usage.unwrap_or(0) as f64;
-        let cpu_percentage = if system_delta > 0.0 && cpu_delta > 0.0 {
-            let num_cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
-            (cpu_delta / system_delta) * num_cpus * 100.0
-        } else {
-            0.0
-        };
-        
-        // Memory stats
-        let memory_usage = stats.memory_stats.usage.unwrap_or(0);
-        let memory_limit = stats.memory_stats.limit.unwrap_or(0);
-        let memory_percentage = if memory_limit > 0 {
-            (memory_usage as f64 / memory_limit as f64) * 100.0
-        } else {
-            0.0
-        };
-        
-        // Network stats
-        let (network_rx, network_tx) = if let Some(networks) = stats.networks {
-            let rx: u64 = networks.values().map(|n| n.rx_bytes).sum();
-            let tx: u64 = networks.values().map(|n| n.tx_bytes).sum();
-            (rx, tx)
-        } else {
-            (0, 0)
-        };
-        
-        // Block I/O stats
-        let (block_read, block_write) = if let Some(io_stats) = stats.blkio_stats.io_service_bytes_recursive {
-            let read: u64 = io_stats
-                .iter()
-                .filter(|s| s.op == "read" || s.op == "Read")
-                .map(|s| s.value)
-                .sum();
-            let write: u64 = io_stats
-                .iter()
-                .filter(|s| s.op == "write" || s.op == "Write")
-                .map(|s| s.value)
-                .sum();
-            (read, write)
-        } else {
-            (0, 0)
-        };
-        
-        Ok(ContainerStats {
-            container_id: container_id.clone(),
-            container_name,
-            cpu_percentage: (cpu_percentage * 100.0).round() / 100.0, // Redondear a 2 decimales
-            memory_usage,
-            memory_limit,
-            memory_percentage: (memory_percentage * 100.0).round() / 100.0,
-            network_rx_bytes: network_rx,
-            network_tx_bytes: network_tx,
-            block_read_bytes: block_read,
-            block_write_bytes: block_write,
-            timestamp: chrono::Utc::now().to_rfc3339(),
-        })
+    // Network stats
+    let (network_rx, network_tx) = if let Some(networks) = &stats.networks {
+        let rx: u64 = networks.values().map(|n| n.rx_bytes).sum();
+        let tx: u64 = networks.values().map(|n| n.tx_bytes).sum();
+        (rx, tx)
+    } else {
+        (0, 0)
+    };
+    
+    // Block I/O stats
+    let (block_read, block_write) = if let Some(io_stats) = &stats.blkio_stats.io_service_bytes_recursive {
+        let read: u64 = io_stats
+            .iter()
+            .filter(|s| s.op == "read" || s.op == "Read")
+            .map(|s| s.value)
+            .sum();
+        let write: u64 = io_stats
+            .iter()
+            .filter(|s| s.op == "write" || s.op == "Write")
+            .map(|s| s.value)
+            .sum();
+        (read, write)
     } else {
-        Err("No stats available for container".to_string())
+        (0, 0)
+    };
+    
+    ContainerStats {
+        container_id: container_id.to_string(),
+        container_name: container_name.to_string(),
+        cpu_percentage: (cpu_percentage * 100.0).round() / 100.0, // Redondear a 2 decimales
+        memory_usage,
+        memory_limit,
+        memory_percentage: (memory_percentage * 100.0).round() / 100.0,
+        network_rx_bytes: network_rx,
+        network_tx_bytes: network_tx,
+        block_read_bytes: block_read,
+        block_write_bytes: block_write,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Latest stats for a container, waiting for the first sample if its stream was just opened
+async fn latest_container_stats(
+    docker: &Docker,
+    stats_state: &StatsState,
+    container_id: &str,
+    container_name: &str,
+) -> Result<ContainerStats, String> {
+    let mut receiver = stats_state.subscribe(docker, container_id, container_name);
+    
+    if let Some(stats) = receiver.borrow().clone() {
+        return Ok(stats);
+    }
+    
+    let first_sample = tokio::time::timeout(
+        std::time::Duration::from_secs(STATS_FIRST_SAMPLE_TIMEOUT_SECS),
+        receiver.wait_for(|s| s.is_some()),
+    ).await;
+    match first_sample {
+        Ok(Ok(stats)) => stats.clone().ok_or_else(|| "No stats available for container".to_string()),
+        Ok(Err(_)) => Err("No stats available for container (is it running?)".to_string()),
+        Err(_) => Err("Timeout waiting for container stats".to_string()),
     }
 }
 
 #[tauri::command]
-async fn get_all_containers_stats(
+async fn get_container_stats(
+    container_id: String,
     docker_state: State<'_, AppState>,
-) -> Result<Vec<ContainerStats>, String> {
-    let docker = docker_state.docker.lock().await;
+    stats_state: State<'_, StatsState>,
+) -> Result<ContainerStats, String> {
+    let docker = docker_state.docker();
+    
+    // Obtener info del contenedor
+    let container_info = docker
+        .inspect_container(&container_id, None)
+        .await
+        .map_err(|e| format!("Failed to inspect container: {}", e))?;
+    
+    let container_name = container_info
+        .name
+        .unwrap_or_else(|| container_id.clone())
+        .trim_start_matches('/')
+        .to_string();
     
-    // Listar todos los contenedores en ejecución
+    latest_container_stats(&docker, &stats_state, &container_id, &container_name).await
+}
+
+/// ID and name of every running container
+async fn list_running_containers(docker: &Docker) -> Result<Vec<(String, String)>, String> {
     let containers = docker
         .list_containers(Some(ListContainersOptions::<String> {
             all: false, // Solo contenedores en ejecución
@@ -2796,33 +4389,66 @@ async fn get_all_containers_stats(
         }))
         .await
         .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    Ok(containers
+        .into_iter()
+        .filter_map(|c| {
+            let id = c.id?;
+            let name = c.names
+                .and_then(|n| n.first().map(|s| s.trim_start_matches('/').to_string()))
+                .unwrap_or_else(|| id.clone());
+            Some((id, name))
+        })
+        .collect())
+}
+
+/// Stats for every running container, fetched concurrently
+async fn collect_all_container_stats(docker: &Docker, stats_state: &StatsState) -> Result<Vec<ContainerStats>, String> {
+    let targets = list_running_containers(docker).await?;
     
-    drop(docker); // Liberar el lock
+    // Consultar en paralelo, con un máximo de STATS_MAX_CONCURRENCY a la vez
+    let results: Vec<(String, Result<ContainerStats, String>)> = futures_util::stream::iter(targets)
+        .map(|(id, name)| async move {
+            let result = latest_container_stats(docker, stats_state, &id, &name).await;
+            (id, result)
+        })
+        .buffer_unordered(STATS_MAX_CONCURRENCY)
+        .collect()
+        .await;
     
     let mut all_stats = Vec::new();
     
-    for container in containers {
-        if let Some(id) = &container.id {
-            match get_container_stats(id.clone(), docker_state.clone()).await {
-                Ok(stats) => all_stats.push(stats),
-                Err(e) => {
-                    eprintln!("Failed to get stats for container {}: {}", id, e);
-                    // Continuar con los demás contenedores
-                }
+    for (id, result) in results {
+        match result {
+            Ok(stats) => all_stats.push(stats),
+            Err(e) => {
+                eprintln!("Failed to get stats for container {}: {}", id, e);
+                // Continuar con los demás contenedores
             }
         }
     }
     
+    all_stats.sort_by(|a, b| a.container_name.cmp(&b.container_name));
     Ok(all_stats)
 }
 
+#[tauri::command]
+async fn get_all_containers_stats(
+    docker_state: State<'_, AppState>,
+    stats_state: State<'_, StatsState>,
+) -> Result<Vec<ContainerStats>, String> {
+    let docker = docker_state.docker();
+    collect_all_container_stats(&docker, &stats_state).await
+}
+
 #[tauri::command]
 async fn check_resource_alerts(
     cpu_threshold: f64,
     memory_threshold: f64,
     docker_state: State<'_, AppState>,
+    stats_state: State<'_, StatsState>,
 ) -> Result<Vec<String>, String> {
-    let stats = get_all_containers_stats(docker_state).await?;
+    let stats = get_all_containers_stats(docker_state, stats_state).await?;
     
     let mut alerts = Vec::new();
     
@@ -2925,7 +4551,7 @@ async fn generate_compose_from_containers(
     container_ids: Vec<String>,
     docker_state: State<'_, AppState>,
 ) -> Result<String, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     let mut services = HashMap::new();
     let mut volumes_set = std::collections::HashSet::new();
 
@@ -3051,7 +4677,7 @@ async fn deploy_compose_file(
     let compose_config: ComposeConfig = serde_yaml::from_str(&yaml_content)
         .map_err(|e| format!("Failed to parse compose file: {}", e))?;
 
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
     let mut created_containers = Vec::new();
 
     // Create volumes first
@@ -3244,7 +4870,7 @@ async fn deploy_compose_file(
 async fn list_compose_projects(
     docker_state: State<'_, AppState>,
 ) -> Result<Vec<ComposeProject>, String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
 
     let mut filters = HashMap::new();
     filters.insert("label".to_string(), vec!["com.docker.compose.project".to_string()]);
@@ -3312,7 +4938,7 @@ async fn stop_compose_project(
     project_name: String,
     docker_state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
 
     let mut filters = HashMap::new();
     filters.insert(
@@ -3359,7 +4985,7 @@ async fn remove_compose_project(
     remove_volumes: bool,
     docker_state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let docker = docker_state.docker.lock().await;
+    let docker = docker_state.docker();
 
     let mut filters = HashMap::new();
     filters.insert(
@@ -3430,3 +5056,10775 @@ async fn remove_compose_project(
 
     Ok(())
 }
+
+// ===== CONTAINER DETAILS =====
+
+/// A single published port binding
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortMapping {
+    pub container_port: String,
+    pub host_ip: Option<String>,
+    pub host_port: Option<String>,
+}
+
+/// A mount attached to a container (named volume, bind mount or tmpfs)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MountDetails {
+    pub mount_type: String,
+    pub volume_name: Option<String>,
+    pub source: Option<String>,
+    pub destination: String,
+    pub mode: Option<String>,
+    pub read_write: bool,
+}
+
+/// A network the container is connected to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NetworkDetails {
+    pub name: String,
+    pub ip_address: Option<String>,
+    pub gateway: Option<String>,
+    pub mac_address: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+/// An environment variable, with the value hidden when the key looks like a secret
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+    pub masked: bool,
+}
+
+/// Resource limits from the HostConfig. `None` means "not limited".
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ResourceLimits {
+    pub memory_bytes: Option<i64>,
+    pub memory_reservation_bytes: Option<i64>,
+    pub memory_swap_bytes: Option<i64>,
+    pub cpus: Option<f64>,
+    pub cpu_shares: Option<i64>,
+    pub cpuset_cpus: Option<String>,
+    pub pids_limit: Option<i64>,
+}
+
+/// Health check state, only present when the image or container defines a HEALTHCHECK
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HealthDetails {
+    pub status: String,
+    pub failing_streak: i64,
+    pub last_output: Option<String>,
+    pub last_exit_code: Option<i64>,
+    pub last_check: Option<String>,
+}
+
+/// Full inspect view of a container
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerDetails {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub image_id: String,
+    pub image_digest: Option<String>,
+    pub status: String,
+    pub running: bool,
+    pub db_type: Option<String>,
+    pub db_icon: Option<String>,
+    pub database_name: Option<String>,
+    pub created: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub uptime_seconds: Option<i64>,
+    pub exit_code: Option<i64>,
+    pub oom_killed: bool,
+    pub error: Option<String>,
+    pub restart_policy: Option<String>,
+    pub restart_max_retries: Option<i64>,
+    pub restart_count: i64,
+    pub command: Vec<String>,
+    pub ports: Vec<PortMapping>,
+    pub mounts: Vec<MountDetails>,
+    pub networks: Vec<NetworkDetails>,
+    pub env: Vec<EnvVar>,
+    pub labels: HashMap<String, String>,
+    pub resources: ResourceLimits,
+    pub health: Option<HealthDetails>,
+}
+
+const MASKED_VALUE: &str = "********";
+
+// Fragmentos de nombre que delatan un secreto (POSTGRES_PASSWORD, API_TOKEN, AWS_SECRET_KEY...)
+const SECRET_ENV_MARKERS: &[&str] = &["PASSWORD", "PASSWD", "PASS", "SECRET", "TOKEN", "KEY", "CREDENTIAL"];
+
+fn is_secret_env_key(key: &str) -> bool {
+    let key = key.to_uppercase();
+    SECRET_ENV_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+fn mask_env_var(entry: &str) -> EnvVar {
+    let (key, value) = entry.split_once('=').unwrap_or((entry, ""));
+    let masked = is_secret_env_key(key) && !value.is_empty();
+    EnvVar {
+        key: key.to_string(),
+        value: if masked { MASKED_VALUE.to_string() } else { value.to_string() },
+        masked,
+    }
+}
+
+// Docker usa "0001-01-01T00:00:00Z" para fechas que nunca ocurrieron
+fn parse_docker_timestamp(value: Option<&String>) -> Option<chrono::DateTime<chrono::Utc>> {
+    value
+        .filter(|v| !v.starts_with("0001-01-01"))
+        .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+/// Get a detailed, parsed view of a container
+///
+/// Unlike `list_containers`, this returns every port binding, mount, network and
+/// environment variable (secrets masked), plus resource limits, restart policy and health.
+///
+/// # Arguments
+/// * `container_id` - The ID or name of the container
+/// * `docker_state` - Docker state
+///
+/// # Returns
+/// * `Ok(ContainerDetails)` - Parsed inspect data
+/// * `Err(String)` - Error message if the container cannot be inspected
+#[tauri::command]
+async fn get_container_details(
+    container_id: String,
+    docker_state: State<'_, AppState>,
+) -> Result<ContainerDetails, String> {
+    let docker = docker_state.docker();
+
+    let inspect = docker
+        .inspect_container(&container_id, None)
+        .await
+        .map_err(|e| format!("Failed to inspect container: {}", e))?;
+
+    let config = inspect.config.unwrap_or_default();
+    let host_config = inspect.host_config.unwrap_or_default();
+    let state = inspect.state.unwrap_or_default();
+    let labels = config.labels.clone().unwrap_or_default();
+    let image = config.image.clone().unwrap_or_default();
+    let image_id = inspect.image.clone().unwrap_or_default();
+
+    // El digest vive en la imagen, no en el contenedor
+    let image_digest = if image_id.is_empty() {
+        None
+    } else {
+        docker
+            .inspect_image(&image_id)
+            .await
+            .ok()
+            .and_then(|img| img.repo_digests)
+            .and_then(|digests| digests.into_iter().next())
+    };
+
+    let db_type = detect_database_type(Some(&labels), Some(&image));
+
+    // Ports: todos los bindings publicados, no solo el primero (un contenedor parado devuelve `Ports: {}`)
+    let mut ports: Vec<PortMapping> = inspect
+        .network_settings
+        .as_ref()
+        .and_then(|ns| ns.ports.clone())
+        .filter(|ports| !ports.is_empty())
+        .or(host_config.port_bindings.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|(container_port, bindings)| match bindings {
+            Some(bindings) if !bindings.is_empty() => bindings
+                .into_iter()
+                .map(|b| PortMapping {
+                    container_port: container_port.clone(),
+                    host_ip: b.host_ip,
+                    host_port: b.host_port,
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![PortMapping { container_port, host_ip: None, host_port: None }],
+        })
+        .collect();
+    ports.sort_by(|a, b| a.container_port.cmp(&b.container_port).then(a.host_ip.cmp(&b.host_ip)));
+
+    let mounts = inspect
+        .mounts
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| MountDetails {
+            mount_type: m.typ.map(|t| t.to_string()).unwrap_or_default(),
+            volume_name: m.name,
+            source: m.source,
+            destination: m.destination.unwrap_or_default(),
+            mode: m.mode.filter(|mode| !mode.is_empty()),
+            read_write: m.rw.unwrap_or(true),
+        })
+        .collect();
+
+    let mut networks: Vec<NetworkDetails> = inspect
+        .network_settings
+        .and_then(|ns| ns.networks)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, endpoint)| NetworkDetails {
+            name,
+            ip_address: endpoint.ip_address.filter(|ip| !ip.is_empty()),
+            gateway: endpoint.gateway.filter(|gw| !gw.is_empty()),
+            mac_address: endpoint.mac_address.filter(|mac| !mac.is_empty()),
+            aliases: endpoint.aliases.unwrap_or_default(),
+        })
+        .collect();
+    networks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let env = config.env.unwrap_or_default().iter().map(|e| mask_env_var(e)).collect();
+
+    let resources = ResourceLimits {
+        memory_bytes: host_config.memory.filter(|m| *m > 0),
+        memory_reservation_bytes: host_config.memory_reservation.filter(|m| *m > 0),
+        memory_swap_bytes: host_config.memory_swap.filter(|m| *m != 0),
+        cpus: host_config
+            .nano_cpus
+            .filter(|n| *n > 0)
+            .map(|n| n as f64 / 1_000_000_000.0)
+            .or_else(|| match (host_config.cpu_quota, host_config.cpu_period) {
+                (Some(quota), Some(period)) if quota > 0 && period > 0 => Some(quota as f64 / period as f64),
+                _ => None,
+            }),
+        cpu_shares: host_config.cpu_shares.filter(|s| *s > 0),
+        cpuset_cpus: host_config.cpuset_cpus.filter(|c| !c.is_empty()),
+        pids_limit: host_config.pids_limit.filter(|p| *p > 0),
+    };
+
+    let (restart_policy, restart_max_retries) = match host_config.restart_policy {
+        Some(policy) => (
+            policy.name.map(|n| n.to_string()).filter(|n| !n.is_empty()),
+            policy.maximum_retry_count.filter(|c| *c > 0),
+        ),
+        None => (None, None),
+    };
+
+    let health = state.health.as_ref().and_then(|h| {
+        let status = h.status.as_ref().map(|s| s.to_string()).unwrap_or_default();
+        if status.is_empty() || status == "none" {
+            return None;
+        }
+        let last = h.log.as_ref().and_then(|log| log.last());
+        Some(HealthDetails {
+            status,
+            failing_streak: h.failing_streak.unwrap_or(0),
+            last_output: last.and_then(|l| l.output.as_ref()).map(|o| o.trim().to_string()),
+            last_exit_code: last.and_then(|l| l.exit_code),
+            last_check: last.and_then(|l| l.end.as_ref()).map(|d| d.to_string()),
+        })
+    });
+
+    let running = state.running.unwrap_or(false);
+    let started_at = parse_docker_timestamp(state.started_at.as_ref());
+    let finished_at = parse_docker_timestamp(state.finished_at.as_ref());
+    let uptime_seconds = if running {
+        started_at.map(|s| (chrono::Utc::now() - s).num_seconds().max(0))
+    } else {
+        None
+    };
+
+    // Path + Args es el comando efectivo (entrypoint incluido)
+    let mut command = Vec::new();
+    if let Some(path) = inspect.path.filter(|p| !p.is_empty()) {
+        command.push(path);
+    }
+    command.extend(inspect.args.unwrap_or_default());
+
+    Ok(ContainerDetails {
+        id: inspect.id.unwrap_or_else(|| container_id.clone()),
+        name: inspect.name.unwrap_or_default().trim_start_matches('/').to_string(),
+        image,
+        image_id,
+        image_digest,
+        status: state.status.map(|s| s.to_string()).unwrap_or_else(|| "unknown".to_string()),
+        running,
+        db_type: db_type.as_ref().map(|t| t.to_string()),
+        db_icon: labels
+            .get("db_icon")
+            .cloned()
+            .or_else(|| db_type.as_ref().map(|t| t.get_icon().to_string())),
+        database_name: labels.get("database_name").cloned(),
+        created: inspect.created.unwrap_or_default(),
+        started_at: started_at.map(|d| d.to_rfc3339()),
+        finished_at: if running { None } else { finished_at.map(|d| d.to_rfc3339()) },
+        uptime_seconds,
+        exit_code: if running { None } else { state.exit_code },
+        oom_killed: state.oom_killed.unwrap_or(false),
+        error: state.error.filter(|e| !e.is_empty()),
+        restart_policy,
+        restart_max_retries,
+        restart_count: inspect.restart_count.unwrap_or(0),
+        command,
+        ports,
+        mounts,
+        networks,
+        env,
+        labels,
+        resources,
+        health,
+    })
+}
+
+// ===== DOCKER EVENTS =====
+
+/// Name of the Tauri event carrying a `DockerEvent`
+const DOCKER_EVENT: &str = "docker-event";
+/// Name of the Tauri event carrying a `DockerEventsStatus`
+const DOCKER_EVENTS_STATUS: &str = "docker-events-status";
+/// Maximum wait between reconnection attempts to the `/events` stream
+const DOCKER_EVENTS_MAX_BACKOFF_SECS: u64 = 30;
+
+/// A Docker lifecycle event forwarded to the frontend
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DockerEvent {
+    /// container, volume, image or network
+    pub kind: String,
+    /// create, start, die, oom, health_status, destroy...
+    pub action: String,
+    pub id: String,
+    pub name: Option<String>,
+    /// Extra detail: health status for `health_status`, exit code for `die`
+    pub detail: Option<String>,
+    pub attributes: HashMap<String, String>,
+    pub time: i64,
+}
+
+/// Whether the backend is currently subscribed to Docker's `/events` stream
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DockerEventsStatus {
+    pub connected: bool,
+    pub message: Option<String>,
+}
+
+// Estado compartido de la suscripción, para que el frontend pueda consultarlo al arrancar
+pub struct DockerEventsState {
+    pub connected: std::sync::atomic::AtomicBool,
+}
+
+fn is_lifecycle_event(kind: &str, action: &str) -> bool {
+    // health_status llega como "health_status: healthy"
+    let action = action.split(':').next().unwrap_or(action).trim();
+    match kind {
+        "container" => matches!(
+            action,
+            "create" | "start" | "restart" | "stop" | "die" | "oom" | "health_status" | "destroy" | "rename" | "pause" | "unpause"
+        ),
+        "volume" => matches!(action, "create" | "destroy"),
+        "image" => matches!(action, "pull" | "tag" | "untag" | "delete" | "import" | "load"),
+        "network" => matches!(action, "create" | "destroy" | "connect" | "disconnect"),
+        _ => false,
+    }
+}
+
+fn to_docker_event(message: bollard::models::EventMessage) -> Option<DockerEvent> {
+    let kind = message.typ.map(|t| t.to_string()).unwrap_or_default();
+    let raw_action = message.action.unwrap_or_default();
+    if !is_lifecycle_event(&kind, &raw_action) {
+        return None;
+    }
+
+    let (action, mut detail) = match raw_action.split_once(':') {
+        Some((action, detail)) => (action.trim().to_string(), Some(detail.trim().to_string())),
+        None => (raw_action.clone(), None),
+    };
+
+    let actor = message.actor.unwrap_or_default();
+    let attributes = actor.attributes.unwrap_or_default();
+    if action == "die" {
+        detail = attributes.get("exitCode").cloned();
+    }
+
+    Some(DockerEvent {
+        kind,
+        action,
+        id: actor.id.unwrap_or_default(),
+        name: attributes.get("name").cloned(),
+        detail,
+        attributes,
+        time: message.time.unwrap_or_else(|| chrono::Utc::now().timestamp()),
+    })
+}
+
+/// Events already forwarded in the last second seen, to resume the stream from that second
+/// (`since` has one-second granularity) without repeating them
+#[derive(Default)]
+struct EventsResume {
+    second: Option<i64>,
+    seen: std::collections::HashSet<(String, String, i64)>,
+}
+
+impl EventsResume {
+    /// Record the event; false if it was already forwarded before a reconnect
+    fn first_time(&mut self, event: &DockerEvent, time_nano: i64) -> bool {
+        if self.second != Some(event.time) {
+            self.second = Some(event.time);
+            self.seen.clear();
+        }
+        self.seen.insert((event.id.clone(), event.action.clone(), time_nano))
+    }
+}
+
+fn set_docker_events_status(app: &tauri::AppHandle, connected: bool, message: Option<String>) {
+    use std::sync::atomic::Ordering;
+
+    let previous = app.state::<DockerEventsState>().connected.swap(connected, Ordering::SeqCst);
+    if previous != connected {
+        println!("[Events] Docker events stream {}", if connected { "connected" } else { "disconnected" });
+        let _ = app.emit(DOCKER_EVENTS_STATUS, DockerEventsStatus { connected, message });
+    }
+}
+
+/// Subscribe to Docker's `/events` stream and forward lifecycle events to the frontend
+///
+/// Runs for the whole life of the app. When the daemon goes away the stream ends and we
+/// reconnect with exponential backoff; when `reconnect_docker` swaps the client the stream
+/// is dropped and reopened with the new one. Either way, events missed since the last one
+/// received are replayed.
+fn spawn_docker_events_listener(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        use bollard::system::EventsOptions;
+
+        let mut backoff = 1;
+        let mut resume = EventsResume::default();
+
+        loop {
+            // Tomar el cliente actual en cada intento, por si reconnect_docker lo reemplazó
+            let docker = app.state::<AppState>().docker();
+
+            match docker.ping().await {
+                Ok(_) => {
+                    set_docker_events_status(&app, true, None);
+                    backoff = 1;
+
+                    let mut filters = HashMap::new();
+                    filters.insert(
+                        "type".to_string(),
+                        vec!["container".to_string(), "volume".to_string(), "image".to_string(), "network".to_string()],
+                    );
+
+                    let mut stream = docker.events(Some(EventsOptions::<String> {
+                        since: resume.second.map(|t| t.to_string()),
+                        until: None,
+                        filters,
+                    }));
+
+                    let state = app.state::<AppState>();
+                    loop {
+                        let result = tokio::select! {
+                            result = stream.next() => result,
+                            _ = state.docker_replaced.notified() => {
+                                println!("[Events] Docker client replaced, reopening the events stream");
+                                break;
+                            }
+                        };
+                        let Some(result) = result else { break };
+                        match result {
+                            Ok(message) => {
+                                let time_nano = message.time_nano.unwrap_or_default();
+                                if let Some(event) = to_docker_event(message) {
+                                    if resume.first_time(&event, time_nano) {
+                                        let _ = app.emit(DOCKER_EVENT, event);
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("[Events] Docker events stream error: {}", e);
+                                break;
+                            }
+                        }
+                    }
+
+                    set_docker_events_status(&app, false, Some("Docker events stream closed".to_string()));
+                }
+                Err(e) => {
+                    set_docker_events_status(&app, false, Some(format!("Docker not reachable: {}", e)));
+                }
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_secs(backoff)).await;
+            backoff = (backoff * 2).min(DOCKER_EVENTS_MAX_BACKOFF_SECS);
+        }
+    });
+}
+
+/// Whether the backend is currently receiving Docker events
+///
+/// The frontend uses this on startup to decide how often it still needs to poll.
+#[tauri::command]
+async fn get_docker_events_status(events_state: State<'_, DockerEventsState>) -> Result<DockerEventsStatus, String> {
+    use std::sync::atomic::Ordering;
+
+    Ok(DockerEventsStatus {
+        connected: events_state.connected.load(Ordering::SeqCst),
+        message: None,
+    })
+}
+
+// ===== APP DATA FILES =====
+
+/// Directory where the backend keeps its own files (metrics, rules, history...)
+fn app_data_dir(app: &tauri::AppHandle) -> std::path::PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("docker-db-manager"))
+}
+
+/// Read a JSON file, returning `None` if it doesn't exist or can't be parsed
+fn read_json_file<T: serde::de::DeserializeOwned>(path: &std::path::Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("[Storage] Ignoring unreadable file {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+static UNIQUE_ID_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Identifier unique within the process and across restarts (`prefix-<nanos>-<counter>`)
+fn unique_id(prefix: &str) -> String {
+    let counter = UNIQUE_ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(), counter)
+}
+
+/// Write a file atomically (temp file + rename) so a crash never leaves it half-written
+fn write_file_atomic(path: &std::path::Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    // Un nombre temporal por escritura: dos escrituras a la vez no comparten archivo
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, unique_id("w")));
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace {}: {}", path.display(), e)
+    })
+}
+
+/// Write a JSON file atomically
+fn write_json_file<T: Serialize>(path: &std::path::Path, value: &T) -> Result<(), String> {
+    let content = serde_json::to_vec(value).map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    write_file_atomic(path, &content)
+}
+
+// ===== METRICS HISTORY =====
+
+/// Downsampling tiers as (resolution, retention) in seconds: 10s for 1h, 1m for 24h, 10m for 30d
+const METRICS_TIERS: &[(i64, i64)] = &[(10, 3_600), (60, 86_400), (600, 2_592_000)];
+// Cada cuánto se vuelcan a disco las series modificadas
+const METRICS_FLUSH_SECS: u64 = 60;
+const METRICS_MIN_INTERVAL_SECS: u64 = 2;
+
+/// Metric names accepted by `get_stats_history`, same as the `ContainerStats` fields
+const METRIC_NAMES: &[&str] = &[
+    "cpu_percentage",
+    "memory_usage",
+    "memory_percentage",
+    "network_rx_bytes",
+    "network_tx_bytes",
+    "block_read_bytes",
+    "block_write_bytes",
+];
+
+/// One stored point of every metric for a container
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct MetricSample {
+    pub timestamp: i64,
+    pub cpu_percentage: f64,
+    pub memory_usage: f64,
+    pub memory_percentage: f64,
+    pub network_rx_bytes: f64,
+    pub network_tx_bytes: f64,
+    pub block_read_bytes: f64,
+    pub block_write_bytes: f64,
+}
+
+impl MetricSample {
+    fn from_stats(stats: &ContainerStats, timestamp: i64) -> Self {
+        MetricSample {
+            timestamp,
+            cpu_percentage: stats.cpu_percentage,
+            memory_usage: stats.memory_usage as f64,
+            memory_percentage: stats.memory_percentage,
+            network_rx_bytes: stats.network_rx_bytes as f64,
+            network_tx_bytes: stats.network_tx_bytes as f64,
+            block_read_bytes: stats.block_read_bytes as f64,
+            block_write_bytes: stats.block_write_bytes as f64,
+        }
+    }
+
+    fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "cpu_percentage" => Some(self.cpu_percentage),
+            "memory_usage" => Some(self.memory_usage),
+            "memory_percentage" => Some(self.memory_percentage),
+            "network_rx_bytes" => Some(self.network_rx_bytes),
+            "network_tx_bytes" => Some(self.network_tx_bytes),
+            "block_read_bytes" => Some(self.block_read_bytes),
+            "block_write_bytes" => Some(self.block_write_bytes),
+            _ => None,
+        }
+    }
+
+    // Gauges se suman para promediar; los contadores acumulados se quedan con el último valor
+    fn accumulate(&mut self, other: &MetricSample) {
+        self.cpu_percentage += other.cpu_percentage;
+        self.memory_usage += other.memory_usage;
+        self.memory_percentage += other.memory_percentage;
+        self.network_rx_bytes = other.network_rx_bytes;
+        self.network_tx_bytes = other.network_tx_bytes;
+        self.block_read_bytes = other.block_read_bytes;
+        self.block_write_bytes = other.block_write_bytes;
+    }
+
+    fn averaged(&self, count: u32) -> MetricSample {
+        let n = count.max(1) as f64;
+        MetricSample {
+            cpu_percentage: (self.cpu_percentage / n * 100.0).round() / 100.0,
+            memory_usage: (self.memory_usage / n).round(),
+            memory_percentage: (self.memory_percentage / n * 100.0).round() / 100.0,
+            ..*self
+        }
+    }
+}
+
+/// Ring buffer of samples at one resolution
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct MetricsTier {
+    resolution_secs: i64,
+    retention_secs: i64,
+    samples: std::collections::VecDeque<MetricSample>,
+    // Bucket abierto: suma de muestras y cuántas van (se guarda para no perderlo al reiniciar)
+    #[serde(default)]
+    pending: Option<(MetricSample, u32)>,
+}
+
+impl MetricsTier {
+    fn new(resolution_secs: i64, retention_secs: i64) -> Self {
+        MetricsTier { resolution_secs, retention_secs, samples: std::collections::VecDeque::new(), pending: None }
+    }
+
+    fn push(&mut self, sample: MetricSample) {
+        let bucket = sample.timestamp - sample.timestamp.rem_euclid(self.resolution_secs);
+        match &mut self.pending {
+            Some((acc, count)) if acc.timestamp == bucket => {
+                acc.accumulate(&sample);
+                *count += 1;
+            }
+            _ => {
+                self.close_bucket();
+                self.pending = Some((MetricSample { timestamp: bucket, ..sample }, 1));
+            }
+        }
+    }
+
+    fn close_bucket(&mut self) {
+        if let Some((acc, count)) = self.pending.take() {
+            self.samples.push_back(acc.averaged(count));
+        }
+        let newest = self.samples.back().map(|s| s.timestamp).unwrap_or(0);
+        while self.samples.front().is_some_and(|s| s.timestamp < newest - self.retention_secs) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn range(&self, from: i64, to: i64) -> Vec<MetricSample> {
+        let open = self.pending.map(|(acc, count)| acc.averaged(count));
+        self.samples
+            .iter()
+            .copied()
+            .chain(open)
+            .filter(|s| s.timestamp >= from && s.timestamp <= to)
+            .collect()
+    }
+}
+
+/// All tiers for one container, persisted as one JSON file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct MetricsSeries {
+    container_id: String,
+    container_name: String,
+    tiers: Vec<MetricsTier>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl MetricsSeries {
+    fn new(container_id: &str, container_name: &str) -> Self {
+        MetricsSeries {
+            container_id: container_id.to_string(),
+            container_name: container_name.to_string(),
+            tiers: METRICS_TIERS.iter().map(|(res, ret)| MetricsTier::new(*res, *ret)).collect(),
+            dirty: false,
+        }
+    }
+
+    fn record(&mut self, sample: MetricSample) {
+        for tier in &mut self.tiers {
+            tier.push(sample);
+        }
+        self.dirty = true;
+    }
+
+    /// The finest tier whose retention still covers `from`
+    fn tier_for(&self, from: i64, now: i64) -> Option<&MetricsTier> {
+        self.tiers
+            .iter()
+            .find(|t| from >= now - t.retention_secs)
+            .or_else(|| self.tiers.last())
+    }
+
+    fn newest_timestamp(&self) -> i64 {
+        self.tiers
+            .iter()
+            .filter_map(|t| t.pending.map(|(s, _)| s.timestamp).or(t.samples.back().map(|s| s.timestamp)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Sampling settings, stored in `metrics/settings.json`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetricsSettings {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        MetricsSettings { enabled: true, interval_secs: 10 }
+    }
+}
+
+/// A metric over time, as returned to the Dashboard charts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StatsHistory {
+    pub container_id: String,
+    pub container_name: String,
+    pub metric: String,
+    pub resolution_secs: i64,
+    pub points: Vec<MetricPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetricPoint {
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+/// On-disk time-series store for container stats
+pub struct MetricsState {
+    dir: std::path::PathBuf,
+    series: std::sync::Mutex<HashMap<String, MetricsSeries>>,
+    settings: std::sync::Mutex<MetricsSettings>,
+}
+
+impl MetricsState {
+    /// Load the store from `dir`, dropping series with nothing inside the longest retention
+    pub fn load(dir: std::path::PathBuf) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        let max_retention = METRICS_TIERS.iter().map(|(_, ret)| *ret).max().unwrap_or(0);
+        let settings = read_json_file(&dir.join("settings.json")).unwrap_or_default();
+
+        let mut series = HashMap::new();
+        if let Ok(entries) = fs::read_dir(dir.join("series")) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(loaded) = read_json_file::<MetricsSeries>(&path) else { continue };
+                if loaded.newest_timestamp() < now - max_retention {
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                series.insert(loaded.container_id.clone(), loaded);
+            }
+        }
+        println!("[Metrics] Loaded history for {} containers", series.len());
+
+        MetricsState {
+            dir,
+            series: std::sync::Mutex::new(series),
+            settings: std::sync::Mutex::new(settings),
+        }
+    }
+
+    fn settings(&self) -> MetricsSettings {
+        self.settings.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn record(&self, stats: &[ContainerStats], timestamp: i64) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        for stat in stats {
+            let entry = series
+                .entry(stat.container_id.clone())
+                .or_insert_with(|| MetricsSeries::new(&stat.container_id, &stat.container_name));
+            entry.container_name = stat.container_name.clone();
+            entry.record(MetricSample::from_stats(stat, timestamp));
+        }
+    }
+
+    /// Write every series modified since the last flush (open buckets included)
+    fn flush(&self) {
+        let dirty: Vec<MetricsSeries> = {
+            let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+            series
+                .values_mut()
+                .filter(|s| s.dirty)
+                .map(|s| {
+                    s.dirty = false;
+                    s.clone()
+                })
+                .collect()
+        };
+        for s in dirty {
+            let path = self.dir.join("series").join(format!("{}.json", s.container_id));
+            if let Err(e) = write_json_file(&path, &s) {
+                eprintln!("[Metrics] {}", e);
+            }
+        }
+    }
+}
+
+/// One sample of every running container, read straight from the daemon
+///
+/// With `one_shot: false` the daemon waits for a second reading, so `precpu_stats` is filled
+/// in and CPU can be computed without opening (or keeping alive) the Dashboard streams.
+async fn sample_running_containers(docker: &Docker) -> Result<Vec<ContainerStats>, String> {
+    use bollard::container::StatsOptions;
+
+    let targets = list_running_containers(docker).await?;
+    let results: Vec<(String, Result<ContainerStats, String>)> = futures_util::stream::iter(targets)
+        .map(|(id, name)| async move {
+            let mut stream = docker.stats(&id, Some(StatsOptions { stream: false, one_shot: false }));
+            let result = match stream.next().await {
+                Some(Ok(stats)) => Ok(container_stats_from_sample(&id, &name, &stats)),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err("No stats available for container".to_string()),
+            };
+            (id, result)
+        })
+        .buffer_unordered(STATS_MAX_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut samples = Vec::new();
+    for (id, result) in results {
+        match result {
+            Ok(stats) => samples.push(stats),
+            Err(e) => eprintln!("[Metrics] Failed to sample container {}: {}", id, e),
+        }
+    }
+    Ok(samples)
+}
+
+/// Sample stats of every running container into the history store
+///
+/// Runs on its own schedule, whether or not the Dashboard is open, and takes its own
+/// samples so the Dashboard streams still end when nobody watches them.
+fn spawn_metrics_sampler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_flush = std::time::Instant::now();
+
+        loop {
+            let settings = app.state::<MetricsState>().settings();
+            tokio::time::sleep(tokio::time::Duration::from_secs(settings.interval_secs.max(METRICS_MIN_INTERVAL_SECS))).await;
+
+            if settings.enabled {
+                let docker = app.state::<AppState>().docker();
+                match sample_running_containers(&docker).await {
+                    Ok(stats) => app.state::<MetricsState>().record(&stats, chrono::Utc::now().timestamp()),
+                    Err(e) => eprintln!("[Metrics] Sampling failed: {}", e),
+                }
+            }
+
+            if last_flush.elapsed().as_secs() >= METRICS_FLUSH_SECS {
+                let metrics = app.state::<MetricsState>();
+                metrics.flush();
+                last_flush = std::time::Instant::now();
+            }
+        }
+    });
+}
+
+/// Get the recorded history of one metric for a container
+///
+/// The resolution is picked automatically: 10s samples for the last hour,
+/// 1m for the last 24h and 10m for up to 30 days.
+///
+/// # Arguments
+/// * `container_id` - The ID of the container
+/// * `metric` - One of the `ContainerStats` numeric fields (e.g. `cpu_percentage`)
+/// * `from` - Start of the range, unix seconds (default: one hour ago)
+/// * `to` - End of the range, unix seconds (default: now)
+///
+/// # Returns
+/// * `Ok(StatsHistory)` - Points in the range, oldest first
+/// * `Err(String)` - Unknown metric or no history for the container
+#[tauri::command]
+async fn get_stats_history(
+    container_id: String,
+    metric: String,
+    from: Option<i64>,
+    to: Option<i64>,
+    metrics_state: State<'_, MetricsState>,
+) -> Result<StatsHistory, String> {
+    if !METRIC_NAMES.contains(&metric.as_str()) {
+        return Err(format!("Unknown metric '{}'. Available: {}", metric, METRIC_NAMES.join(", ")));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let to = to.unwrap_or(now);
+    let from = from.unwrap_or(to - 3_600);
+    if from > to {
+        return Err("'from' must be before 'to'".to_string());
+    }
+
+    let series = metrics_state.series.lock().unwrap_or_else(|e| e.into_inner());
+    let container_series = series
+        .get(&container_id)
+        .or_else(|| series.values().find(|s| s.container_name == container_id))
+        .ok_or_else(|| format!("No metrics history for container {}", container_id))?;
+    let tier = container_series.tier_for(from, now).ok_or("No metrics tiers configured")?;
+
+    let points = tier
+        .range(from, to)
+        .iter()
+        .filter_map(|s| s.metric(&metric).map(|value| MetricPoint { timestamp: s.timestamp, value }))
+        .collect();
+
+    Ok(StatsHistory {
+        container_id: container_series.container_id.clone(),
+        container_name: container_series.container_name.clone(),
+        metric,
+        resolution_secs: tier.resolution_secs,
+        points,
+    })
+}
+
+/// Get the metrics sampling settings
+#[tauri::command]
+async fn get_metrics_settings(metrics_state: State<'_, MetricsState>) -> Result<MetricsSettings, String> {
+    Ok(metrics_state.settings())
+}
+
+/// Update the metrics sampling settings
+///
+/// # Arguments
+/// * `settings` - `enabled` and `interval_secs` (minimum 2 seconds)
+#[tauri::command]
+async fn update_metrics_settings(
+    settings: MetricsSettings,
+    metrics_state: State<'_, MetricsState>,
+) -> Result<MetricsSettings, String> {
+    let settings = MetricsSettings {
+        interval_secs: settings.interval_secs.max(METRICS_MIN_INTERVAL_SECS),
+        ..settings
+    };
+    write_json_file(&metrics_state.dir.join("settings.json"), &settings)?;
+    *metrics_state.settings.lock().unwrap_or_else(|e| e.into_inner()) = settings.clone();
+    Ok(settings)
+}
+
+// ===== RESOURCE ALERTING =====
+
+// Cada cuánto se evalúan las reglas
+const ALERT_EVAL_INTERVAL_SECS: u64 = 30;
+// Una alerta de CPU/memoria/disco se resuelve al bajar un 10% por debajo del umbral
+const ALERT_HYSTERESIS_RATIO: f64 = 0.9;
+// Ventana para contar reinicios
+const ALERT_RESTART_WINDOW_SECS: i64 = 600;
+const ALERT_HISTORY_LIMIT: usize = 500;
+/// Name of the Tauri event carrying an `AlertEvent`
+const ALERT_EVENT: &str = "resource-alert";
+
+/// What an alert rule watches
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// CPU usage in percent
+    CpuPercentage,
+    /// Memory usage in percent of the limit
+    MemoryPercentage,
+    /// Disk writes in MB per minute
+    DiskGrowth,
+    /// Restarts in the last 10 minutes
+    RestartCount,
+    /// Consecutive failed health checks
+    HealthFailures,
+    /// Container stopped with a non-zero exit code or was OOM-killed (threshold ignored)
+    ContainerExit,
+}
+
+impl AlertMetric {
+    fn label(&self) -> &str {
+        match self {
+            AlertMetric::CpuPercentage => "CPU usage",
+            AlertMetric::MemoryPercentage => "Memory usage",
+            AlertMetric::DiskGrowth => "Disk writes",
+            AlertMetric::RestartCount => "Restarts",
+            AlertMetric::HealthFailures => "Failed health checks",
+            AlertMetric::ContainerExit => "Container exited",
+        }
+    }
+
+    fn format_value(&self, value: f64) -> String {
+        match self {
+            AlertMetric::CpuPercentage | AlertMetric::MemoryPercentage => format!("{:.2}%", value),
+            AlertMetric::DiskGrowth => format!("{:.2} MB/min", value),
+            AlertMetric::RestartCount | AlertMetric::HealthFailures => format!("{}", value as i64),
+            AlertMetric::ContainerExit => format!("exit code {}", value as i64),
+        }
+    }
+
+    // Las métricas continuas usan histéresis; los contadores se resuelven al bajar del umbral
+    fn uses_hysteresis(&self) -> bool {
+        matches!(self, AlertMetric::CpuPercentage | AlertMetric::MemoryPercentage | AlertMetric::DiskGrowth)
+    }
+}
+
+/// An alert rule, global (`container: None`) or for one container (ID or name)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertRule {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub container: Option<String>,
+    pub metric: AlertMetric,
+    #[serde(default)]
+    pub threshold: f64,
+    /// The condition must hold this long before the alert fires
+    #[serde(default)]
+    pub for_minutes: u32,
+    #[serde(default = "default_true")]
+    pub notify: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl AlertRule {
+    fn applies_to(&self, container_id: &str, container_name: &str) -> bool {
+        match &self.container {
+            None => true,
+            Some(target) => target == container_name || (!target.is_empty() && container_id.starts_with(target.as_str())),
+        }
+    }
+}
+
+/// A rule firing or resolving, kept in the alert history
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub metric: AlertMetric,
+    pub value: f64,
+    pub threshold: f64,
+    /// "firing" or "resolved"
+    pub state: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// What the evaluator knows about a container in one cycle
+#[derive(Debug, Clone, Default)]
+struct AlertObservation {
+    container_id: String,
+    container_name: String,
+    cpu_percentage: Option<f64>,
+    memory_percentage: Option<f64>,
+    block_write_bytes: Option<u64>,
+    restart_count: Option<i64>,
+    failing_streak: Option<i64>,
+    /// Exit code when the container stopped abnormally
+    abnormal_exit: Option<i64>,
+}
+
+// Estado de una regla para un contenedor
+#[derive(Debug, Clone, Default)]
+struct AlertTracker {
+    pending_since: Option<i64>,
+    firing: bool,
+}
+
+// Muestras anteriores necesarias para métricas derivadas (tasa de escritura, reinicios)
+#[derive(Debug, Clone, Default)]
+struct AlertContainerHistory {
+    last_block_write: Option<(i64, u64)>,
+    restarts: std::collections::VecDeque<(i64, i64)>,
+}
+
+/// A rule already firing for a container, persisted so a restart doesn't fire it again
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct FiringAlert {
+    rule_id: String,
+    container_id: String,
+}
+
+#[derive(Default)]
+struct AlertEngine {
+    trackers: HashMap<(String, String), AlertTracker>,
+    history: HashMap<String, AlertContainerHistory>,
+}
+
+impl AlertEngine {
+    /// Engine that starts with these alerts already firing
+    fn with_firing(firing: &[FiringAlert]) -> Self {
+        let mut engine = AlertEngine::default();
+        for alert in firing {
+            engine
+                .trackers
+                .insert((alert.rule_id.clone(), alert.container_id.clone()), AlertTracker { pending_since: None, firing: true });
+        }
+        engine
+    }
+
+    fn firing(&self) -> Vec<FiringAlert> {
+        self.trackers
+            .iter()
+            .filter(|(_, t)| t.firing)
+            .map(|((rule_id, container_id), _)| FiringAlert { rule_id: rule_id.clone(), container_id: container_id.clone() })
+            .collect()
+    }
+
+    /// Value of `metric` for a container, updating derived-metric history
+    fn metric_values(&mut self, obs: &AlertObservation, now: i64) -> HashMap<AlertMetric, f64> {
+        let history = self.history.entry(obs.container_id.clone()).or_default();
+        let mut values = HashMap::new();
+
+        if let Some(cpu) = obs.cpu_percentage {
+            values.insert(AlertMetric::CpuPercentage, cpu);
+        }
+        if let Some(memory) = obs.memory_percentage {
+            values.insert(AlertMetric::MemoryPercentage, memory);
+        }
+        if let Some(written) = obs.block_write_bytes {
+            if let Some((last_ts, last_written)) = history.last_block_write {
+                let minutes = (now - last_ts) as f64 / 60.0;
+                if minutes > 0.0 && written >= last_written {
+                    values.insert(AlertMetric::DiskGrowth, (written - last_written) as f64 / 1_048_576.0 / minutes);
+                }
+            }
+            history.last_block_write = Some((now, written));
+        }
+        if let Some(count) = obs.restart_count {
+            history.restarts.push_back((now, count));
+            while history.restarts.front().is_some_and(|(ts, _)| *ts < now - ALERT_RESTART_WINDOW_SECS) {
+                history.restarts.pop_front();
+            }
+            let oldest = history.restarts.front().map(|(_, c)| *c).unwrap_or(count);
+            values.insert(AlertMetric::RestartCount, (count - oldest).max(0) as f64);
+        }
+        if let Some(streak) = obs.failing_streak {
+            values.insert(AlertMetric::HealthFailures, streak as f64);
+        }
+        values.insert(AlertMetric::ContainerExit, obs.abnormal_exit.map(|c| c as f64).unwrap_or(-1.0));
+
+        values
+    }
+
+    /// Evaluate all rules against one cycle of observations and return state changes
+    fn evaluate(&mut self, rules: &[AlertRule], observations: &[AlertObservation], now: i64) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+
+        for obs in observations {
+            let values = self.metric_values(obs, now);
+
+            for rule in rules.iter().filter(|r| r.enabled && r.applies_to(&obs.container_id, &obs.container_name)) {
+                let value = values.get(&rule.metric).copied();
+                let (triggered, cleared) = match (rule.metric, value) {
+                    (AlertMetric::ContainerExit, Some(code)) => (code >= 0.0, code < 0.0),
+                    (metric, Some(v)) if metric.uses_hysteresis() => (v >= rule.threshold, v < rule.threshold * ALERT_HYSTERESIS_RATIO),
+                    (_, Some(v)) => (v >= rule.threshold, v < rule.threshold),
+                    // Sin dato (p.ej. contenedor parado): la alerta se resuelve
+                    (_, None) => (false, true),
+                };
+
+                let tracker = self.trackers.entry((rule.id.clone(), obs.container_id.clone())).or_default();
+                if triggered {
+                    let since = *tracker.pending_since.get_or_insert(now);
+                    if !tracker.firing && now - since >= i64::from(rule.for_minutes) * 60 {
+                        tracker.firing = true;
+                        events.push(alert_event(rule, obs, value.unwrap_or(0.0), "firing"));
+                    }
+                } else {
+                    tracker.pending_since = None;
+                    if tracker.firing && cleared {
+                        tracker.firing = false;
+                        events.push(alert_event(rule, obs, value.unwrap_or(0.0), "resolved"));
+                    }
+                }
+            }
+        }
+
+        // Olvidar contenedores que ya no existen
+        let seen: std::collections::HashSet<&String> = observations.iter().map(|o| &o.container_id).collect();
+        self.trackers.retain(|(_, container_id), _| seen.contains(container_id));
+        self.history.retain(|container_id, _| seen.contains(container_id));
+
+        events
+    }
+}
+
+fn alert_event(rule: &AlertRule, obs: &AlertObservation, value: f64, state: &str) -> AlertEvent {
+    let message = match (rule.metric, state) {
+        (AlertMetric::ContainerExit, "firing") => format!("⚠️ {} - {} ({})", obs.container_name, rule.metric.label(), rule.metric.format_value(value)),
+        (_, "firing") => format!(
+            "⚠️ {} - {}: {} (threshold {})",
+            obs.container_name,
+            rule.metric.label(),
+            rule.metric.format_value(value),
+            rule.metric.format_value(rule.threshold)
+        ),
+        _ => format!("✅ {} - {} back to normal", obs.container_name, rule.metric.label()),
+    };
+
+    AlertEvent {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        container_id: obs.container_id.clone(),
+        container_name: obs.container_name.clone(),
+        metric: rule.metric,
+        value,
+        threshold: rule.threshold,
+        state: state.to_string(),
+        message,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Alert rules and history, stored under `alerts/` in the app data dir
+pub struct AlertsState {
+    dir: std::path::PathBuf,
+    rules: std::sync::Mutex<Vec<AlertRule>>,
+    history: std::sync::Mutex<std::collections::VecDeque<AlertEvent>>,
+    engine: std::sync::Mutex<AlertEngine>,
+}
+
+impl AlertsState {
+    pub fn load(dir: std::path::PathBuf) -> Self {
+        let rules: Vec<AlertRule> = read_json_file(&dir.join("rules.json")).unwrap_or_default();
+        let history = read_json_file(&dir.join("history.json")).unwrap_or_default();
+        // Alertas activas al cerrar: un contenedor que ya estaba caído no vuelve a avisar
+        let firing: Vec<FiringAlert> = read_json_file(&dir.join("firing.json")).unwrap_or_default();
+        println!("[Alerts] Loaded {} alert rules", rules.len());
+
+        AlertsState {
+            dir,
+            rules: std::sync::Mutex::new(rules),
+            history: std::sync::Mutex::new(history),
+            engine: std::sync::Mutex::new(AlertEngine::with_firing(&firing)),
+        }
+    }
+
+    fn save_firing(&self) {
+        let firing = self.engine.lock().unwrap_or_else(|e| e.into_inner()).firing();
+        if let Err(e) = write_json_file(&self.dir.join("firing.json"), &firing) {
+            eprintln!("[Alerts] {}", e);
+        }
+    }
+
+    fn rules(&self) -> Vec<AlertRule> {
+        self.rules.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Modify the rules and persist them while holding the lock, so concurrent
+    /// saves can't overwrite each other. Nothing changes if `f` or the write fails.
+    fn update_rules<R>(&self, f: impl FnOnce(&mut Vec<AlertRule>) -> Result<R, String>) -> Result<R, String> {
+        let mut rules = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        let mut updated = rules.clone();
+        let result = f(&mut updated)?;
+        write_json_file(&self.dir.join("rules.json"), &updated)?;
+        *rules = updated;
+        Ok(result)
+    }
+
+    fn push_history(&self, events: &[AlertEvent]) {
+        let snapshot = {
+            let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+            history.extend(events.iter().cloned());
+            while history.len() > ALERT_HISTORY_LIMIT {
+                history.pop_front();
+            }
+            history.clone()
+        };
+        if let Err(e) = write_json_file(&self.dir.join("history.json"), &snapshot) {
+            eprintln!("[Alerts] {}", e);
+        }
+    }
+}
+
+/// Gather the data every rule needs for one evaluation cycle
+async fn collect_alert_observations(docker: &Docker, stats_state: &StatsState, rules: &[AlertRule]) -> Result<Vec<AlertObservation>, String> {
+    let containers = docker
+        .list_containers(Some(ListContainersOptions::<String> { all: true, ..Default::default() }))
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    let needs_stats = rules.iter().any(|r| matches!(r.metric, AlertMetric::CpuPercentage | AlertMetric::MemoryPercentage | AlertMetric::DiskGrowth));
+    let needs_inspect = rules.iter().any(|r| matches!(r.metric, AlertMetric::RestartCount | AlertMetric::HealthFailures | AlertMetric::ContainerExit));
+
+    let stats: HashMap<String, ContainerStats> = if needs_stats {
+        collect_all_container_stats(docker, stats_state)
+            .await?
+            .into_iter()
+            .map(|s| (s.container_id.clone(), s))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let targets: Vec<(String, String)> = containers
+        .into_iter()
+        .filter_map(|c| {
+            let id = c.id?;
+            let name = c.names
+                .and_then(|n| n.first().map(|s| s.trim_start_matches('/').to_string()))
+                .unwrap_or_else(|| id.clone());
+            rules.iter().any(|r| r.enabled && r.applies_to(&id, &name)).then_some((id, name))
+        })
+        .collect();
+
+    let observations = futures_util::stream::iter(targets)
+        .map(|(id, name)| {
+            let stat = stats.get(&id).cloned();
+            async move {
+                let mut obs = AlertObservation {
+                    container_id: id.clone(),
+                    container_name: name,
+                    cpu_percentage: stat.as_ref().map(|s| s.cpu_percentage),
+                    memory_percentage: stat.as_ref().map(|s| s.memory_percentage),
+                    block_write_bytes: stat.as_ref().map(|s| s.block_write_bytes),
+                    ..Default::default()
+                };
+                if needs_inspect {
+                    if let Ok(inspect) = docker.inspect_container(&id, None).await {
+                        obs.restart_count = inspect.restart_count;
+                        if let Some(state) = inspect.state {
+                            obs.failing_streak = state.health.as_ref().and_then(|h| h.failing_streak);
+                            let running = state.running.unwrap_or(false) || state.restarting.unwrap_or(false);
+                            let exit_code = state.exit_code.unwrap_or(0);
+                            if !running && (exit_code != 0 || state.oom_killed.unwrap_or(false)) {
+                                obs.abnormal_exit = Some(exit_code);
+                            }
+                        }
+                    }
+                }
+                obs
+            }
+        })
+        .buffer_unordered(STATS_MAX_CONCURRENCY)
+        .collect()
+        .await;
+
+    Ok(observations)
+}
+
+/// Evaluate alert rules continuously, record history and raise desktop notifications
+fn spawn_alert_evaluator(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        use tauri_plugin_notification::NotificationExt;
+
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(ALERT_EVAL_INTERVAL_SECS)).await;
+
+            let rules = app.state::<AlertsState>().rules();
+            if !rules.iter().any(|r| r.enabled) {
+                continue;
+            }
+
+            let docker = app.state::<AppState>().docker();
+            let stats_state = app.state::<StatsState>();
+            let observations = match collect_alert_observations(&docker, &stats_state, &rules).await {
+                Ok(observations) => observations,
+                Err(e) => {
+                    eprintln!("[Alerts] Evaluation skipped: {}", e);
+                    continue;
+                }
+            };
+
+            let alerts = app.state::<AlertsState>();
+            let events = alerts
+                .engine
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .evaluate(&rules, &observations, chrono::Utc::now().timestamp());
+            if events.is_empty() {
+                continue;
+            }
+            alerts.push_history(&events);
+            alerts.save_firing();
+
+            for event in &events {
+                println!("[Alerts] {}", event.message);
+                let _ = app.emit(ALERT_EVENT, event.clone());
+
+                let notify = rules.iter().any(|r| r.id == event.rule_id && r.notify);
+                if notify {
+                    if let Err(e) = app.notification().builder().title(&event.rule_name).body(&event.message).show() {
+                        eprintln!("[Alerts] Failed to show notification: {}", e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// List alert rules
+#[tauri::command]
+async fn list_alert_rules(alerts_state: State<'_, AlertsState>) -> Result<Vec<AlertRule>, String> {
+    Ok(alerts_state.rules())
+}
+
+/// Create or update an alert rule
+///
+/// # Arguments
+/// * `rule` - The rule; an empty `id` creates a new rule
+///
+/// # Returns
+/// * `Ok(AlertRule)` - The stored rule, with its ID
+/// * `Err(String)` - Validation or storage error
+#[tauri::command]
+async fn save_alert_rule(rule: AlertRule, alerts_state: State<'_, AlertsState>) -> Result<AlertRule, String> {
+    if rule.name.trim().is_empty() {
+        return Err("Alert rule name cannot be empty".to_string());
+    }
+    if rule.threshold < 0.0 {
+        return Err("Alert threshold cannot be negative".to_string());
+    }
+
+    let mut rule = rule;
+    if rule.id.is_empty() {
+        rule.id = unique_id("rule");
+    }
+    rule.container = rule.container.filter(|c| !c.trim().is_empty());
+
+    alerts_state.update_rules(|rules| {
+        match rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+        Ok(())
+    })?;
+
+    Ok(rule)
+}
+
+/// Delete an alert rule
+#[tauri::command]
+async fn delete_alert_rule(rule_id: String, alerts_state: State<'_, AlertsState>) -> Result<String, String> {
+    alerts_state.update_rules(|rules| {
+        let before = rules.len();
+        rules.retain(|r| r.id != rule_id);
+        if rules.len() == before {
+            return Err(format!("Alert rule '{}' not found", rule_id));
+        }
+        Ok(())
+    })?;
+    Ok("Alert rule deleted".to_string())
+}
+
+/// Get the alert history, newest first
+///
+/// # Arguments
+/// * `limit` - Maximum number of entries (default: all kept, up to 500)
+#[tauri::command]
+async fn get_alert_history(limit: Option<usize>, alerts_state: State<'_, AlertsState>) -> Result<Vec<AlertEvent>, String> {
+    let history = alerts_state.history.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(history.iter().rev().take(limit.unwrap_or(ALERT_HISTORY_LIMIT)).cloned().collect())
+}
+
+/// Clear the alert history
+#[tauri::command]
+async fn clear_alert_history(alerts_state: State<'_, AlertsState>) -> Result<String, String> {
+    alerts_state.history.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    write_json_file(&alerts_state.dir.join("history.json"), &Vec::<AlertEvent>::new())?;
+    Ok("Alert history cleared".to_string())
+}
+
+// ===== DATABASE ENGINE ACCESS =====
+
+// Tiempo máximo por defecto de un comando del cliente dentro del contenedor
+const ENGINE_EXEC_TIMEOUT_SECS: u64 = 30;
+
+/// A running database container and the credentials its image was started with
+#[derive(Debug, Clone)]
+struct DbTarget {
+    container_id: String,
+    container_name: String,
+    db_type: DatabaseType,
+    user: String,
+    password: String,
+    database: String,
+}
+
+/// Output of a command run with `docker exec`
+#[derive(Debug, Clone, Default)]
+struct ExecOutput {
+    stdout: String,
+    stderr: String,
+    exit_code: Option<i64>,
+}
+
+impl ExecOutput {
+    /// stdout on success, otherwise the client's error message
+    fn into_result(self) -> Result<String, String> {
+        if self.exit_code.unwrap_or(0) == 0 {
+            Ok(self.stdout)
+        } else {
+            let message = if self.stderr.trim().is_empty() { self.stdout } else { self.stderr };
+            Err(message.trim().to_string())
+        }
+    }
+}
+
+/// Read engine credentials from the container env (and the redis `--requirepass` argument)
+fn db_target_from_config(
+    container_id: &str,
+    container_name: &str,
+    db_type: DatabaseType,
+    env: &[String],
+    cmd: &[String],
+) -> DbTarget {
+    let env: HashMap<&str, &str> = env.iter().filter_map(|e| e.split_once('=')).collect();
+    let get = |keys: &[&str]| keys.iter().find_map(|k| env.get(k).filter(|v| !v.is_empty()).map(|v| v.to_string()));
+
+    let (user, password, database) = match db_type {
+        DatabaseType::PostgreSQL => {
+            let user = get(&["POSTGRES_USER"]).unwrap_or_else(|| "postgres".to_string());
+            let database = get(&["POSTGRES_DB"]).unwrap_or_else(|| user.clone());
+            (user, get(&["POSTGRES_PASSWORD"]).unwrap_or_default(), database)
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let database = get(&["MARIADB_DATABASE", "MYSQL_DATABASE"]).unwrap_or_default();
+            match get(&["MARIADB_ROOT_PASSWORD", "MYSQL_ROOT_PASSWORD"]) {
+                Some(password) => ("root".to_string(), password, database),
+                // Sin contraseña de root (p.ej. MYSQL_RANDOM_ROOT_PASSWORD) usamos el usuario de la app
+                None => match get(&["MARIADB_USER", "MYSQL_USER"]) {
+                    Some(user) => (user, get(&["MARIADB_PASSWORD", "MYSQL_PASSWORD"]).unwrap_or_default(), database),
+                    None => ("root".to_string(), String::new(), database),
+                },
+            }
+        }
+        DatabaseType::MongoDB => (
+            get(&["MONGO_INITDB_ROOT_USERNAME"]).unwrap_or_default(),
+            get(&["MONGO_INITDB_ROOT_PASSWORD"]).unwrap_or_default(),
+            get(&["MONGO_INITDB_DATABASE"]).unwrap_or_else(|| "admin".to_string()),
+        ),
+        DatabaseType::Redis => {
+            let password = cmd
+                .iter()
+                .position(|arg| arg == "--requirepass")
+                .and_then(|i| cmd.get(i + 1).cloned())
+                .or_else(|| get(&["REDIS_PASSWORD"]))
+                .unwrap_or_default();
+            (String::new(), password, "0".to_string())
+        }
+    };
+
+    DbTarget {
+        container_id: container_id.to_string(),
+        container_name: container_name.to_string(),
+        db_type,
+        user,
+        password,
+        database,
+    }
+}
+
+/// Inspect a running container and work out how to talk to its engine
+async fn resolve_db_target(docker: &Docker, container_id: &str) -> Result<DbTarget, String> {
+    let info = docker
+        .inspect_container(container_id, None)
+        .await
+        .map_err(|e| format!("Failed to inspect container: {}", e))?;
+
+    let running = info.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+    let name = info.name.clone().unwrap_or_else(|| container_id.to_string()).trim_start_matches('/').to_string();
+    if !running {
+        return Err(format!("Container '{}' is not running", name));
+    }
+
+    let config = info.config.unwrap_or_default();
+    let db_type = detect_database_type(config.labels.as_ref(), config.image.as_deref())
+        .ok_or_else(|| format!("Container '{}' is not a supported database", name))?;
+
+    Ok(db_target_from_config(
+        info.id.as_deref().unwrap_or(container_id),
+        &name,
+        db_type,
+        &config.env.unwrap_or_default(),
+        &config.cmd.unwrap_or_default(),
+    ))
+}
+
+/// Run a command inside a container, collecting stdout/stderr and the exit code
+async fn exec_in_container(
+    docker: &Docker,
+    container_id: &str,
+    cmd: Vec<String>,
+    env: Vec<String>,
+    timeout_secs: u64,
+) -> Result<ExecOutput, String> {
+    let exec = docker
+        .create_exec(
+            container_id,
+            CreateExecOptions {
+                cmd: Some(cmd),
+                env: Some(env),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to create exec: {}", e))?;
+
+    let run = async {
+        let mut output = ExecOutput::default();
+        if let StartExecResults::Attached { output: mut stream, .. } = docker
+            .start_exec(&exec.id, None)
+            .await
+            .map_err(|e| format!("Failed to start exec: {}", e))?
+        {
+            while let Some(msg) = stream.next().await {
+                match msg {
+                    Ok(bollard::container::LogOutput::StdErr { message }) => output.stderr.push_str(&String::from_utf8_lossy(&message)),
+                    Ok(log) => output.stdout.push_str(&log.to_string()),
+                    Err(e) => return Err(format!("Exec stream error: {}", e)),
+                }
+            }
+        }
+        Ok::<ExecOutput, String>(output)
+    };
+
+    let mut output = tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), run)
+        .await
+        .map_err(|_| format!("Timeout: command did not finish within {} seconds", timeout_secs))??;
+
+    output.exit_code = docker.inspect_exec(&exec.id).await.ok().and_then(|i| i.exit_code);
+    Ok(output)
+}
+
+/// psql invocation running each script as a separate `-c` in the same session
+fn psql_command(target: &DbTarget, database: &str, scripts: &[&str]) -> Vec<String> {
+    let mut cmd: Vec<String> = ["psql", "-X", "-q", "-A", "-t", "-v", "ON_ERROR_STOP=1", "-U", &target.user, "-d", database]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    for script in scripts {
+        cmd.push("-c".to_string());
+        cmd.push(script.to_string());
+    }
+    cmd
+}
+
+/// Command line (and env) that runs `script` with the engine's own client inside the container.
+/// SQL clients print unaligned/tab-separated rows without headers; Mongo gets a JS snippet.
+fn engine_script_command(target: &DbTarget, database: Option<&str>, script: &str) -> (Vec<String>, Vec<String>) {
+    let database = database.filter(|d| !d.is_empty()).unwrap_or(&target.database).to_string();
+    let mut env = Vec::new();
+
+    let cmd: Vec<String> = match target.db_type {
+        DatabaseType::PostgreSQL => {
+            env.push(format!("PGPASSWORD={}", target.password));
+            psql_command(target, &database, &[script])
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            env.push(format!("MYSQL_PWD={}", target.password));
+            // Las imágenes de MariaDB 11 ya no incluyen el binario `mysql`
+            let client = if target.db_type == DatabaseType::MariaDB { "mariadb" } else { "mysql" };
+            let mut cmd: Vec<String> = [client, "-u", &target.user, "-N", "-B", "-r"].iter().map(|s| s.to_string()).collect();
+            if !database.is_empty() {
+                cmd.push(database);
+            }
+            cmd.extend(["-e".to_string(), script.to_string()]);
+            cmd
+        }
+        DatabaseType::MongoDB => {
+            // mongosh en imágenes >= 5.0, el shell `mongo` clásico en las anteriores
+            let mut cmd: Vec<String> = vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "if command -v mongosh >/dev/null 2>&1; then exec mongosh \"$@\"; else exec mongo \"$@\"; fi".to_string(),
+                "mongo-shell".to_string(),
+                "--quiet".to_string(),
+            ];
+            if !target.user.is_empty() {
+                cmd.extend([
+                    "-u".to_string(),
+                    target.user.clone(),
+                    "-p".to_string(),
+                    target.password.clone(),
+                    "--authenticationDatabase".to_string(),
+                    "admin".to_string(),
+                ]);
+            }
+            cmd.extend([database, "--eval".to_string(), script.to_string()]);
+            cmd
+        }
+        DatabaseType::Redis => {
+            // Para Redis `script` es una línea de comando de redis-cli
+            if !target.password.is_empty() {
+                env.push(format!("REDISCLI_AUTH={}", target.password));
+            }
+            let mut cmd = vec!["redis-cli".to_string(), "-n".to_string(), database];
+            cmd.extend(script.split_whitespace().map(|s| s.to_string()));
+            cmd
+        }
+    };
+
+    (cmd, env)
+}
+
+/// Run a query/script with the engine client and return its stdout
+async fn run_engine_script(docker: &Docker, target: &DbTarget, database: Option<&str>, script: &str) -> Result<String, String> {
+    let (cmd, env) = engine_script_command(target, database, script);
+    exec_in_container(docker, &target.container_id, cmd, env, ENGINE_EXEC_TIMEOUT_SECS)
+        .await?
+        .into_result()
+}
+
+/// Run one redis-cli command with pre-split arguments (values may contain spaces)
+async fn run_redis_command(docker: &Docker, target: &DbTarget, args: &[&str]) -> Result<String, String> {
+    run_redis_command_in_db(docker, target, None, args).await
+}
+
+/// Same as `run_redis_command` on a given logical database (`SELECT` index)
+async fn run_redis_command_in_db(docker: &Docker, target: &DbTarget, db: Option<u32>, args: &[&str]) -> Result<String, String> {
+    let db = db.map(|d| d.to_string());
+    let (mut cmd, env) = engine_script_command(target, db.as_deref(), "");
+    cmd.extend(args.iter().map(|s| s.to_string()));
+    let output = exec_in_container(docker, &target.container_id, cmd, env, ENGINE_EXEC_TIMEOUT_SECS)
+        .await?
+        .into_result()?;
+    // redis-cli devuelve código 0 incluso con errores del servidor
+    let error = ["ERR ", "(error) ", "WRONGTYPE ", "NOAUTH ", "NOPERM "]
+        .iter()
+        .find(|prefix| output.starts_with(*prefix));
+    match error {
+        Some(prefix) if *prefix == "ERR " || *prefix == "(error) " => Err(output[prefix.len()..].trim().to_string()),
+        Some(_) => Err(output.trim().to_string()),
+        None => Ok(output),
+    }
+}
+
+// ===== DATABASE METRICS =====
+
+/// Engine-level metrics read from the database itself. Fields an engine does not report stay `None`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DatabaseMetrics {
+    pub container_id: String,
+    pub container_name: String,
+    pub db_type: String,
+    pub uptime_secs: Option<f64>,
+    pub connections: Option<i64>,
+    pub max_connections: Option<i64>,
+    pub commits: Option<u64>,
+    pub rollbacks: Option<u64>,
+    /// Percentage of reads served from cache (PG buffers, InnoDB buffer pool, Redis keyspace)
+    pub cache_hit_ratio: Option<f64>,
+    pub deadlocks: Option<u64>,
+    pub slow_queries: Option<u64>,
+    /// Cumulative operation counter the rate is computed from
+    pub operations_total: Option<u64>,
+    pub ops_per_sec: Option<f64>,
+    pub memory_used_bytes: Option<u64>,
+    pub keyspace_hits: Option<u64>,
+    pub keyspace_misses: Option<u64>,
+    /// Per-operation counters (Mongo opcounters, MySQL Com_*)
+    pub op_counters: HashMap<String, u64>,
+    pub timestamp: String,
+}
+
+/// Container and engine metrics of one database
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerMetrics {
+    pub stats: ContainerStats,
+    pub database: Option<DatabaseMetrics>,
+    pub database_error: Option<String>,
+}
+
+/// Previous operation counters, used to turn them into rates
+#[derive(Default)]
+pub struct DatabaseMetricsState {
+    previous: std::sync::Mutex<HashMap<String, (i64, u64)>>,
+}
+
+fn ratio_percent(hits: u64, misses: u64) -> Option<f64> {
+    let total = hits + misses;
+    (total > 0).then(|| hits as f64 / total as f64 * 100.0)
+}
+
+const PG_METRICS_QUERY: &str = "SELECT json_build_object(\
+    'connections', (SELECT count(*) FROM pg_stat_activity WHERE backend_type = 'client backend'), \
+    'max_connections', current_setting('max_connections')::int, \
+    'xact_commit', sum(xact_commit), 'xact_rollback', sum(xact_rollback), \
+    'blks_hit', sum(blks_hit), 'blks_read', sum(blks_read), 'deadlocks', sum(deadlocks), \
+    'tup_returned', sum(tup_returned), 'tup_inserted', sum(tup_inserted), \
+    'tup_updated', sum(tup_updated), 'tup_deleted', sum(tup_deleted), \
+    'uptime', extract(epoch FROM now() - pg_postmaster_start_time())) \
+    FROM pg_stat_database";
+
+const MYSQL_METRICS_QUERY: &str = "SHOW GLOBAL STATUS; SHOW GLOBAL VARIABLES LIKE 'max_connections';";
+
+// Solo campos numéricos: el shell clásico no serializa bien NumberLong
+const MONGO_METRICS_SCRIPT: &str = "var s = db.adminCommand({serverStatus: 1}); var ops = {}; \
+    for (var k in s.opcounters) { ops[k] = Number(s.opcounters[k]); } \
+    print(JSON.stringify({uptime: Number(s.uptime), connections: Number(s.connections.current), \
+    available: Number(s.connections.available), resident_mb: Number(s.mem.resident), opcounters: ops}));";
+
+fn parse_pg_metrics(output: &str) -> Result<DatabaseMetrics, String> {
+    let value: serde_json::Value = serde_json::from_str(output.trim())
+        .map_err(|e| format!("Unexpected pg_stat_database output: {}", e))?;
+    let num = |key: &str| value.get(key).and_then(|v| v.as_f64());
+    let int = |key: &str| num(key).map(|v| v as u64);
+
+    let commits = int("xact_commit");
+    let rollbacks = int("xact_rollback");
+    let mut op_counters = HashMap::new();
+    for key in ["tup_returned", "tup_inserted", "tup_updated", "tup_deleted"] {
+        if let Some(v) = int(key) {
+            op_counters.insert(key.trim_start_matches("tup_").to_string(), v);
+        }
+    }
+
+    Ok(DatabaseMetrics {
+        uptime_secs: num("uptime"),
+        connections: num("connections").map(|v| v as i64),
+        max_connections: num("max_connections").map(|v| v as i64),
+        commits,
+        rollbacks,
+        cache_hit_ratio: ratio_percent(int("blks_hit").unwrap_or(0), int("blks_read").unwrap_or(0)),
+        deadlocks: int("deadlocks"),
+        operations_total: commits.zip(rollbacks).map(|(c, r)| c + r),
+        op_counters,
+        ..Default::default()
+    })
+}
+
+fn parse_mysql_metrics(output: &str) -> DatabaseMetrics {
+    let status: HashMap<String, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(k, v)| (k.trim().to_lowercase(), v.trim()))
+        .collect();
+    let int = |key: &str| status.get(key).and_then(|v| v.parse::<u64>().ok());
+
+    let mut op_counters = HashMap::new();
+    for op in ["select", "insert", "update", "delete"] {
+        if let Some(v) = int(&format!("com_{}", op)) {
+            op_counters.insert(op.to_string(), v);
+        }
+    }
+
+    // read_requests incluye las lecturas que fueron a disco
+    let read_requests = int("innodb_buffer_pool_read_requests").unwrap_or(0);
+    let disk_reads = int("innodb_buffer_pool_reads").unwrap_or(0);
+
+    DatabaseMetrics {
+        uptime_secs: int("uptime").map(|v| v as f64),
+        connections: int("threads_connected").map(|v| v as i64),
+        max_connections: int("max_connections").map(|v| v as i64),
+        commits: int("com_commit"),
+        rollbacks: int("com_rollback"),
+        cache_hit_ratio: ratio_percent(read_requests.saturating_sub(disk_reads), disk_reads),
+        deadlocks: int("innodb_deadlocks"),
+        slow_queries: int("slow_queries"),
+        operations_total: int("questions"),
+        op_counters,
+        ..Default::default()
+    }
+}
+
+fn parse_mongo_metrics(output: &str) -> Result<DatabaseMetrics, String> {
+    // El shell puede imprimir avisos antes del JSON
+    let line = output.lines().rev().find(|l| l.trim_start().starts_with('{')).unwrap_or(output);
+    let value: serde_json::Value = serde_json::from_str(line.trim())
+        .map_err(|e| format!("Unexpected serverStatus output: {}", e))?;
+    let num = |key: &str| value.get(key).and_then(|v| v.as_f64());
+
+    let op_counters: HashMap<String, u64> = value
+        .get("opcounters")
+        .and_then(|o| o.as_object())
+        .map(|o| o.iter().filter_map(|(k, v)| v.as_f64().map(|v| (k.clone(), v as u64))).collect())
+        .unwrap_or_default();
+    let connections = num("connections").map(|v| v as i64);
+
+    Ok(DatabaseMetrics {
+        uptime_secs: num("uptime"),
+        connections,
+        max_connections: connections.zip(num("available")).map(|(c, a)| c + a as i64),
+        operations_total: (!op_counters.is_empty()).then(|| op_counters.values().sum()),
+        memory_used_bytes: num("resident_mb").map(|mb| (mb * 1_048_576.0) as u64),
+        op_counters,
+        ..Default::default()
+    })
+}
+
+fn parse_redis_metrics(output: &str) -> DatabaseMetrics {
+    let info: HashMap<&str, &str> = output
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|l| l.trim().split_once(':'))
+        .collect();
+    let int = |key: &str| info.get(key).and_then(|v| v.parse::<u64>().ok());
+
+    let hits = int("keyspace_hits");
+    let misses = int("keyspace_misses");
+
+    DatabaseMetrics {
+        uptime_secs: int("uptime_in_seconds").map(|v| v as f64),
+        connections: int("connected_clients").map(|v| v as i64),
+        max_connections: int("maxclients").map(|v| v as i64),
+        cache_hit_ratio: ratio_percent(hits.unwrap_or(0), misses.unwrap_or(0)),
+        operations_total: int("total_commands_processed"),
+        ops_per_sec: info.get("instantaneous_ops_per_sec").and_then(|v| v.parse().ok()),
+        memory_used_bytes: int("used_memory"),
+        keyspace_hits: hits,
+        keyspace_misses: misses,
+        ..Default::default()
+    }
+}
+
+/// Query the engine of a container for its internal metrics
+async fn collect_database_metrics(docker: &Docker, metrics_state: &DatabaseMetricsState, container_id: &str) -> Result<DatabaseMetrics, String> {
+    let target = resolve_db_target(docker, container_id).await?;
+
+    let mut metrics = match target.db_type {
+        DatabaseType::PostgreSQL => parse_pg_metrics(&run_engine_script(docker, &target, None, PG_METRICS_QUERY).await?)?,
+        DatabaseType::MySQL | DatabaseType::MariaDB => parse_mysql_metrics(&run_engine_script(docker, &target, Some(""), MYSQL_METRICS_QUERY).await?),
+        DatabaseType::MongoDB => parse_mongo_metrics(&run_engine_script(docker, &target, Some("admin"), MONGO_METRICS_SCRIPT).await?)?,
+        DatabaseType::Redis => parse_redis_metrics(&run_redis_command(docker, &target, &["INFO"]).await?),
+    };
+
+    metrics.container_id = target.container_id.clone();
+    metrics.container_name = target.container_name.clone();
+    metrics.db_type = target.db_type.to_string();
+    metrics.timestamp = chrono::Utc::now().to_rfc3339();
+
+    // Tasa a partir de la lectura anterior del mismo contenedor
+    if let Some(total) = metrics.operations_total {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut previous = metrics_state.previous.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((last_ts, last_total)) = previous.insert(target.container_id.clone(), (now, total)) {
+            let elapsed = (now - last_ts) as f64 / 1000.0;
+            if elapsed > 0.0 && total >= last_total {
+                metrics.ops_per_sec = Some((total - last_total) as f64 / elapsed);
+            }
+        }
+    }
+
+    Ok(metrics)
+}
+
+/// Get engine-level metrics of a database container
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+///
+/// # Returns
+/// * `Ok(DatabaseMetrics)` - Connections, throughput, cache hit ratio, etc.
+/// * `Err(String)` - Container not running, unsupported engine or client error
+#[tauri::command]
+async fn get_database_metrics(
+    container_id: String,
+    docker_state: State<'_, AppState>,
+    db_metrics_state: State<'_, DatabaseMetricsState>,
+) -> Result<DatabaseMetrics, String> {
+    let docker = docker_state.docker();
+    collect_database_metrics(&docker, &db_metrics_state, &container_id).await
+}
+
+/// Get container stats together with engine-level metrics
+///
+/// Engine errors do not fail the call: they are returned in `database_error`.
+#[tauri::command]
+async fn get_container_metrics(
+    container_id: String,
+    docker_state: State<'_, AppState>,
+    stats_state: State<'_, StatsState>,
+    db_metrics_state: State<'_, DatabaseMetricsState>,
+) -> Result<ContainerMetrics, String> {
+    let docker = docker_state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+
+    let (stats, database) = tokio::join!(
+        latest_container_stats(&docker, &stats_state, &target.container_id, &target.container_name),
+        collect_database_metrics(&docker, &db_metrics_state, &target.container_id)
+    );
+
+    let (database, database_error) = match database {
+        Ok(metrics) => (Some(metrics), None),
+        Err(e) => (None, Some(e)),
+    };
+    Ok(ContainerMetrics { stats: stats?, database, database_error })
+}
+
+// ===== PROMETHEUS EXPORTER =====
+
+const EXPORTER_DEFAULT_PORT: u16 = 9464;
+const EXPORTER_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+// Peticiones más grandes no son un scrape de Prometheus
+const EXPORTER_MAX_REQUEST_BYTES: usize = 8 * 1024;
+const EXPORTER_REQUEST_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExporterSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ExporterSettings {
+    fn default() -> Self {
+        ExporterSettings { enabled: false, port: EXPORTER_DEFAULT_PORT }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExporterStatus {
+    pub settings: ExporterSettings,
+    pub running: bool,
+    /// Scrape URL while the endpoint is up
+    pub url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Exporter settings (stored in `exporter/settings.json`, edited from the Settings view)
+/// and the running server
+pub struct ExporterState {
+    dir: std::path::PathBuf,
+    settings: std::sync::Mutex<ExporterSettings>,
+    server: std::sync::Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    error: std::sync::Mutex<Option<String>>,
+    // Un reinicio a la vez: dos a la par se pelean por el puerto
+    restart: Mutex<()>,
+}
+
+impl ExporterState {
+    pub fn load(dir: std::path::PathBuf) -> Self {
+        let settings = read_json_file(&dir.join("settings.json")).unwrap_or_default();
+        ExporterState {
+            dir,
+            settings: std::sync::Mutex::new(settings),
+            server: std::sync::Mutex::new(None),
+            error: std::sync::Mutex::new(None),
+            restart: Mutex::new(()),
+        }
+    }
+
+    fn settings(&self) -> ExporterSettings {
+        self.settings.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set_error(&self, error: Option<String>) {
+        *self.error.lock().unwrap_or_else(|e| e.into_inner()) = error;
+    }
+
+    fn status(&self) -> ExporterStatus {
+        let settings = self.settings();
+        let running = self.server.lock().unwrap_or_else(|e| e.into_inner()).as_ref().is_some();
+        let error = self.error.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let running = running && error.is_none();
+        ExporterStatus {
+            url: running.then(|| format!("http://127.0.0.1:{}/metrics", settings.port)),
+            running,
+            error,
+            settings,
+        }
+    }
+}
+
+/// One managed database as seen by a scrape
+#[derive(Debug, Clone, Default)]
+struct ExporterEntry {
+    container_id: String,
+    container_name: String,
+    database_name: String,
+    db_type: String,
+    state: String,
+    /// healthy / unhealthy / starting, `None` without a healthcheck
+    health: Option<String>,
+    stats: Option<ContainerStats>,
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Health as reported in the container status, e.g. "Up 3 minutes (healthy)"
+fn health_from_status(status: &str) -> Option<String> {
+    ["healthy", "unhealthy", "health: starting"]
+        .iter()
+        .find(|h| status.contains(&format!("({})", h)))
+        .map(|h| h.trim_start_matches("health: ").to_string())
+}
+
+/// Render the OpenMetrics text exposition for a scrape
+fn render_open_metrics(entries: &[ExporterEntry]) -> String {
+    use std::fmt::Write;
+
+    type Getter = fn(&ContainerStats) -> f64;
+    let stats_metrics: &[(&str, &str, &str, Getter)] = &[
+        ("docker_db_cpu_percentage", "gauge", "CPU usage in percent", |s| s.cpu_percentage),
+        ("docker_db_memory_usage_bytes", "gauge", "Memory usage in bytes", |s| s.memory_usage as f64),
+        ("docker_db_memory_limit_bytes", "gauge", "Memory limit in bytes", |s| s.memory_limit as f64),
+        ("docker_db_memory_percentage", "gauge", "Memory usage in percent of the limit", |s| s.memory_percentage),
+        ("docker_db_network_rx_bytes", "counter", "Bytes received over the network", |s| s.network_rx_bytes as f64),
+        ("docker_db_network_tx_bytes", "counter", "Bytes sent over the network", |s| s.network_tx_bytes as f64),
+        ("docker_db_block_read_bytes", "counter", "Bytes read from block devices", |s| s.block_read_bytes as f64),
+        ("docker_db_block_write_bytes", "counter", "Bytes written to block devices", |s| s.block_write_bytes as f64),
+        ("docker_db_stats_timestamp_seconds", "gauge", "Time the stats sample was taken", |s| {
+            chrono::DateTime::parse_from_rfc3339(&s.timestamp).map(|t| t.timestamp() as f64).unwrap_or(0.0)
+        }),
+    ];
+
+    let labels = |e: &ExporterEntry| {
+        format!(
+            "container_id=\"{}\",container_name=\"{}\",database_name=\"{}\",db_type=\"{}\"",
+            escape_label_value(&e.container_id.chars().take(12).collect::<String>()),
+            escape_label_value(&e.container_name),
+            escape_label_value(&e.database_name),
+            escape_label_value(&e.db_type)
+        )
+    };
+
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# TYPE docker_db_up gauge\n# HELP docker_db_up Whether the container is running");
+    for e in entries {
+        let _ = writeln!(out, "docker_db_up{{{}}} {}", labels(e), if e.state == "running" { 1 } else { 0 });
+    }
+
+    let _ = writeln!(out, "# TYPE docker_db_state stateset\n# HELP docker_db_state Container state");
+    for e in entries {
+        for state in ["created", "running", "paused", "restarting", "exited", "dead"] {
+            let _ = writeln!(out, "docker_db_state{{{},docker_db_state=\"{}\"}} {}", labels(e), state, if e.state == state { 1 } else { 0 });
+        }
+    }
+
+    let _ = writeln!(out, "# TYPE docker_db_health stateset\n# HELP docker_db_health Healthcheck status (only containers with a healthcheck)");
+    for e in entries {
+        if let Some(health) = &e.health {
+            for status in ["healthy", "unhealthy", "starting"] {
+                let _ = writeln!(out, "docker_db_health{{{},docker_db_health=\"{}\"}} {}", labels(e), status, if health == status { 1 } else { 0 });
+            }
+        }
+    }
+
+    for (name, kind, help, get) in stats_metrics {
+        let _ = writeln!(out, "# TYPE {} {}\n# HELP {} {}", name, kind, name, help);
+        // En OpenMetrics las muestras de un counter llevan el sufijo _total
+        let suffix = if *kind == "counter" { "_total" } else { "" };
+        for e in entries {
+            if let Some(stats) = &e.stats {
+                let _ = writeln!(out, "{}{}{{{}}} {}", name, suffix, labels(e), get(stats));
+            }
+        }
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+/// Current state and stats of every managed database container
+async fn collect_exporter_entries(docker: &Docker, stats_state: &StatsState) -> Result<Vec<ExporterEntry>, String> {
+    let containers = docker
+        .list_containers(Some(ListContainersOptions::<String> { all: true, ..Default::default() }))
+        .await
+        .map_err(|e| format!("Failed to list containers: {}", e))?;
+
+    let stats: HashMap<String, ContainerStats> = collect_all_container_stats(docker, stats_state)
+        .await?
+        .into_iter()
+        .map(|s| (s.container_id.clone(), s))
+        .collect();
+
+    let mut entries: Vec<ExporterEntry> = containers
+        .into_iter()
+        .filter_map(|c| {
+            let db_type = detect_database_type(c.labels.as_ref(), c.image.as_deref())?;
+            let id = c.id?;
+            Some(ExporterEntry {
+                container_name: c.names
+                    .and_then(|n| n.first().map(|s| s.trim_start_matches('/').to_string()))
+                    .unwrap_or_else(|| id.clone()),
+                database_name: c.labels.as_ref().and_then(|l| l.get("database_name")).cloned().unwrap_or_default(),
+                db_type: db_type.to_string(),
+                state: c.state.unwrap_or_else(|| "unknown".to_string()),
+                health: c.status.as_deref().and_then(health_from_status),
+                stats: stats.get(&id).cloned(),
+                container_id: id,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.container_name.cmp(&b.container_name));
+
+    Ok(entries)
+}
+
+/// Answer one HTTP request on the exporter socket
+async fn handle_exporter_request(app: &tauri::AppHandle, mut stream: tokio::net::TcpStream) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    let read_headers = async {
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < EXPORTER_MAX_REQUEST_BYTES {
+            match stream.read(&mut buffer).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => request.extend_from_slice(&buffer[..n]),
+            }
+        }
+    };
+    if tokio::time::timeout(std::time::Duration::from_secs(EXPORTER_REQUEST_TIMEOUT_SECS), read_headers).await.is_err() {
+        return;
+    }
+
+    let request_line = String::from_utf8_lossy(&request).lines().next().unwrap_or_default().to_string();
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+    let path = path.split('?').next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            let docker = app.state::<AppState>().docker();
+            let stats_state = app.state::<StatsState>();
+            match collect_exporter_entries(&docker, &stats_state).await {
+                Ok(entries) => ("200 OK", EXPORTER_CONTENT_TYPE, render_open_metrics(&entries)),
+                Err(e) => ("503 Service Unavailable", "text/plain; charset=utf-8", format!("{}\n", e)),
+            }
+        }
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found. Metrics are served at /metrics\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Method not allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Stop the exporter and start it again with the current settings (if enabled)
+async fn restart_exporter(app: &tauri::AppHandle) {
+    let exporter = app.state::<ExporterState>();
+    let _restart = exporter.restart.lock().await;
+    restart_exporter_locked(app, &exporter).await;
+}
+
+async fn restart_exporter_locked(app: &tauri::AppHandle, exporter: &ExporterState) {
+    if let Some(server) = exporter.server.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        server.abort();
+    }
+    exporter.set_error(None);
+
+    let settings = exporter.settings();
+    if !settings.enabled {
+        return;
+    }
+
+    // Solo loopback: las métricas no deben quedar expuestas en la red
+    let listener = match tokio::net::TcpListener::bind(("127.0.0.1", settings.port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            let message = format!("Failed to bind 127.0.0.1:{}: {}", settings.port, e);
+            eprintln!("[Exporter] {}", message);
+            exporter.set_error(Some(message));
+            return;
+        }
+    };
+    println!("📈 Prometheus exporter listening on http://127.0.0.1:{}/metrics", settings.port);
+
+    let handle = app.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = handle.clone();
+                    tauri::async_runtime::spawn(async move { handle_exporter_request(&app, stream).await });
+                }
+                Err(e) => eprintln!("[Exporter] Accept failed: {}", e),
+            }
+        }
+    });
+    *exporter.server.lock().unwrap_or_else(|e| e.into_inner()) = Some(server);
+}
+
+/// Get exporter settings and whether the endpoint is up
+#[tauri::command]
+async fn get_exporter_settings(exporter_state: State<'_, ExporterState>) -> Result<ExporterStatus, String> {
+    Ok(exporter_state.status())
+}
+
+/// Enable/disable the `/metrics` endpoint or change its port
+///
+/// # Arguments
+/// * `settings` - New exporter settings
+///
+/// # Returns
+/// * `Ok(ExporterStatus)` - Status after applying the settings (bind errors are reported in `error`)
+/// * `Err(String)` - Invalid settings or storage error
+#[tauri::command]
+async fn update_exporter_settings(
+    settings: ExporterSettings,
+    app: tauri::AppHandle,
+    exporter_state: State<'_, ExporterState>,
+) -> Result<ExporterStatus, String> {
+    if settings.port < 1024 {
+        return Err("Exporter port must be 1024 or higher".to_string());
+    }
+
+    // Guardar y reiniciar bajo el mismo lock para que gane la última configuración
+    let _restart = exporter_state.restart.lock().await;
+    write_json_file(&exporter_state.dir.join("settings.json"), &settings)?;
+    *exporter_state.settings.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+
+    restart_exporter_locked(&app, &exporter_state).await;
+    Ok(exporter_state.status())
+}
+
+// ===== ACTIVE SESSIONS =====
+
+/// A client session (or running operation, for MongoDB) on a database engine
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DbSession {
+    /// PID (PostgreSQL), thread ID (MySQL), opid (MongoDB) or client ID (Redis)
+    pub id: String,
+    pub user: Option<String>,
+    pub database: Option<String>,
+    pub client: Option<String>,
+    pub application: Option<String>,
+    pub state: Option<String>,
+    /// Seconds since the current query (or the session) started
+    pub duration_secs: Option<f64>,
+    pub query: Option<String>,
+    pub wait_event: Option<String>,
+}
+
+// Todas las consultas devuelven un array JSON con los campos de DbSession, en una sola línea
+// (json_agg de una fila compuesta separa los elementos con saltos de línea; row_to_json no)
+const PG_SESSIONS_QUERY: &str = "SELECT coalesce(json_agg(row_to_json(s) ORDER BY s.duration_secs DESC NULLS LAST), '[]') FROM (\
+    SELECT pid::text AS id, usename AS \"user\", datname AS database, client_addr::text AS client, \
+    application_name AS application, state, \
+    extract(epoch FROM now() - coalesce(query_start, backend_start))::float8 AS duration_secs, \
+    query, wait_event_type || ': ' || wait_event AS wait_event \
+    FROM pg_stat_activity WHERE backend_type = 'client backend' AND pid <> pg_backend_pid()) s";
+
+const MYSQL_SESSIONS_QUERY: &str = "SELECT JSON_ARRAYAGG(JSON_OBJECT(\
+    'id', CAST(ID AS CHAR), 'user', USER, 'database', DB, 'client', HOST, \
+    'state', COALESCE(NULLIF(STATE, ''), COMMAND), 'duration_secs', TIME, 'query', INFO, 'wait_event', COMMAND)) \
+    FROM information_schema.PROCESSLIST WHERE ID <> CONNECTION_ID()";
+
+const MONGO_SESSIONS_SCRIPT: &str = "var ops = db.currentOp({active: true}).inprog || []; \
+    print(JSON.stringify(ops.filter(function (op) { return !(op.command && op.command.currentOp); }).map(function (op) { \
+        var users = op.effectiveUsers || []; \
+        return {id: String(op.opid), user: users.length ? users[0].user : null, \
+            database: op.ns ? op.ns.split('.')[0] : null, client: op.client || null, \
+            application: op.appName || op.desc || null, state: op.op, \
+            duration_secs: op.microsecs_running !== undefined ? Number(op.microsecs_running) / 1e6 : null, \
+            query: op.command ? JSON.stringify(op.command) : null, \
+            wait_event: op.waitingForLock ? 'waiting for lock' : null}; })));";
+
+fn parse_sessions_json(output: &str) -> Result<Vec<DbSession>, String> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    // JSON_ARRAYAGG devuelve NULL si no hay filas
+    if line.is_empty() || line == "NULL" {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(line).map_err(|e| format!("Unexpected sessions output: {}", e))
+}
+
+/// Parse `CLIENT LIST`: one `key=value key=value ...` line per connection
+fn parse_redis_client_list(output: &str) -> Vec<DbSession> {
+    output
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let fields: HashMap<&str, &str> = line.split_whitespace().filter_map(|f| f.split_once('=')).collect();
+            let get = |key: &str| fields.get(key).filter(|v| !v.is_empty()).map(|v| v.to_string());
+            let idle = fields.get("idle").and_then(|v| v.parse::<f64>().ok());
+            let blocked = fields.get("flags").is_some_and(|f| f.contains('b'));
+            DbSession {
+                id: get("id").unwrap_or_default(),
+                user: get("user"),
+                database: get("db"),
+                client: get("addr"),
+                application: get("name"),
+                state: Some(if blocked { "blocked" } else if idle.unwrap_or(0.0) > 0.0 { "idle" } else { "active" }.to_string()),
+                duration_secs: if blocked || idle.unwrap_or(0.0) > 0.0 { idle } else { fields.get("age").and_then(|v| v.parse().ok()) },
+                query: get("cmd"),
+                wait_event: blocked.then(|| "blocking command".to_string()),
+            }
+        })
+        // La propia conexión de redis-cli
+        .filter(|s| !matches!(s.query.as_deref(), Some("client|list") | Some("client")))
+        .collect()
+}
+
+/// Validate a session ID before interpolating it into a command
+fn validate_session_id(db_type: &DatabaseType, session_id: &str) -> Result<(), String> {
+    let valid = match db_type {
+        // opid puede ser "shard:1234" en clusters
+        DatabaseType::MongoDB => !session_id.is_empty() && session_id.chars().all(|c| c.is_ascii_alphanumeric() || c == ':' || c == '-' || c == '_'),
+        _ => !session_id.is_empty() && session_id.chars().all(|c| c.is_ascii_digit()),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid session ID '{}'", session_id))
+    }
+}
+
+/// List sessions of a container's engine, longest running first
+async fn list_db_sessions(docker: &Docker, target: &DbTarget) -> Result<Vec<DbSession>, String> {
+    match target.db_type {
+        DatabaseType::PostgreSQL => parse_sessions_json(&run_engine_script(docker, target, None, PG_SESSIONS_QUERY).await?),
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let mut sessions = parse_sessions_json(&run_engine_script(docker, target, Some(""), MYSQL_SESSIONS_QUERY).await?)?;
+            sessions.sort_by(|a, b| b.duration_secs.unwrap_or(0.0).total_cmp(&a.duration_secs.unwrap_or(0.0)));
+            Ok(sessions)
+        }
+        DatabaseType::MongoDB => parse_sessions_json(&run_engine_script(docker, target, Some("admin"), MONGO_SESSIONS_SCRIPT).await?),
+        DatabaseType::Redis => Ok(parse_redis_client_list(&run_redis_command(docker, target, &["CLIENT", "LIST"]).await?)),
+    }
+}
+
+/// Cancel the running query of a session, or kill the whole session
+async fn stop_db_session(docker: &Docker, target: &DbTarget, session_id: &str, terminate: bool) -> Result<String, String> {
+    validate_session_id(&target.db_type, session_id)?;
+
+    match target.db_type {
+        DatabaseType::PostgreSQL => {
+            let function = if terminate { "pg_terminate_backend" } else { "pg_cancel_backend" };
+            let output = run_engine_script(docker, target, None, &format!("SELECT {}({})", function, session_id)).await?;
+            if output.trim() == "t" {
+                Ok(format!("Signal sent to backend {}", session_id))
+            } else {
+                Err(format!("Backend {} not found", session_id))
+            }
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let statement = if terminate { format!("KILL {}", session_id) } else { format!("KILL QUERY {}", session_id) };
+            run_engine_script(docker, target, Some(""), &statement).await?;
+            Ok(format!("{} executed", statement))
+        }
+        DatabaseType::MongoDB => {
+            // MongoDB no permite cerrar conexiones ajenas: en ambos casos se mata la operación
+            let opid = if session_id.chars().all(|c| c.is_ascii_digit()) { session_id.to_string() } else { format!("'{}'", session_id) };
+            let output = run_engine_script(docker, target, Some("admin"), &format!("printjson(db.killOp({}))", opid)).await?;
+            if output.contains("\"ok\" : 1") || output.contains("ok: 1") {
+                Ok(format!("Operation {} killed", session_id))
+            } else {
+                Err(format!("killOp failed: {}", output.trim()))
+            }
+        }
+        DatabaseType::Redis => {
+            let args: &[&str] = if terminate { &["CLIENT", "KILL", "ID", session_id] } else { &["CLIENT", "UNBLOCK", session_id] };
+            let output = run_redis_command(docker, target, args).await?;
+            match output.trim() {
+                "1" | "OK" => Ok(format!("Client {} {}", session_id, if terminate { "killed" } else { "unblocked" })),
+                _ if !terminate => Err(format!("Client {} is not blocked; Redis can only interrupt blocking commands", session_id)),
+                other => Err(format!("Client {} not found ({})", session_id, other)),
+            }
+        }
+    }
+}
+
+/// List active sessions of a database container
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+///
+/// # Returns
+/// * `Ok(Vec<DbSession>)` - Sessions with user, database, state, duration and query text
+/// * `Err(String)` - Container not running, unsupported engine or client error
+#[tauri::command]
+async fn list_sessions(container_id: String, state: State<'_, AppState>) -> Result<Vec<DbSession>, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    list_db_sessions(&docker, &target).await
+}
+
+/// Cancel the query a session is running, keeping the connection open
+///
+/// On Redis only clients blocked in commands like `BLPOP` can be interrupted.
+#[tauri::command]
+async fn cancel_session_query(container_id: String, session_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    println!("🛑 Cancelando consulta de la sesión {} en {}", session_id, target.container_name);
+    stop_db_session(&docker, &target, &session_id, false).await
+}
+
+/// Kill a session (connection). On MongoDB this kills the operation.
+#[tauri::command]
+async fn terminate_session(container_id: String, session_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    println!("💀 Terminando sesión {} en {}", session_id, target.container_name);
+    stop_db_session(&docker, &target, &session_id, true).await
+}
+
+// ===== LOCK INSPECTOR =====
+
+/// "`waiting` waits for a lock held by `blocking`"
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+struct LockWaitEdge {
+    waiting: String,
+    blocking: String,
+    lock_mode: Option<String>,
+    relation: Option<String>,
+    wait_secs: Option<f64>,
+}
+
+/// A session in the blocking tree; `blocked` are the sessions waiting on it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockingNode {
+    pub session: DbSession,
+    /// Lock requested from the parent node (None for roots)
+    pub lock_mode: Option<String>,
+    pub relation: Option<String>,
+    pub wait_secs: Option<f64>,
+    pub blocked: Vec<BlockingNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LockGraph {
+    /// Root blockers: sessions that block others without waiting themselves
+    pub roots: Vec<BlockingNode>,
+    pub waiting_sessions: usize,
+    /// A wait cycle was found (its lowest session ID is reported as root)
+    pub deadlock: bool,
+}
+
+// row_to_json mantiene el array en una línea aunque haya varias esperas
+const PG_LOCK_WAITS_QUERY: &str = "SELECT coalesce(json_agg(row_to_json(e)), '[]') FROM (\
+    SELECT a.pid::text AS waiting, b::text AS blocking, l.mode AS lock_mode, \
+    coalesce(l.relation::regclass::text, l.locktype) AS relation, \
+    extract(epoch FROM now() - coalesce(a.query_start, a.backend_start))::float8 AS wait_secs \
+    FROM pg_stat_activity a CROSS JOIN LATERAL unnest(pg_blocking_pids(a.pid)) b \
+    LEFT JOIN LATERAL (SELECT mode, relation, locktype FROM pg_locks WHERE pid = a.pid AND NOT granted LIMIT 1) l ON true) e";
+
+const MYSQL_LOCK_WAITS_QUERY: &str = "SELECT JSON_ARRAYAGG(JSON_OBJECT(\
+    'waiting', CAST(wt.PROCESSLIST_ID AS CHAR), 'blocking', CAST(bt.PROCESSLIST_ID AS CHAR), \
+    'lock_mode', wl.LOCK_MODE, \
+    'relation', CONCAT(wl.OBJECT_SCHEMA, '.', wl.OBJECT_NAME, IFNULL(CONCAT(' (', wl.INDEX_NAME, ')'), '')), \
+    'wait_secs', wt.PROCESSLIST_TIME)) \
+    FROM performance_schema.data_lock_waits w \
+    JOIN performance_schema.data_locks wl ON wl.ENGINE_LOCK_ID = w.REQUESTING_ENGINE_LOCK_ID \
+    JOIN performance_schema.threads wt ON wt.THREAD_ID = w.REQUESTING_THREAD_ID \
+    JOIN performance_schema.threads bt ON bt.THREAD_ID = w.BLOCKING_THREAD_ID";
+
+// MariaDB no tiene data_lock_waits; sigue exponiendo las tablas INNODB_* de information_schema
+const MARIADB_LOCK_WAITS_QUERY: &str = "SELECT JSON_ARRAYAGG(JSON_OBJECT(\
+    'waiting', CAST(r.trx_mysql_thread_id AS CHAR), 'blocking', CAST(b.trx_mysql_thread_id AS CHAR), \
+    'lock_mode', l.lock_mode, 'relation', CONCAT(l.lock_table, IFNULL(CONCAT(' (', l.lock_index, ')'), '')), \
+    'wait_secs', TIMESTAMPDIFF(SECOND, r.trx_wait_started, NOW()))) \
+    FROM information_schema.INNODB_LOCK_WAITS w \
+    JOIN information_schema.INNODB_TRX r ON r.trx_id = w.requesting_trx_id \
+    JOIN information_schema.INNODB_TRX b ON b.trx_id = w.blocking_trx_id \
+    LEFT JOIN information_schema.INNODB_LOCKS l ON l.lock_id = w.requested_lock_id";
+
+/// Build the blocking forest from wait edges
+fn build_blocking_tree(sessions: &[DbSession], edges: &[LockWaitEdge]) -> LockGraph {
+    let by_id: HashMap<&str, &DbSession> = sessions.iter().map(|s| (s.id.as_str(), s)).collect();
+    let session = |id: &str| by_id.get(id).map(|s| (*s).clone()).unwrap_or_else(|| DbSession { id: id.to_string(), ..Default::default() });
+
+    let mut waiters: HashMap<&str, Vec<&LockWaitEdge>> = HashMap::new();
+    let mut waiting: std::collections::HashSet<&str> = std::collections::HashSet::new();
+    for edge in edges {
+        waiters.entry(edge.blocking.as_str()).or_default().push(edge);
+        waiting.insert(edge.waiting.as_str());
+    }
+
+    fn build<'a>(
+        id: &'a str,
+        waiters: &HashMap<&'a str, Vec<&'a LockWaitEdge>>,
+        visited: &mut std::collections::HashSet<&'a str>,
+        session: &dyn Fn(&str) -> DbSession,
+    ) -> Vec<BlockingNode> {
+        visited.insert(id);
+        let mut children = Vec::new();
+        for edge in waiters.get(id).into_iter().flatten() {
+            // Cada sesión aparece una sola vez: esto corta los ciclos y evita
+            // repetir el subárbol de quien espera a varios bloqueadores
+            if !visited.insert(edge.waiting.as_str()) {
+                continue;
+            }
+            children.push(BlockingNode {
+                session: session(&edge.waiting),
+                lock_mode: edge.lock_mode.clone(),
+                relation: edge.relation.clone(),
+                wait_secs: edge.wait_secs,
+                blocked: build(&edge.waiting, waiters, visited, session),
+            });
+        }
+        children.sort_by(|a, b| b.wait_secs.unwrap_or(0.0).total_cmp(&a.wait_secs.unwrap_or(0.0)));
+        children
+    }
+
+    let mut blockers: Vec<&str> = waiters.keys().copied().collect();
+    blockers.sort_by_key(|id| (id.parse::<u64>().unwrap_or(u64::MAX), id.to_string()));
+
+    let mut visited = std::collections::HashSet::new();
+    let mut roots = Vec::new();
+
+    for id in blockers.iter().copied().filter(|id| !waiting.contains(id)) {
+        let blocked = build(id, &waiters, &mut visited, &session);
+        roots.push(BlockingNode { session: session(id), lock_mode: None, relation: None, wait_secs: None, blocked });
+    }
+
+    // Lo que queda sin visitar solo puede formar parte de ciclos
+    let mut deadlock = false;
+    for id in blockers.iter().copied() {
+        if !visited.contains(id) {
+            deadlock = true;
+            let blocked = build(id, &waiters, &mut visited, &session);
+            roots.push(BlockingNode { session: session(id), lock_mode: None, relation: None, wait_secs: None, blocked });
+        }
+    }
+
+    LockGraph { roots, waiting_sessions: waiting.len(), deadlock }
+}
+
+fn parse_lock_waits_json(output: &str) -> Result<Vec<LockWaitEdge>, String> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if line.is_empty() || line == "NULL" {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(line).map_err(|e| format!("Unexpected lock waits output: {}", e))
+}
+
+/// Get the blocking graph of a PostgreSQL, MySQL 8 or MariaDB container
+///
+/// Terminate a root blocker with `terminate_session` to release everything below it.
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+///
+/// # Returns
+/// * `Ok(LockGraph)` - Root blockers with the sessions waiting on them as children
+/// * `Err(String)` - Unsupported engine or client error
+#[tauri::command]
+async fn get_lock_graph(container_id: String, state: State<'_, AppState>) -> Result<LockGraph, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+
+    let edges = match target.db_type {
+        DatabaseType::PostgreSQL => run_engine_script(&docker, &target, None, PG_LOCK_WAITS_QUERY).await?,
+        DatabaseType::MySQL => run_engine_script(&docker, &target, Some(""), MYSQL_LOCK_WAITS_QUERY)
+            .await
+            .map_err(|e| format!("Lock inspection requires MySQL 8 with performance_schema enabled: {}", e))?,
+        DatabaseType::MariaDB => run_engine_script(&docker, &target, Some(""), MARIADB_LOCK_WAITS_QUERY).await?,
+        DatabaseType::MongoDB | DatabaseType::Redis => {
+            return Err(format!("Lock inspection is not available for {}", target.db_type.to_string()));
+        }
+    };
+    let edges = parse_lock_waits_json(&edges)?;
+    if edges.is_empty() {
+        return Ok(LockGraph { roots: Vec::new(), waiting_sessions: 0, deadlock: false });
+    }
+
+    let sessions = list_db_sessions(&docker, &target).await?;
+    Ok(build_blocking_tree(&sessions, &edges))
+}
+
+// ===== QUERY INSIGHTS =====
+
+const PG_STAT_STATEMENTS_PRELOAD: &str = "shared_preload_libraries=pg_stat_statements";
+const PG_STAT_STATEMENTS_TRACK: &str = "pg_stat_statements.track=all";
+const MARIADB_PERFORMANCE_SCHEMA: &str = "--performance-schema=ON";
+const QUERY_INSIGHTS_DEFAULT_LIMIT: u32 = 20;
+
+fn supports_query_insights(db_type: &DatabaseType) -> bool {
+    matches!(db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB)
+}
+
+/// Container command with statement statistics switched on or off.
+/// MySQL 8 has performance_schema on by default, so its command is left alone.
+fn query_insights_cmd(db_type: &DatabaseType, current: &[String], enabled: bool) -> Vec<String> {
+    match db_type {
+        DatabaseType::PostgreSQL => {
+            let mut cmd: Vec<String> = Vec::new();
+            let mut args = current.iter().peekable();
+            while let Some(arg) = args.next() {
+                if arg == "-c" && args.peek().is_some_and(|v| v.as_str() == PG_STAT_STATEMENTS_PRELOAD || v.as_str() == PG_STAT_STATEMENTS_TRACK) {
+                    args.next();
+                    continue;
+                }
+                cmd.push(arg.clone());
+            }
+            if cmd.is_empty() {
+                cmd.push("postgres".to_string());
+            }
+            if enabled {
+                cmd.extend(["-c", PG_STAT_STATEMENTS_PRELOAD, "-c", PG_STAT_STATEMENTS_TRACK].iter().map(|s| s.to_string()));
+            }
+            cmd
+        }
+        DatabaseType::MariaDB => {
+            let mut cmd: Vec<String> = current.iter().filter(|a| a.as_str() != MARIADB_PERFORMANCE_SCHEMA).cloned().collect();
+            if enabled {
+                cmd.push(MARIADB_PERFORMANCE_SCHEMA.to_string());
+            }
+            cmd
+        }
+        _ => current.to_vec(),
+    }
+}
+
+/// Ordering of the top queries report
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryStatsOrder {
+    #[default]
+    TotalTime,
+    Calls,
+    MeanTime,
+    Rows,
+}
+
+/// Aggregated statistics of one normalized statement
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QueryStat {
+    pub query: String,
+    pub database: Option<String>,
+    pub user: Option<String>,
+    pub calls: u64,
+    pub total_time_ms: f64,
+    pub mean_time_ms: f64,
+    pub rows: u64,
+}
+
+impl QueryStatsOrder {
+    fn key(&self, stat: &QueryStat) -> f64 {
+        match self {
+            QueryStatsOrder::TotalTime => stat.total_time_ms,
+            QueryStatsOrder::Calls => stat.calls as f64,
+            QueryStatsOrder::MeanTime => stat.mean_time_ms,
+            QueryStatsOrder::Rows => stat.rows as f64,
+        }
+    }
+}
+
+fn parse_query_stats_json(output: &str) -> Result<Vec<QueryStat>, String> {
+    let line = output.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if line.is_empty() || line == "NULL" {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(line).map_err(|e| format!("Unexpected statement statistics output: {}", e))
+}
+
+/// Top N statements by the given order (sorted here: JSON_ARRAYAGG does not keep row order)
+fn top_query_stats(mut stats: Vec<QueryStat>, order: QueryStatsOrder, limit: usize) -> Vec<QueryStat> {
+    stats.sort_by(|a, b| order.key(b).total_cmp(&order.key(a)));
+    stats.truncate(limit);
+    stats
+}
+
+fn pg_top_queries_query(order: QueryStatsOrder, limit: u32) -> String {
+    let column = match order {
+        QueryStatsOrder::TotalTime => "total_time_ms",
+        QueryStatsOrder::Calls => "calls",
+        QueryStatsOrder::MeanTime => "mean_time_ms",
+        QueryStatsOrder::Rows => "rows",
+    };
+    // total_exec_time desde PG 13, total_time antes; row_to_json mantiene el array en una línea
+    format!(
+        "SELECT coalesce(json_agg(row_to_json(t)), '[]') FROM (\
+         SELECT s.query, d.datname AS database, r.rolname AS \"user\", s.calls, x.total AS total_time_ms, \
+         x.total / greatest(s.calls, 1) AS mean_time_ms, s.rows \
+         FROM pg_stat_statements s \
+         CROSS JOIN LATERAL (SELECT coalesce(to_jsonb(s)->>'total_exec_time', to_jsonb(s)->>'total_time')::float8 AS total) x \
+         LEFT JOIN pg_database d ON d.oid = s.dbid LEFT JOIN pg_roles r ON r.oid = s.userid \
+         ORDER BY {} DESC LIMIT {}) t",
+        column, limit
+    )
+}
+
+fn mysql_top_queries_query(order: QueryStatsOrder, limit: u32) -> String {
+    let column = match order {
+        QueryStatsOrder::TotalTime => "SUM_TIMER_WAIT",
+        QueryStatsOrder::Calls => "COUNT_STAR",
+        QueryStatsOrder::MeanTime => "AVG_TIMER_WAIT",
+        QueryStatsOrder::Rows => "SUM_ROWS_SENT + SUM_ROWS_AFFECTED",
+    };
+    // Los timers de performance_schema están en picosegundos
+    format!(
+        "SELECT JSON_ARRAYAGG(JSON_OBJECT('query', DIGEST_TEXT, 'database', SCHEMA_NAME, 'calls', COUNT_STAR, \
+         'total_time_ms', SUM_TIMER_WAIT / 1e9, 'mean_time_ms', AVG_TIMER_WAIT / 1e9, \
+         'rows', SUM_ROWS_SENT + SUM_ROWS_AFFECTED)) FROM (\
+         SELECT * FROM performance_schema.events_statements_summary_by_digest \
+         WHERE DIGEST_TEXT IS NOT NULL ORDER BY {} DESC LIMIT {}) t",
+        column, limit
+    )
+}
+
+/// Make sure statement statistics can be read, creating the PG extension if needed
+async fn ensure_query_insights(docker: &Docker, target: &DbTarget) -> Result<(), String> {
+    match target.db_type {
+        DatabaseType::PostgreSQL => {
+            let preload = run_engine_script(docker, target, None, "SHOW shared_preload_libraries").await?;
+            if !preload.contains("pg_stat_statements") {
+                return Err("Query insights are not enabled for this container. Enable them with set_query_insights (the container will be recreated).".to_string());
+            }
+            run_engine_script(docker, target, None, "CREATE EXTENSION IF NOT EXISTS pg_stat_statements").await?;
+            Ok(())
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let enabled = run_engine_script(docker, target, Some(""), "SELECT @@performance_schema").await?;
+            if enabled.trim() != "1" {
+                return Err("performance_schema is disabled. Enable query insights with set_query_insights (the container will be recreated).".to_string());
+            }
+            Ok(())
+        }
+        _ => Err(format!("Query insights are not available for {}", target.db_type.to_string())),
+    }
+}
+
+/// Get the most expensive statements of a PostgreSQL, MySQL or MariaDB container
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+/// * `order_by` - `total_time` (default), `calls`, `mean_time` or `rows`
+/// * `limit` - Number of statements (default: 20)
+///
+/// # Returns
+/// * `Ok(Vec<QueryStat>)` - Statements, most expensive first
+/// * `Err(String)` - Insights disabled, unsupported engine or client error
+#[tauri::command]
+async fn get_top_queries(
+    container_id: String,
+    order_by: Option<QueryStatsOrder>,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueryStat>, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    ensure_query_insights(&docker, &target).await?;
+
+    let order = order_by.unwrap_or_default();
+    let limit = limit.unwrap_or(QUERY_INSIGHTS_DEFAULT_LIMIT).clamp(1, 500);
+
+    let output = match target.db_type {
+        DatabaseType::PostgreSQL => run_engine_script(&docker, &target, None, &pg_top_queries_query(order, limit)).await?,
+        _ => run_engine_script(&docker, &target, Some(""), &mysql_top_queries_query(order, limit)).await?,
+    };
+
+    Ok(top_query_stats(parse_query_stats_json(&output)?, order, limit as usize))
+}
+
+/// Reset statement statistics (e.g. between test runs)
+#[tauri::command]
+async fn reset_query_stats(container_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    ensure_query_insights(&docker, &target).await?;
+
+    match target.db_type {
+        DatabaseType::PostgreSQL => run_engine_script(&docker, &target, None, "SELECT pg_stat_statements_reset()").await?,
+        _ => run_engine_script(&docker, &target, Some(""), "TRUNCATE TABLE performance_schema.events_statements_summary_by_digest").await?,
+    };
+
+    println!("🧹 Estadísticas de consultas reiniciadas en {}", target.container_name);
+    Ok("Query statistics reset".to_string())
+}
+
+/// Endpoint de red para recrear un contenedor: conserva la configuración
+/// (IP fija, alias, links) y descarta lo que Docker asigna en tiempo de ejecución
+fn recreated_endpoint(endpoint: &bollard::models::EndpointSettings, old_id: &str) -> bollard::models::EndpointSettings {
+    bollard::models::EndpointSettings {
+        ipam_config: endpoint.ipam_config.clone(),
+        links: endpoint.links.clone(),
+        // Docker añade el ID corto del contenedor viejo como alias
+        aliases: endpoint.aliases.as_ref().map(|aliases| aliases.iter().filter(|a| !old_id.starts_with(a.as_str())).cloned().collect()),
+        driver_opts: endpoint.driver_opts.clone(),
+        ..Default::default()
+    }
+}
+
+/// Enable or disable query insights on an existing container
+///
+/// PostgreSQL needs `pg_stat_statements` in `shared_preload_libraries` and MariaDB needs
+/// `performance_schema`, both startup options, so the container is recreated with the same
+/// configuration, volumes and networks. MySQL 8 has it on by default and is left untouched.
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+/// * `enabled` - Whether statement statistics should be collected
+///
+/// # Returns
+/// * `Ok(String)` - Success message
+/// * `Err(String)` - Unsupported engine or Docker error
+#[tauri::command]
+async fn set_query_insights(container_id: String, enabled: bool, state: State<'_, AppState>) -> Result<String, String> {
+    let docker = state.docker();
+    let _guard = state.lock_container(&container_id).await;
+
+    let info = docker
+        .inspect_container(&container_id, None)
+        .await
+        .map_err(|e| format!("Error inspecting container: {}", e))?;
+    let container_config = info.config.clone().ok_or("Container config not found")?;
+    let db_type = detect_database_type(container_config.labels.as_ref(), container_config.image.as_deref())
+        .filter(supports_query_insights)
+        .ok_or("Query insights are only available for PostgreSQL, MySQL and MariaDB")?;
+
+    if db_type == DatabaseType::MySQL {
+        return Ok("MySQL collects statement statistics by default (performance_schema)".to_string());
+    }
+
+    let current_cmd = container_config.cmd.clone().unwrap_or_default();
+    let new_cmd = query_insights_cmd(&db_type, &current_cmd, enabled);
+    if new_cmd == current_cmd {
+        return Ok(format!("Query insights already {}", if enabled { "enabled" } else { "disabled" }));
+    }
+
+    let container_name = info.name.clone().ok_or("Container name not found")?.trim_start_matches('/').to_string();
+    let was_running = info.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+    println!("🔧 {} query insights en {} (recreando contenedor)", if enabled { "Activando" } else { "Desactivando" }, container_name);
+
+    // Los volúmenes anónimos (p.ej. /var/lib/postgresql/data) se montan por nombre para conservar los datos
+    let mut host_config = info.host_config.clone().unwrap_or_default();
+    let mut binds = host_config.binds.clone().unwrap_or_default();
+    for mount in info.mounts.iter().flatten() {
+        if mount.typ != Some(bollard::models::MountPointTypeEnum::VOLUME) {
+            continue;
+        }
+        if let (Some(name), Some(destination)) = (&mount.name, &mount.destination) {
+            if !binds.iter().any(|b| b.split(':').nth(1) == Some(destination.as_str())) {
+                binds.push(format!("{}:{}", name, destination));
+            }
+        }
+    }
+    host_config.binds = Some(binds);
+
+    let mut labels = container_config.labels.clone().unwrap_or_default();
+    if enabled {
+        labels.insert("query_insights".to_string(), "true".to_string());
+    } else {
+        labels.remove("query_insights");
+    }
+
+    // Las redes se reconectan tal cual: la principal al crear y el resto después
+    let network_mode = host_config.network_mode.clone().unwrap_or_default();
+    let primary_network = if network_mode.is_empty() || network_mode == "default" { "bridge".to_string() } else { network_mode.clone() };
+    let attachable = !(network_mode == "host" || network_mode == "none" || network_mode.starts_with("container:"));
+    let mut extra_networks = Vec::new();
+    let mut endpoints_config = HashMap::new();
+    // El alias del ID corto se compara con el ID real: `container_id` puede ser el nombre
+    let old_id = info.id.clone().unwrap_or_default();
+    for (network, endpoint) in info.network_settings.as_ref().and_then(|n| n.networks.clone()).unwrap_or_default() {
+        let endpoint = recreated_endpoint(&endpoint, &old_id);
+        if network == primary_network {
+            endpoints_config.insert(network, endpoint);
+        } else if attachable {
+            extra_networks.push((network, endpoint));
+        }
+    }
+
+    let new_config = Config {
+        cmd: (!new_cmd.is_empty()).then_some(new_cmd),
+        labels: Some(labels),
+        host_config: Some(host_config),
+        networking_config: (!endpoints_config.is_empty()).then_some(NetworkingConfig { endpoints_config }),
+        ..Config::from(container_config)
+    };
+
+    if was_running {
+        docker.stop_container(&container_id, None).await.map_err(|e| format!("Error stopping container: {}", e))?;
+    }
+    docker
+        .remove_container(&container_id, Some(RemoveContainerOptions { v: false, force: true, ..Default::default() }))
+        .await
+        .map_err(|e| format!("Error removing old container: {}", e))?;
+
+    let new_container = docker
+        .create_container(Some(CreateContainerOptions { name: container_name.clone(), ..Default::default() }), new_config)
+        .await
+        .map_err(|e| format!("Error creating new container: {}", e))?;
+
+    for (network, endpoint_config) in extra_networks {
+        docker
+            .connect_network(&network, ConnectNetworkOptions { container: new_container.id.clone(), endpoint_config })
+            .await
+            .map_err(|e| format!("Error connecting container to network '{}': {}", network, e))?;
+    }
+
+    if was_running {
+        docker
+            .start_container(&new_container.id, None::<StartContainerOptions<String>>)
+            .await
+            .map_err(|e| format!("Error starting container: {}", e))?;
+    }
+
+    println!("✅ Contenedor {} recreado", container_name);
+    Ok(format!(
+        "Query insights {} for '{}'. Container ID: {}",
+        if enabled { "enabled" } else { "disabled" },
+        container_name,
+        new_container.id
+    ))
+}
+
+// ===== QUERY PLANS =====
+
+// Un seq scan que lee más filas que esto se marca como hotspot
+const PLAN_LARGE_SCAN_ROWS: f64 = 10_000.0;
+// Estimación de filas desviada en este factor o más
+const PLAN_MISESTIMATE_FACTOR: f64 = 10.0;
+// Ignorar desviaciones en nodos con muy pocas filas
+const PLAN_MISESTIMATE_MIN_ROWS: f64 = 100.0;
+
+/// One node of a normalized execution plan
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PlanNode {
+    pub node_type: String,
+    pub relation: Option<String>,
+    pub index: Option<String>,
+    pub startup_cost: Option<f64>,
+    pub total_cost: Option<f64>,
+    pub estimated_rows: Option<f64>,
+    /// Rows per loop, only with ANALYZE
+    pub actual_rows: Option<f64>,
+    /// Inclusive time of the node across all loops, only with ANALYZE
+    pub actual_time_ms: Option<f64>,
+    pub loops: Option<f64>,
+    pub shared_hit_blocks: Option<u64>,
+    pub shared_read_blocks: Option<u64>,
+    /// Filter / join / attached condition
+    pub condition: Option<String>,
+    pub hotspots: Vec<String>,
+    pub children: Vec<PlanNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QueryPlan {
+    pub db_type: String,
+    pub analyzed: bool,
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    pub root: PlanNode,
+    pub hotspot_count: usize,
+    /// Plan exactly as returned by the engine
+    pub raw: serde_json::Value,
+}
+
+impl PlanNode {
+    /// Flag hotspots on this node and its children
+    fn flag_hotspots(&mut self) -> usize {
+        let scanned = self.actual_rows.map(|r| r * self.loops.unwrap_or(1.0)).or(self.estimated_rows);
+        let full_scan = matches!(self.node_type.as_str(), "Seq Scan" | "Full Table Scan");
+        if let (true, Some(rows)) = (full_scan, scanned) {
+            if rows >= PLAN_LARGE_SCAN_ROWS {
+                self.hotspots.push(format!(
+                    "Full scan of {} ({} rows)",
+                    self.relation.as_deref().unwrap_or("table"),
+                    rows as u64
+                ));
+            }
+        }
+
+        if let (Some(estimated), Some(actual)) = (self.estimated_rows, self.actual_rows) {
+            let (low, high) = if estimated < actual { (estimated, actual) } else { (actual, estimated) };
+            if high >= PLAN_MISESTIMATE_MIN_ROWS && high >= low.max(1.0) * PLAN_MISESTIMATE_FACTOR {
+                self.hotspots.push(format!(
+                    "Row estimate off by {:.0}x ({} estimated, {} actual)",
+                    high / low.max(1.0),
+                    estimated as u64,
+                    actual as u64
+                ));
+            }
+        }
+
+        self.hotspots.len() + self.children.iter_mut().map(|c| c.flag_hotspots()).sum::<usize>()
+    }
+}
+
+fn normalize_pg_plan(plan: &serde_json::Value) -> PlanNode {
+    let num = |key: &str| plan.get(key).and_then(|v| v.as_f64());
+    let text = |key: &str| plan.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    let mut node = PlanNode {
+        node_type: text("Node Type").unwrap_or_else(|| "Unknown".to_string()),
+        relation: text("Relation Name").map(|r| match text("Schema") {
+            Some(schema) => format!("{}.{}", schema, r),
+            None => r,
+        }),
+        index: text("Index Name"),
+        startup_cost: num("Startup Cost"),
+        total_cost: num("Total Cost"),
+        estimated_rows: num("Plan Rows"),
+        actual_rows: num("Actual Rows"),
+        actual_time_ms: num("Actual Total Time").map(|t| t * num("Actual Loops").unwrap_or(1.0)),
+        loops: num("Actual Loops"),
+        shared_hit_blocks: num("Shared Hit Blocks").map(|v| v as u64),
+        shared_read_blocks: num("Shared Read Blocks").map(|v| v as u64),
+        condition: ["Filter", "Index Cond", "Hash Cond", "Merge Cond", "Join Filter", "Recheck Cond"]
+            .iter()
+            .find_map(|k| text(k)),
+        hotspots: Vec::new(),
+        children: plan
+            .get("Plans")
+            .and_then(|p| p.as_array())
+            .map(|plans| plans.iter().map(normalize_pg_plan).collect())
+            .unwrap_or_default(),
+    };
+
+    if text("Sort Space Type").as_deref() == Some("Disk") {
+        node.hotspots.push("Sort spilled to disk".to_string());
+    }
+    node
+}
+
+// Claves de EXPLAIN FORMAT=JSON de MySQL/MariaDB que contienen sub-planes
+const MYSQL_PLAN_KEYS: &[(&str, &str)] = &[
+    ("query_block", "Query Block"),
+    ("nested_loop", "Nested Loop"),
+    ("ordering_operation", "Sort"),
+    ("grouping_operation", "Group"),
+    ("duplicates_removal", "Distinct"),
+    ("windowing", "Window"),
+    ("materialized_from_subquery", "Materialize"),
+    ("union_result", "Union"),
+    ("query_specifications", "Union Member"),
+    ("attached_subqueries", "Subquery"),
+    ("optimized_away_subqueries", "Subquery"),
+    ("subqueries", "Subquery"),
+    ("read_sorted_file", "Read Sorted File"),
+    ("filesort", "Filesort"),
+    ("temporary_table", "Temporary Table"),
+    ("table", "Table"),
+];
+
+fn normalize_mysql_plan(node_type: &str, value: &serde_json::Value) -> PlanNode {
+    let obj = match value.as_object() {
+        Some(obj) => obj,
+        None => return PlanNode { node_type: node_type.to_string(), ..Default::default() },
+    };
+    let num = |key: &str| {
+        obj.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+    };
+    let cost = |key: &str| {
+        obj.get("cost_info")
+            .and_then(|c| c.get(key))
+            .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+    };
+    let text = |key: &str| obj.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+
+    let access_type = text("access_type");
+    let node_type = match (node_type, access_type.as_deref()) {
+        ("Table", Some("ALL")) => "Full Table Scan".to_string(),
+        ("Table", Some("index")) => "Full Index Scan".to_string(),
+        ("Table", Some(access)) => format!("Table Access ({})", access),
+        (other, _) => other.to_string(),
+    };
+
+    let mut children = Vec::new();
+    for (key, child_type) in MYSQL_PLAN_KEYS {
+        match obj.get(*key) {
+            Some(serde_json::Value::Array(items)) => {
+                // nested_loop y similares son listas de { "table": {...} } / { "query_block": {...} }
+                let members = items
+                    .iter()
+                    .map(|item| match MYSQL_PLAN_KEYS.iter().find_map(|(k, t)| item.get(*k).map(|v| (*t, v))) {
+                        Some((t, v)) => normalize_mysql_plan(t, v),
+                        None => normalize_mysql_plan("Table", item),
+                    })
+                    .collect();
+                children.push(PlanNode { node_type: child_type.to_string(), children: members, ..Default::default() });
+            }
+            Some(child) if child.is_object() => children.push(normalize_mysql_plan(child_type, child)),
+            _ => {}
+        }
+    }
+
+    let mut node = PlanNode {
+        node_type,
+        relation: text("table_name"),
+        index: text("key"),
+        startup_cost: None,
+        total_cost: cost("query_cost").or_else(|| cost("prefix_cost")).or_else(|| cost("sort_cost")),
+        estimated_rows: num("rows_examined_per_scan").or_else(|| num("rows")),
+        // MariaDB ANALYZE FORMAT=JSON
+        actual_rows: num("r_rows"),
+        actual_time_ms: num("r_total_time_ms"),
+        loops: num("r_loops"),
+        shared_hit_blocks: None,
+        shared_read_blocks: None,
+        condition: text("attached_condition"),
+        hotspots: Vec::new(),
+        children,
+    };
+
+    if obj.get("using_filesort").and_then(|v| v.as_bool()) == Some(true) {
+        node.hotspots.push("Using filesort".to_string());
+    }
+    if obj.get("using_temporary_table").and_then(|v| v.as_bool()) == Some(true) {
+        node.hotspots.push("Using temporary table".to_string());
+    }
+    node
+}
+
+/// Extract the JSON document from client output (psql/mysql may print other lines)
+fn extract_plan_json(output: &str) -> Result<serde_json::Value, String> {
+    let start = output.find(['[', '{']).ok_or_else(|| format!("No plan in output: {}", output.trim()))?;
+    let end = output.rfind([']', '}']).filter(|end| *end >= start).ok_or("Truncated plan output")?;
+    serde_json::from_str(&output[start..=end]).map_err(|e| format!("Invalid plan JSON: {}", e))
+}
+
+fn parse_pg_plan(output: &str, analyzed: bool) -> Result<QueryPlan, String> {
+    let raw = extract_plan_json(output)?;
+    let top = raw.get(0).ok_or("Empty plan")?;
+    let mut root = normalize_pg_plan(top.get("Plan").ok_or("Plan node missing")?);
+    let hotspot_count = root.flag_hotspots();
+
+    Ok(QueryPlan {
+        db_type: DatabaseType::PostgreSQL.to_string(),
+        analyzed,
+        planning_time_ms: top.get("Planning Time").and_then(|v| v.as_f64()),
+        execution_time_ms: top.get("Execution Time").and_then(|v| v.as_f64()),
+        root,
+        hotspot_count,
+        raw,
+    })
+}
+
+fn parse_mysql_plan(output: &str, db_type: &DatabaseType, analyzed: bool) -> Result<QueryPlan, String> {
+    let raw = extract_plan_json(output)?;
+    let block = raw.get("query_block").ok_or("query_block missing from plan")?;
+    let mut root = normalize_mysql_plan("Query Block", block);
+    let hotspot_count = root.flag_hotspots();
+
+    Ok(QueryPlan {
+        db_type: db_type.to_string(),
+        analyzed,
+        planning_time_ms: None,
+        execution_time_ms: block.get("r_total_time_ms").and_then(|v| v.as_f64()),
+        root,
+        hotspot_count,
+        raw,
+    })
+}
+
+/// One line of MySQL's `EXPLAIN ANALYZE` tree, e.g.
+/// `Table scan on orders  (cost=2051 rows=20000) (actual time=0.05..9.8 rows=20000 loops=1)`
+fn parse_mysql_tree_node(line: &str) -> PlanNode {
+    let (desc, stats) = line.split_once("  (").unwrap_or((line, ""));
+    let (estimated, actual) = match stats.find("(actual ") {
+        Some(i) => (&stats[..i], &stats[i..]),
+        None => (stats, ""),
+    };
+    let field = |section: &str, key: &str| -> Option<String> {
+        let start = section.find(key)? + key.len();
+        let rest = &section[start..];
+        Some(rest[..rest.find([' ', ')']).unwrap_or(rest.len())].to_string())
+    };
+    let num = |value: Option<&str>| value.and_then(|v| v.parse::<f64>().ok());
+
+    // 8.0.x da `cost=X`; las versiones nuevas `cost=inicio..total`
+    let cost = field(estimated, "cost=");
+    let (startup_cost, total_cost) = match cost.as_deref().and_then(|c| c.split_once("..")) {
+        Some((startup, total)) => (num(Some(startup)), num(Some(total))),
+        None => (None, num(cost.as_deref())),
+    };
+    let loops = num(field(actual, "loops=").as_deref());
+    let actual_time_ms = field(actual, "time=").and_then(|t| t.rsplit("..").next().and_then(|v| v.parse::<f64>().ok()));
+
+    let (head, condition) = match desc.split_once(": ") {
+        Some((head, condition)) => (head, Some(condition.to_string())),
+        None => (desc, None),
+    };
+    let target = head.split_once(" on ").map(|(_, t)| t);
+    let word = |text: &str| text.split_whitespace().next().map(|w| w.trim_matches('`').to_string());
+    let node_type = match head.split(" on ").next().unwrap_or(head) {
+        "Table scan" => "Full Table Scan".to_string(),
+        "Index scan" => "Full Index Scan".to_string(),
+        other => other.to_string(),
+    };
+
+    PlanNode {
+        node_type,
+        relation: target.and_then(word),
+        index: target.and_then(|t| t.split_once(" using ")).and_then(|(_, i)| word(i)),
+        startup_cost,
+        total_cost,
+        estimated_rows: num(field(estimated, "rows=").as_deref()),
+        actual_rows: num(field(actual, "rows=").as_deref()),
+        actual_time_ms: actual_time_ms.map(|t| t * loops.unwrap_or(1.0)),
+        loops,
+        condition,
+        ..Default::default()
+    }
+}
+
+/// MySQL 8.0.18+ `EXPLAIN ANALYZE` only prints a text tree (4 spaces per level)
+fn parse_mysql_analyze_tree(output: &str) -> Result<QueryPlan, String> {
+    fn attach(stack: &mut [(usize, PlanNode)], roots: &mut Vec<PlanNode>, node: PlanNode) {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    let mut stack: Vec<(usize, PlanNode)> = Vec::new();
+    let mut roots = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        let Some(desc) = trimmed.strip_prefix("-> ") else { continue };
+        let depth = line.len() - trimmed.len();
+        while stack.last().is_some_and(|(d, _)| *d >= depth) {
+            let (_, done) = stack.pop().unwrap();
+            attach(&mut stack, &mut roots, done);
+        }
+        stack.push((depth, parse_mysql_tree_node(desc)));
+    }
+    while let Some((_, done)) = stack.pop() {
+        attach(&mut stack, &mut roots, done);
+    }
+
+    let mut root = match roots.len() {
+        0 => return Err(format!("No plan in output: {}", output.trim())),
+        1 => roots.remove(0),
+        _ => PlanNode { node_type: "Query Block".to_string(), children: roots, ..Default::default() },
+    };
+    let hotspot_count = root.flag_hotspots();
+
+    Ok(QueryPlan {
+        db_type: DatabaseType::MySQL.to_string(),
+        analyzed: true,
+        planning_time_ms: None,
+        execution_time_ms: root.actual_time_ms,
+        root,
+        hotspot_count,
+        raw: serde_json::Value::String(output.trim().to_string()),
+    })
+}
+
+/// `8.0.35`, `8.0.35-log`, `10.11.6-MariaDB` -> (8, 0, 35)
+fn parse_server_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split(|c: char| !c.is_ascii_digit()).map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// Explain a statement and return its plan as a normalized tree with hotspots flagged
+///
+/// With `analyze` the statement is executed inside a transaction that is rolled back,
+/// so `INSERT`/`UPDATE`/`DELETE` can be analyzed without changing data.
+/// MySQL uses `EXPLAIN ANALYZE` (8.0.18+, text tree) and MariaDB `ANALYZE FORMAT=JSON`.
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+/// * `database` - Database to run in (default: the container's database)
+/// * `sql` - A single statement
+/// * `analyze` - Execute the statement to get actual rows and timings
+///
+/// # Returns
+/// * `Ok(QueryPlan)` - Normalized plan plus the raw engine output
+/// * `Err(String)` - Unsupported engine or SQL error
+#[tauri::command]
+async fn explain_query(
+    container_id: String,
+    database: Option<String>,
+    sql: String,
+    analyze: bool,
+    state: State<'_, AppState>,
+) -> Result<QueryPlan, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    let statement = sql.trim().trim_end_matches(';').trim();
+    if statement.is_empty() {
+        return Err("Statement cannot be empty".to_string());
+    }
+
+    match target.db_type {
+        DatabaseType::PostgreSQL => {
+            let explain = if analyze {
+                format!("EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS) {}", statement)
+            } else {
+                format!("EXPLAIN (FORMAT JSON) {}", statement)
+            };
+            let database = database.unwrap_or_else(|| target.database.clone());
+            // ANALYZE ejecuta la sentencia: se deshace al terminar
+            let cmd = if analyze {
+                psql_command(&target, &database, &["BEGIN", &explain, "ROLLBACK"])
+            } else {
+                psql_command(&target, &database, &[&explain])
+            };
+            let output = exec_in_container(&docker, &target.container_id, cmd, vec![format!("PGPASSWORD={}", target.password)], ENGINE_EXEC_TIMEOUT_SECS)
+                .await?
+                .into_result()?;
+            parse_pg_plan(&output, analyze)
+        }
+        DatabaseType::MySQL if analyze => {
+            let version = run_engine_script(&docker, &target, database.as_deref(), "SELECT VERSION()").await?;
+            if parse_server_version(&version).is_none_or(|v| v < (8, 0, 18)) {
+                return Err(format!("EXPLAIN ANALYZE requires MySQL 8.0.18 or later (server is {})", version.trim()));
+            }
+            let script = format!("START TRANSACTION; EXPLAIN ANALYZE {}; ROLLBACK;", statement);
+            let output = run_engine_script(&docker, &target, database.as_deref(), &script).await?;
+            parse_mysql_analyze_tree(&output)
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let script = if analyze {
+                format!("START TRANSACTION; ANALYZE FORMAT=JSON {}; ROLLBACK;", statement)
+            } else {
+                format!("EXPLAIN FORMAT=JSON {}", statement)
+            };
+            let output = run_engine_script(&docker, &target, database.as_deref(), &script).await?;
+            parse_mysql_plan(&output, &target.db_type, analyze)
+        }
+        DatabaseType::MongoDB | DatabaseType::Redis => Err(format!("EXPLAIN is not available for {}", target.db_type.to_string())),
+    }
+}
+
+// ===== SCHEMA BROWSER =====
+
+/// Accept `true`/`false`, `1`/`0` and `"YES"`/`"NO"` (MySQL JSON_OBJECT turns comparisons into numbers)
+fn deserialize_flexible_bool<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(match value {
+        serde_json::Value::Bool(b) => b,
+        serde_json::Value::Number(n) => n.as_f64().unwrap_or(0.0) != 0.0,
+        serde_json::Value::String(s) => matches!(s.to_lowercase().as_str(), "yes" | "true" | "1" | "t"),
+        _ => false,
+    })
+}
+
+/// SQL string literal, escaping quotes (and backslashes for MySQL)
+fn quote_sql_literal(value: &str, db_type: &DatabaseType) -> String {
+    let escaped = value.replace('\'', "''");
+    match db_type {
+        DatabaseType::MySQL | DatabaseType::MariaDB => format!("'{}'", escaped.replace('\\', "\\\\")),
+        _ => format!("'{}'", escaped),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct DatabaseSummary {
+    pub name: String,
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: String,
+    #[serde(deserialize_with = "deserialize_flexible_bool")]
+    pub nullable: bool,
+    pub default: Option<String>,
+    pub ordinal: u32,
+    /// Identity, generation or auto-increment clause as written in a column definition,
+    /// e.g. `GENERATED ALWAYS AS IDENTITY` or `AUTO_INCREMENT ON UPDATE CURRENT_TIMESTAMP`
+    pub extra: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    #[serde(deserialize_with = "deserialize_flexible_bool")]
+    pub unique: bool,
+    #[serde(deserialize_with = "deserialize_flexible_bool")]
+    pub primary: bool,
+    pub definition: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ForeignKeyInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: Option<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_update: Option<String>,
+    pub on_delete: Option<String>,
+}
+
+/// A table, view or MongoDB collection
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TableInfo {
+    pub schema: String,
+    pub name: String,
+    /// table, view, materialized_view, foreign_table, collection
+    pub kind: String,
+    pub row_estimate: Option<i64>,
+    pub size_bytes: Option<u64>,
+    pub primary_key: Vec<String>,
+    /// For MongoDB: top-level fields inferred from a sample of documents
+    pub columns: Vec<ColumnInfo>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SchemaInfo {
+    pub name: String,
+    pub tables: Vec<TableInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DatabaseSchema {
+    pub name: String,
+    pub db_type: String,
+    /// MySQL/MariaDB/MongoDB have a single schema named after the database
+    pub schemas: Vec<SchemaInfo>,
+}
+
+const PG_DATABASES_QUERY: &str = "SELECT coalesce(json_agg(json_build_object('name', datname, 'size_bytes', pg_database_size(datname)) ORDER BY datname), '[]') \
+    FROM pg_database WHERE NOT datistemplate AND datallowconn";
+
+const MYSQL_DATABASES_QUERY: &str = "SELECT JSON_ARRAYAGG(JSON_OBJECT('name', s.SCHEMA_NAME, 'size_bytes', \
+    (SELECT SUM(t.DATA_LENGTH + t.INDEX_LENGTH) FROM information_schema.TABLES t WHERE t.TABLE_SCHEMA = s.SCHEMA_NAME))) \
+    FROM information_schema.SCHEMATA s WHERE s.SCHEMA_NAME NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')";
+
+const MONGO_DATABASES_SCRIPT: &str = "print(JSON.stringify(db.adminCommand({listDatabases: 1}).databases.map(function (d) { \
+    return {name: d.name, size_bytes: Number(d.sizeOnDisk)}; })));";
+
+const PG_SCHEMA_QUERY: &str = "SELECT json_build_object(\
+    'schemas', (SELECT coalesce(json_agg(nspname ORDER BY nspname), '[]') FROM pg_namespace \
+        WHERE nspname NOT IN ('pg_catalog', 'information_schema') AND nspname NOT LIKE 'pg_toast%' AND nspname NOT LIKE 'pg_temp%'), \
+    'tables', (SELECT coalesce(json_agg(json_build_object(\
+        'schema', n.nspname, 'name', c.relname, \
+        'kind', CASE c.relkind WHEN 'v' THEN 'view' WHEN 'm' THEN 'materialized_view' WHEN 'f' THEN 'foreign_table' ELSE 'table' END, \
+        'row_estimate', CASE WHEN c.reltuples < 0 THEN NULL ELSE c.reltuples::bigint END, \
+        'size_bytes', pg_total_relation_size(c.oid), \
+        'columns', (SELECT coalesce(json_agg(json_build_object('name', a.attname, 'data_type', format_type(a.atttypid, a.atttypmod), \
+            'nullable', NOT a.attnotnull, \
+            'default', CASE WHEN a.attgenerated = '' THEN pg_get_expr(d.adbin, d.adrelid) END, 'ordinal', a.attnum, \
+            'extra', CASE WHEN a.attidentity = 'a' THEN 'GENERATED ALWAYS AS IDENTITY' WHEN a.attidentity = 'd' THEN 'GENERATED BY DEFAULT AS IDENTITY' \
+                WHEN a.attgenerated = 's' THEN 'GENERATED ALWAYS AS (' || pg_get_expr(d.adbin, d.adrelid) || ') STORED' END) ORDER BY a.attnum), '[]') \
+            FROM pg_attribute a LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+            WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped), \
+        'indexes', (SELECT coalesce(json_agg(json_build_object('name', ic.relname, \
+            'columns', (SELECT json_agg(pg_get_indexdef(i.indexrelid, k, true) ORDER BY k) FROM generate_series(1, i.indnkeyatts) k), \
+            'unique', i.indisunique, 'primary', i.indisprimary, 'definition', pg_get_indexdef(i.indexrelid)) ORDER BY ic.relname), '[]') \
+            FROM pg_index i JOIN pg_class ic ON ic.oid = i.indexrelid WHERE i.indrelid = c.oid), \
+        'foreign_keys', (SELECT coalesce(json_agg(json_build_object('name', con.conname, \
+            'columns', (SELECT json_agg(att.attname ORDER BY u.ord) FROM unnest(con.conkey) WITH ORDINALITY u(attnum, ord) \
+                JOIN pg_attribute att ON att.attrelid = con.conrelid AND att.attnum = u.attnum), \
+            'referenced_schema', rn.nspname, 'referenced_table', rc.relname, \
+            'referenced_columns', (SELECT json_agg(att.attname ORDER BY u.ord) FROM unnest(con.confkey) WITH ORDINALITY u(attnum, ord) \
+                JOIN pg_attribute att ON att.attrelid = con.confrelid AND att.attnum = u.attnum), \
+            'on_update', con.confupdtype, 'on_delete', con.confdeltype) ORDER BY con.conname), '[]') \
+            FROM pg_constraint con JOIN pg_class rc ON rc.oid = con.confrelid JOIN pg_namespace rn ON rn.oid = rc.relnamespace \
+            WHERE con.conrelid = c.oid AND con.contype = 'f') \
+        ) ORDER BY n.nspname, c.relname), '[]') \
+        FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace \
+        WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f') AND NOT c.relispartition \
+        AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp%'))";
+
+/// Catalog queries for one MySQL/MariaDB database: tables, columns, index columns, FK columns
+fn mysql_schema_queries(database: &str, db_type: &DatabaseType) -> String {
+    let db = quote_sql_literal(database, db_type);
+    format!(
+        "SELECT JSON_ARRAYAGG(JSON_OBJECT('name', TABLE_NAME, 'kind', IF(TABLE_TYPE = 'VIEW', 'view', 'table'), \
+            'row_estimate', TABLE_ROWS, 'size_bytes', DATA_LENGTH + INDEX_LENGTH)) \
+            FROM information_schema.TABLES WHERE TABLE_SCHEMA = {db}; \
+         SELECT JSON_ARRAYAGG(JSON_OBJECT('table', TABLE_NAME, 'name', COLUMN_NAME, 'data_type', COLUMN_TYPE, \
+            'nullable', IS_NULLABLE, 'default', COLUMN_DEFAULT, 'ordinal', ORDINAL_POSITION, \
+            'column_extra', EXTRA, 'generation_expression', GENERATION_EXPRESSION)) \
+            FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = {db}; \
+         SELECT JSON_ARRAYAGG(JSON_OBJECT('table', TABLE_NAME, 'name', INDEX_NAME, 'column', COLUMN_NAME, \
+            'seq', SEQ_IN_INDEX, 'non_unique', NON_UNIQUE, 'method', INDEX_TYPE)) \
+            FROM information_schema.STATISTICS WHERE TABLE_SCHEMA = {db}; \
+         SELECT JSON_ARRAYAGG(JSON_OBJECT('table', k.TABLE_NAME, 'name', k.CONSTRAINT_NAME, 'column', k.COLUMN_NAME, \
+            'seq', k.ORDINAL_POSITION, 'referenced_schema', k.REFERENCED_TABLE_SCHEMA, 'referenced_table', k.REFERENCED_TABLE_NAME, \
+            'referenced_column', k.REFERENCED_COLUMN_NAME, 'on_update', r.UPDATE_RULE, 'on_delete', r.DELETE_RULE)) \
+            FROM information_schema.KEY_COLUMN_USAGE k JOIN information_schema.REFERENTIAL_CONSTRAINTS r \
+            ON r.CONSTRAINT_SCHEMA = k.CONSTRAINT_SCHEMA AND r.CONSTRAINT_NAME = k.CONSTRAINT_NAME AND r.TABLE_NAME = k.TABLE_NAME \
+            WHERE k.TABLE_SCHEMA = {db} AND k.REFERENCED_TABLE_NAME IS NOT NULL;",
+        db = db
+    )
+}
+
+fn mongo_schema_script(database: &str) -> String {
+    let name = serde_json::to_string(database).unwrap_or_else(|_| "\"test\"".to_string());
+    format!(
+        "var d = db.getSiblingDB({name}); \
+         function typeOf(v) {{ return v === null ? 'null' : Array.isArray(v) ? 'array' : v instanceof ObjectId ? 'objectId' : v instanceof Date ? 'date' : typeof v; }} \
+         print(JSON.stringify(d.getCollectionInfos().map(function (c) {{ \
+            var coll = d.getCollection(c.name); var isView = c.type === 'view'; var fields = {{}}; var order = []; \
+            coll.aggregate([{{$sample: {{size: 20}}}}]).forEach(function (doc) {{ for (var k in doc) {{ var t = typeOf(doc[k]); \
+                if (!(k in fields)) {{ order.push(k); fields[k] = t; }} else if (fields[k] !== t) {{ fields[k] = 'mixed'; }} }} }}); \
+            var stats = isView ? {{}} : coll.stats(); \
+            return {{schema: {name}, name: c.name, kind: isView ? 'view' : 'collection', \
+                row_estimate: isView ? null : Number(coll.estimatedDocumentCount()), \
+                size_bytes: isView ? null : Number(stats.size || 0) + Number(stats.totalIndexSize || 0), \
+                primary_key: isView ? [] : ['_id'], \
+                columns: order.map(function (k, i) {{ return {{name: k, data_type: fields[k], nullable: true, ordinal: i + 1}}; }}), \
+                indexes: isView ? [] : coll.getIndexes().map(function (ix) {{ return {{name: ix.name, columns: Object.keys(ix.key), \
+                    unique: !!ix.unique || ix.name === '_id_', primary: ix.name === '_id_', definition: JSON.stringify(ix.key)}}; }}), \
+                foreign_keys: []}}; }})));",
+        name = name
+    )
+}
+
+/// Last non-empty line of client output as JSON (`NULL` from an empty JSON_ARRAYAGG is `[]`)
+fn parse_json_line<T: serde::de::DeserializeOwned + Default>(line: &str) -> Result<T, String> {
+    let line = line.trim();
+    if line.is_empty() || line == "NULL" {
+        return Ok(T::default());
+    }
+    serde_json::from_str(line).map_err(|e| format!("Unexpected catalog output: {}", e))
+}
+
+fn last_json_line(output: &str) -> &str {
+    output.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("")
+}
+
+fn pg_fk_action(code: &str) -> String {
+    match code {
+        "a" => "NO ACTION",
+        "r" => "RESTRICT",
+        "c" => "CASCADE",
+        "n" => "SET NULL",
+        "d" => "SET DEFAULT",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Group flat rows into schemas, filling primary keys from the indexes
+fn group_tables(database: &str, db_type: &DatabaseType, schema_names: Vec<String>, tables: Vec<TableInfo>) -> DatabaseSchema {
+    let mut schemas: Vec<SchemaInfo> = schema_names.into_iter().map(|name| SchemaInfo { name, tables: Vec::new() }).collect();
+    for mut table in tables {
+        if table.primary_key.is_empty() {
+            if let Some(pk) = table.indexes.iter().find(|i| i.primary) {
+                table.primary_key = pk.columns.clone();
+            }
+        }
+        match schemas.iter_mut().find(|s| s.name == table.schema) {
+            Some(schema) => schema.tables.push(table),
+            None => schemas.push(SchemaInfo { name: table.schema.clone(), tables: vec![table] }),
+        }
+    }
+    DatabaseSchema { name: database.to_string(), db_type: db_type.to_string(), schemas }
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct MysqlColumnRow {
+    table: String,
+    #[serde(flatten)]
+    column: ColumnInfo,
+    column_extra: String,
+    generation_expression: Option<String>,
+}
+
+impl MysqlColumnRow {
+    fn into_column(self) -> ColumnInfo {
+        let extra = mysql_column_extra(&self.column_extra, self.generation_expression.as_deref());
+        ColumnInfo { extra, ..self.column }
+    }
+}
+
+/// Column clause for what `COLUMNS.EXTRA` reports (`DEFAULT_GENERATED` only marks expression defaults)
+fn mysql_column_extra(extra: &str, generation_expression: Option<&str>) -> Option<String> {
+    let lower = extra.to_lowercase();
+    let mut clauses = Vec::new();
+    if let Some(expression) = generation_expression.filter(|e| !e.is_empty()) {
+        let storage = if lower.contains("stored") || lower.contains("persistent") { "STORED" } else { "VIRTUAL" };
+        clauses.push(format!("GENERATED ALWAYS AS ({}) {}", expression, storage));
+    }
+    if lower.contains("auto_increment") {
+        clauses.push("AUTO_INCREMENT".to_string());
+    }
+    if let Some(position) = lower.find("on update ") {
+        clauses.push(format!("ON UPDATE {}", extra[position + "on update ".len()..].trim().to_uppercase()));
+    }
+    (!clauses.is_empty()).then(|| clauses.join(" "))
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct MysqlIndexRow {
+    table: String,
+    name: String,
+    column: Option<String>,
+    seq: u32,
+    #[serde(deserialize_with = "deserialize_flexible_bool")]
+    non_unique: bool,
+    method: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct MysqlForeignKeyRow {
+    table: String,
+    name: String,
+    column: String,
+    seq: u32,
+    referenced_schema: Option<String>,
+    referenced_table: String,
+    referenced_column: String,
+    on_update: Option<String>,
+    on_delete: Option<String>,
+}
+
+/// Assemble the output of `mysql_schema_queries` (one JSON line per query) into a schema tree
+fn parse_mysql_schema(database: &str, db_type: &DatabaseType, output: &str) -> Result<DatabaseSchema, String> {
+    let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() < 4 {
+        return Err(format!("Unexpected catalog output: {}", output.trim()));
+    }
+    let mut tables: Vec<TableInfo> = parse_json_line(lines[0])?;
+    let columns: Vec<MysqlColumnRow> = parse_json_line(lines[1])?;
+    let mut index_rows: Vec<MysqlIndexRow> = parse_json_line(lines[2])?;
+    let mut fk_rows: Vec<MysqlForeignKeyRow> = parse_json_line(lines[3])?;
+
+    let mut columns: Vec<(String, ColumnInfo)> = columns.into_iter().map(|c| (c.table.clone(), c.into_column())).collect();
+    columns.sort_by_key(|(_, c)| c.ordinal);
+    index_rows.sort_by(|a, b| (&a.table, &a.name, a.seq).cmp(&(&b.table, &b.name, b.seq)));
+    fk_rows.sort_by(|a, b| (&a.table, &a.name, a.seq).cmp(&(&b.table, &b.name, b.seq)));
+    tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    for table in tables.iter_mut() {
+        table.schema = database.to_string();
+        table.columns = columns.iter().filter(|(t, _)| *t == table.name).map(|(_, c)| c.clone()).collect();
+
+        for row in index_rows.iter().filter(|r| r.table == table.name) {
+            let column = row.column.clone().unwrap_or_else(|| "(expression)".to_string());
+            match table.indexes.iter_mut().find(|i| i.name == row.name) {
+                Some(index) => index.columns.push(column),
+                None => table.indexes.push(IndexInfo {
+                    name: row.name.clone(),
+                    columns: vec![column],
+                    unique: !row.non_unique,
+                    primary: row.name == "PRIMARY",
+                    definition: row.method.clone(),
+                }),
+            }
+        }
+
+        for row in fk_rows.iter().filter(|r| r.table == table.name) {
+            match table.foreign_keys.iter_mut().find(|f| f.name == row.name) {
+                Some(fk) => {
+                    fk.columns.push(row.column.clone());
+                    fk.referenced_columns.push(row.referenced_column.clone());
+                }
+                None => table.foreign_keys.push(ForeignKeyInfo {
+                    name: row.name.clone(),
+                    columns: vec![row.column.clone()],
+                    referenced_schema: row.referenced_schema.clone(),
+                    referenced_table: row.referenced_table.clone(),
+                    referenced_columns: vec![row.referenced_column.clone()],
+                    on_update: row.on_update.clone(),
+                    on_delete: row.on_delete.clone(),
+                }),
+            }
+        }
+    }
+
+    Ok(group_tables(database, db_type, vec![database.to_string()], tables))
+}
+
+/// Parse the output of `PG_SCHEMA_QUERY`
+fn parse_pg_schema(database: &str, output: &str) -> Result<DatabaseSchema, String> {
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct PgSchemaOutput {
+        schemas: Vec<String>,
+        tables: Vec<TableInfo>,
+    }
+    let parsed: PgSchemaOutput = parse_json_line(output)?;
+    let tables = parsed
+        .tables
+        .into_iter()
+        .map(|mut t| {
+            for fk in t.foreign_keys.iter_mut() {
+                fk.on_update = fk.on_update.as_deref().map(pg_fk_action);
+                fk.on_delete = fk.on_delete.as_deref().map(pg_fk_action);
+            }
+            t
+        })
+        .collect();
+    Ok(group_tables(database, &DatabaseType::PostgreSQL, parsed.schemas, tables))
+}
+
+/// Load the schema tree of one database
+async fn load_database_schema(docker: &Docker, target: &DbTarget, database: &str) -> Result<DatabaseSchema, String> {
+    match target.db_type {
+        DatabaseType::PostgreSQL => parse_pg_schema(database, &run_engine_script(docker, target, Some(database), PG_SCHEMA_QUERY).await?),
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let output = run_engine_script(docker, target, Some(""), &mysql_schema_queries(database, &target.db_type)).await?;
+            parse_mysql_schema(database, &target.db_type, &output)
+        }
+        DatabaseType::MongoDB => {
+            let tables: Vec<TableInfo> = parse_json_line(last_json_line(
+                &run_engine_script(docker, target, Some("admin"), &mongo_schema_script(database)).await?,
+            ))?;
+            let mut schema = group_tables(database, &target.db_type, vec![database.to_string()], tables);
+            for s in schema.schemas.iter_mut() {
+                s.tables.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            Ok(schema)
+        }
+        DatabaseType::Redis => Err("Redis has no schema; use the key browser instead".to_string()),
+    }
+}
+
+/// List the databases of a managed container
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+///
+/// # Returns
+/// * `Ok(Vec<DatabaseSummary>)` - Database names with their size on disk
+/// * `Err(String)` - Unsupported engine or client error
+#[tauri::command]
+async fn list_databases(container_id: String, state: State<'_, AppState>) -> Result<Vec<DatabaseSummary>, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+
+    let output = match target.db_type {
+        DatabaseType::PostgreSQL => run_engine_script(&docker, &target, None, PG_DATABASES_QUERY).await?,
+        DatabaseType::MySQL | DatabaseType::MariaDB => run_engine_script(&docker, &target, Some(""), MYSQL_DATABASES_QUERY).await?,
+        DatabaseType::MongoDB => run_engine_script(&docker, &target, Some("admin"), MONGO_DATABASES_SCRIPT).await?,
+        DatabaseType::Redis => return Err("Redis has no named databases; use the key browser instead".to_string()),
+    };
+
+    let mut databases: Vec<DatabaseSummary> = parse_json_line(last_json_line(&output))?;
+    databases.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(databases)
+}
+
+/// Get the schema tree of a database: schemas → tables/views → columns, indexes and foreign keys
+///
+/// Row counts and sizes are the engine's estimates, not exact counts.
+/// For MongoDB, collections get their indexes and the top-level fields of a 20 document sample.
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+/// * `database` - Database name (from `list_databases`)
+///
+/// # Returns
+/// * `Ok(DatabaseSchema)` - The schema tree
+/// * `Err(String)` - Unsupported engine or client error
+#[tauri::command]
+async fn get_database_schema(container_id: String, database: String, state: State<'_, AppState>) -> Result<DatabaseSchema, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    load_database_schema(&docker, &target, &database).await
+}
+
+// ===== SQL DRIVER CONNECTIONS =====
+
+/// Direct driver connection to a managed PostgreSQL/MySQL/MariaDB container through its published port.
+/// Used where the CLI clients are not enough: bound parameters and typed results.
+enum SqlConn {
+    Postgres(tokio_postgres::Client),
+    MySql(mysql_async::Conn),
+}
+
+/// Address to reach a published port from the host: wildcard bindings go through loopback
+fn binding_host(host_ip: Option<&str>) -> String {
+    match host_ip.unwrap_or("") {
+        "" | "0.0.0.0" => "127.0.0.1".to_string(),
+        "::" => "::1".to_string(),
+        ip => ip.to_string(),
+    }
+}
+
+/// Host address and port Docker publishes for a container port such as `5432/tcp`
+async fn published_port(docker: &Docker, container_id: &str, container_port: &str) -> Result<(String, u16), String> {
+    let info = docker
+        .inspect_container(container_id, None)
+        .await
+        .map_err(|e| format!("Failed to inspect container: {}", e))?;
+    let name = info.name.as_deref().unwrap_or(container_id).trim_start_matches('/').to_string();
+    if !info.state.as_ref().and_then(|s| s.running).unwrap_or(false) {
+        return Err(format!("Container '{}' is not running", name));
+    }
+
+    let mut bindings: Vec<(String, u16)> = info
+        .network_settings
+        .and_then(|n| n.ports)
+        .and_then(|ports| ports.get(container_port).cloned().flatten())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|b| Some((binding_host(b.host_ip.as_deref()), b.host_port?.parse().ok()?)))
+        .collect();
+    // Con bindings IPv4 e IPv6 del mismo puerto se prefiere IPv4
+    bindings.sort_by_key(|(host, _)| host.contains(':'));
+    bindings.into_iter().next().ok_or_else(|| {
+        format!(
+            "Port {} of container '{}' is not published on the host. Publish it to connect to the database",
+            container_port, name
+        )
+    })
+}
+
+/// Quote an identifier for the engine (`"name"` or `` `name` ``)
+fn quote_ident(name: &str, db_type: &DatabaseType) -> String {
+    match db_type {
+        DatabaseType::MySQL | DatabaseType::MariaDB => format!("`{}`", name.replace('`', "``")),
+        _ => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Parameters travel as text (or NULL); PostgreSQL casts them to the column type in the SQL
+fn json_to_sql_param(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn mysql_value_to_json(value: &mysql_async::Value) -> serde_json::Value {
+    use mysql_async::Value;
+    match value {
+        Value::NULL => serde_json::Value::Null,
+        Value::Bytes(bytes) => json!(String::from_utf8_lossy(bytes)),
+        Value::Int(i) => json!(i),
+        Value::UInt(u) => json!(u),
+        Value::Float(f) => json!(f),
+        Value::Double(d) => json!(d),
+        Value::Date(y, m, d, h, mi, s, us) => {
+            let mut text = format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", y, m, d, h, mi, s);
+            if *us > 0 {
+                text.push_str(&format!(".{:06}", us));
+            }
+            json!(text)
+        }
+        Value::Time(negative, days, h, mi, s, us) => {
+            let hours = *days * 24 + u32::from(*h);
+            let mut text = format!("{}{:02}:{:02}:{:02}", if *negative { "-" } else { "" }, hours, mi, s);
+            if *us > 0 {
+                text.push_str(&format!(".{:06}", us));
+            }
+            json!(text)
+        }
+    }
+}
+
+/// SQL text plus its positional parameters, with the engine's placeholder syntax
+struct SqlBuilder {
+    db_type: DatabaseType,
+    sql: String,
+    params: Vec<Option<String>>,
+}
+
+impl SqlBuilder {
+    fn new(db_type: &DatabaseType) -> Self {
+        SqlBuilder { db_type: db_type.clone(), sql: String::new(), params: Vec::new() }
+    }
+
+    fn push(&mut self, sql: &str) -> &mut Self {
+        self.sql.push_str(sql);
+        self
+    }
+
+    /// Bind a value; on PostgreSQL it is cast from text to `pg_type` when given
+    fn bind(&mut self, value: Option<String>, pg_type: Option<&str>) -> &mut Self {
+        self.params.push(value);
+        let placeholder = match (&self.db_type, pg_type) {
+            (DatabaseType::PostgreSQL, Some(t)) => format!("CAST(${}::text AS {})", self.params.len(), t),
+            (DatabaseType::PostgreSQL, None) => format!("${}::text", self.params.len()),
+            _ => "?".to_string(),
+        };
+        self.sql.push_str(&placeholder);
+        self
+    }
+}
+
+impl SqlConn {
+    /// Open a driver connection to a container's database
+    async fn connect(docker: &Docker, target: &DbTarget, database: Option<&str>) -> Result<SqlConn, String> {
+        let database = database.filter(|d| !d.is_empty()).unwrap_or(&target.database).to_string();
+
+        match target.db_type {
+            DatabaseType::PostgreSQL => {
+                let (host, port) = published_port(docker, &target.container_id, "5432/tcp").await?;
+                let (client, connection) = tokio_postgres::Config::new()
+                    .host(&host)
+                    .port(port)
+                    .user(&target.user)
+                    .password(&target.password)
+                    .dbname(&database)
+                    .application_name("docker-db-manager")
+                    .connect(tokio_postgres::NoTls)
+                    .await
+                    .map_err(|e| format!("Failed to connect to PostgreSQL: {}", e))?;
+                tokio::spawn(async move {
+                    if let Err(e) = connection.await {
+                        eprintln!("PostgreSQL connection error: {}", e);
+                    }
+                });
+                Ok(SqlConn::Postgres(client))
+            }
+            DatabaseType::MySQL | DatabaseType::MariaDB => {
+                let (host, port) = published_port(docker, &target.container_id, "3306/tcp").await?;
+                let opts = mysql_async::OptsBuilder::default()
+                    .ip_or_hostname(host)
+                    .tcp_port(port)
+                    .user(Some(target.user.clone()))
+                    .pass(Some(target.password.clone()))
+                    .db_name((!database.is_empty()).then_some(database))
+                    // affected_rows cuenta filas encontradas, no solo las modificadas
+                    .client_found_rows(true);
+                let conn = mysql_async::Conn::new(opts)
+                    .await
+                    .map_err(|e| format!("Failed to connect to {}: {}", target.db_type.to_string(), e))?;
+                Ok(SqlConn::MySql(conn))
+            }
+            _ => Err(format!("SQL connections are not available for {}", target.db_type.to_string())),
+        }
+    }
+
+    /// Open a driver connection to a PostgreSQL server outside Docker (the source of `migrate_database`)
+    async fn connect_local(config: &LocalPostgresConfig, database: &str) -> Result<SqlConn, String> {
+        let (client, connection) = tokio_postgres::Config::new()
+            .host(&config.host)
+            .port(config.port)
+            .user(&config.user)
+            .password(&config.password)
+            .dbname(database)
+            .application_name("docker-db-manager")
+            .connect(tokio_postgres::NoTls)
+            .await
+            .map_err(|e| format!("Failed to connect to {}:{}: {}", config.host, config.port, e))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("PostgreSQL connection error: {}", e);
+            }
+        });
+        Ok(SqlConn::Postgres(client))
+    }
+
+    /// Run a row-returning statement; every row comes back as a JSON object keyed by column
+    async fn query_json(&mut self, query: &SqlBuilder) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, String> {
+        match self {
+            SqlConn::Postgres(client) => {
+                // row_to_json evita tener que mapear cada tipo de PostgreSQL a mano
+                let sql = format!("SELECT row_to_json(q)::text FROM ({}) q", query.sql);
+                let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                    query.params.iter().map(|p| p as &(dyn tokio_postgres::types::ToSql + Sync)).collect();
+                let rows = client.query(&sql, &params).await.map_err(format_pg_error)?;
+                rows.iter()
+                    .map(|row| {
+                        let text: String = row.get(0);
+                        serde_json::from_str(&text).map_err(|e| format!("Invalid row JSON: {}", e))
+                    })
+                    .collect()
+            }
+            SqlConn::MySql(conn) => {
+                use mysql_async::prelude::Queryable;
+                let params = mysql_params(&query.params);
+                let rows: Vec<mysql_async::Row> = conn.exec(query.sql.as_str(), params).await.map_err(|e| e.to_string())?;
+                Ok(rows.iter().map(mysql_row_to_json).collect())
+            }
+        }
+    }
+
+    /// PostgreSQL only: run a query whose single text column already holds one JSON object per row
+    async fn query_json_column(&mut self, query: &SqlBuilder) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, String> {
+        match self {
+            SqlConn::Postgres(client) => {
+                let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                    query.params.iter().map(|p| p as &(dyn tokio_postgres::types::ToSql + Sync)).collect();
+                let rows = client.query(query.sql.as_str(), &params).await.map_err(format_pg_error)?;
+                rows.iter()
+                    .map(|row| {
+                        let text: String = row.get(0);
+                        serde_json::from_str(&text).map_err(|e| format!("Invalid row JSON: {}", e))
+                    })
+                    .collect()
+            }
+            SqlConn::MySql(_) => Err("JSON column queries are only used with PostgreSQL".to_string()),
+        }
+    }
+
+    /// Run a statement and return the number of affected rows
+    async fn execute(&mut self, statement: &SqlBuilder) -> Result<u64, String> {
+        match self {
+            SqlConn::Postgres(client) => {
+                let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> =
+                    statement.params.iter().map(|p| p as &(dyn tokio_postgres::types::ToSql + Sync)).collect();
+                client.execute(statement.sql.as_str(), &params).await.map_err(format_pg_error)
+            }
+            SqlConn::MySql(conn) => {
+                use mysql_async::prelude::Queryable;
+                conn.exec_drop(statement.sql.as_str(), mysql_params(&statement.params)).await.map_err(|e| e.to_string())?;
+                Ok(conn.affected_rows())
+            }
+        }
+    }
+
+    /// Run statements without parameters (transaction control, SET, ...)
+    async fn batch(&mut self, sql: &str) -> Result<(), String> {
+        match self {
+            SqlConn::Postgres(client) => client.batch_execute(sql).await.map_err(format_pg_error),
+            SqlConn::MySql(conn) => {
+                use mysql_async::prelude::Queryable;
+                conn.query_drop(sql).await.map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    async fn close(self) {
+        if let SqlConn::MySql(conn) = self {
+            let _ = conn.disconnect().await;
+        }
+    }
+
+    /// Run free-form SQL (one or more statements) keeping every result set, up to `max_rows` rows each
+    /// Completed statements are pushed to `results` as they finish, so after an error
+    /// (or a cancel) it holds the ones that ran before the failing statement.
+    async fn run_script(&mut self, sql: &str, max_rows: usize, results: &mut Vec<StatementResult>) -> Result<(), String> {
+        match self {
+            SqlConn::Postgres(client) => {
+                let mut current: Option<StatementResult> = None;
+                let messages = client.simple_query_raw(sql).await.map_err(format_pg_error)?;
+                tokio::pin!(messages);
+                while let Some(message) = messages.next().await {
+                    match message.map_err(format_pg_error)? {
+                        tokio_postgres::SimpleQueryMessage::RowDescription(columns) => {
+                            current = Some(StatementResult {
+                                columns: columns.iter().map(|c| c.name().to_string()).collect(),
+                                ..Default::default()
+                            });
+                        }
+                        tokio_postgres::SimpleQueryMessage::Row(row) => {
+                            let result = current.get_or_insert_with(|| StatementResult {
+                                columns: row.columns().iter().map(|c| c.name().to_string()).collect(),
+                                ..Default::default()
+                            });
+                            if result.rows.len() < max_rows {
+                                result.rows.push((0..row.len()).map(|i| row.get(i).map(|v| json!(v)).unwrap_or(serde_json::Value::Null)).collect());
+                            } else {
+                                result.truncated = true;
+                            }
+                        }
+                        tokio_postgres::SimpleQueryMessage::CommandComplete(count) => {
+                            let mut result = current.take().unwrap_or_default();
+                            result.affected_rows = Some(count);
+                            results.push(result);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            SqlConn::MySql(conn) => {
+                use mysql_async::prelude::Queryable;
+                let mut query = conn.query_iter(sql).await.map_err(|e| e.to_string())?;
+                while !query.is_empty() {
+                    let mut result = StatementResult {
+                        columns: query.columns_ref().iter().map(|c| c.name_str().to_string()).collect(),
+                        ..Default::default()
+                    };
+                    // Se cuentan todas las filas, también las que no caben en `max_rows`
+                    let mut row_count = 0;
+                    while let Some(row) = query.next().await.map_err(|e| e.to_string())? {
+                        row_count += 1;
+                        if result.rows.len() < max_rows {
+                            result.rows.push((0..row.len()).map(|i| row.as_ref(i).map(mysql_value_to_json).unwrap_or(serde_json::Value::Null)).collect());
+                        } else {
+                            result.truncated = true;
+                        }
+                    }
+                    result.affected_rows = Some(if result.columns.is_empty() { query.affected_rows() } else { row_count });
+                    results.push(result);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle to cancel the statement this connection is running, from another task
+    fn cancel_handle(&self) -> SqlCancelHandle {
+        match self {
+            SqlConn::Postgres(client) => SqlCancelHandle::Postgres(client.cancel_token()),
+            SqlConn::MySql(conn) => SqlCancelHandle::MySql(conn.id()),
+        }
+    }
+}
+
+enum SqlCancelHandle {
+    Postgres(tokio_postgres::CancelToken),
+    /// Connection ID for `KILL QUERY`
+    MySql(u32),
+}
+
+impl SqlCancelHandle {
+    async fn cancel(&self, docker: &Docker, target: &DbTarget) -> Result<(), String> {
+        match self {
+            SqlCancelHandle::Postgres(token) => token.cancel_query(tokio_postgres::NoTls).await.map_err(format_pg_error),
+            SqlCancelHandle::MySql(id) => {
+                let mut conn = SqlConn::connect(docker, target, Some("")).await?;
+                let result = conn.batch(&format!("KILL QUERY {}", id)).await;
+                conn.close().await;
+                result
+            }
+        }
+    }
+}
+
+/// One result set (or command) of a script run through a driver connection
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StatementResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// Rows affected (or returned, for queries)
+    pub affected_rows: Option<u64>,
+    /// More rows than the limit were returned
+    pub truncated: bool,
+}
+
+fn mysql_params(params: &[Option<String>]) -> mysql_async::Params {
+    if params.is_empty() {
+        return mysql_async::Params::Empty;
+    }
+    mysql_async::Params::Positional(
+        params
+            .iter()
+            .map(|p| match p {
+                Some(text) => mysql_async::Value::Bytes(text.clone().into_bytes()),
+                None => mysql_async::Value::NULL,
+            })
+            .collect(),
+    )
+}
+
+fn mysql_row_to_json(row: &mysql_async::Row) -> serde_json::Map<String, serde_json::Value> {
+    row.columns_ref()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let value = row.as_ref(i).map(mysql_value_to_json).unwrap_or(serde_json::Value::Null);
+            (column.name_str().to_string(), value)
+        })
+        .collect()
+}
+
+/// PostgreSQL errors with the server message and detail instead of just "db error"
+fn format_pg_error(e: tokio_postgres::Error) -> String {
+    match e.as_db_error() {
+        Some(db) => match db.detail() {
+            Some(detail) => format!("{}: {} ({})", db.severity(), db.message(), detail),
+            None => format!("{}: {}", db.severity(), db.message()),
+        },
+        None => e.to_string(),
+    }
+}
+
+// ===== TABLE DATA BROWSER =====
+
+const TABLE_PAGE_DEFAULT_LIMIT: u32 = 100;
+const TABLE_PAGE_MAX_LIMIT: u32 = 1000;
+
+/// A table inside a container: `schema` is only used by PostgreSQL (default `public`)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TableRef {
+    pub database: Option<String>,
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+    Ilike,
+    IsNull,
+    IsNotNull,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableFilter {
+    pub column: String,
+    pub op: FilterOp,
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableSort {
+    pub column: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TablePageRequest {
+    #[serde(flatten)]
+    pub table: TableRef,
+    pub filters: Vec<TableFilter>,
+    pub sort: Vec<TableSort>,
+    pub limit: Option<u32>,
+    /// LIMIT/OFFSET pagination
+    pub offset: Option<u64>,
+    /// Keyset pagination: the `next_cursor` of the previous page
+    pub after: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TablePage {
+    pub columns: Vec<TableColumn>,
+    pub primary_key: Vec<String>,
+    pub rows: Vec<serde_json::Map<String, serde_json::Value>>,
+    pub has_more: bool,
+    /// Pass as `after` to get the next page with keyset pagination (tables with a primary key)
+    pub next_cursor: Option<Vec<serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowMutationResult {
+    pub affected_rows: u64,
+    /// The row as stored after the change (not for deletes)
+    pub row: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// Columns and primary key of a table, used to validate identifiers coming from the UI
+#[derive(Debug, Clone, Default)]
+struct TableMeta {
+    qualified_name: String,
+    columns: Vec<TableColumn>,
+    primary_key: Vec<String>,
+}
+
+impl TableMeta {
+    fn column(&self, name: &str) -> Result<&TableColumn, String> {
+        self.columns
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| format!("Unknown column '{}'", name))
+    }
+}
+
+async fn load_table_meta(conn: &mut SqlConn, db_type: &DatabaseType, table: &TableRef, database: &str) -> Result<TableMeta, String> {
+    let (schema, columns_sql, pk_sql) = match db_type {
+        DatabaseType::PostgreSQL => (
+            table.schema.clone().unwrap_or_else(|| "public".to_string()),
+            "SELECT a.attname AS name, format_type(a.atttypid, a.atttypmod) AS data_type FROM pg_attribute a \
+             WHERE a.attrelid = to_regclass(quote_ident(",
+            "SELECT a.attname AS name FROM pg_index i CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY k(attnum, ord) \
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum \
+             WHERE i.indisprimary AND i.indrelid = to_regclass(quote_ident(",
+        ),
+        _ => (
+            database.to_string(),
+            "SELECT COLUMN_NAME AS name, COLUMN_TYPE AS data_type FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = ",
+            "SELECT COLUMN_NAME AS name FROM information_schema.STATISTICS WHERE INDEX_NAME = 'PRIMARY' AND TABLE_SCHEMA = ",
+        ),
+    };
+
+    let build = |prefix: &str, suffix: &str| {
+        let mut sql = SqlBuilder::new(db_type);
+        sql.push(prefix);
+        if *db_type == DatabaseType::PostgreSQL {
+            sql.bind(Some(schema.clone()), None).push(") || '.' || quote_ident(").bind(Some(table.table.clone()), None).push("))").push(suffix);
+        } else {
+            sql.bind(Some(schema.clone()), None).push(" AND TABLE_NAME = ").bind(Some(table.table.clone()), None).push(suffix);
+        }
+        sql
+    };
+    let (columns_suffix, pk_suffix) = match db_type {
+        DatabaseType::PostgreSQL => (" AND a.attnum > 0 AND NOT a.attisdropped ORDER BY a.attnum", " ORDER BY k.ord"),
+        _ => (" ORDER BY ORDINAL_POSITION", " ORDER BY SEQ_IN_INDEX"),
+    };
+
+    let name = |row: &serde_json::Map<String, serde_json::Value>, key: &str| row.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let columns: Vec<TableColumn> = conn
+        .query_json(&build(columns_sql, columns_suffix))
+        .await?
+        .iter()
+        .map(|r| TableColumn { name: name(r, "name"), data_type: name(r, "data_type") })
+        .collect();
+    if columns.is_empty() {
+        return Err(format!("Table '{}.{}' not found", schema, table.table));
+    }
+    let primary_key = conn.query_json(&build(pk_sql, pk_suffix)).await?.iter().map(|r| name(r, "name")).collect();
+
+    Ok(TableMeta {
+        qualified_name: format!("{}.{}", quote_ident(&schema, db_type), quote_ident(&table.table, db_type)),
+        columns,
+        primary_key,
+    })
+}
+
+/// Build the SELECT for one page; returns the SQL and the columns the page is ordered by
+fn build_table_select(db_type: &DatabaseType, meta: &TableMeta, request: &TablePageRequest, limit: u32) -> Result<(SqlBuilder, Vec<String>), String> {
+    let pg = *db_type == DatabaseType::PostgreSQL;
+    let mut sql = SqlBuilder::new(db_type);
+    sql.push("SELECT * FROM ").push(&meta.qualified_name);
+
+    let mut conditions = 0;
+    let mut next_condition = |sql: &mut SqlBuilder| {
+        sql.push(if conditions == 0 { " WHERE " } else { " AND " });
+        conditions += 1;
+    };
+
+    for filter in &request.filters {
+        let column = meta.column(&filter.column)?;
+        let ident = quote_ident(&column.name, db_type);
+        let value = || filter.value.as_ref().and_then(json_to_sql_param).ok_or_else(|| format!("Filter on '{}' needs a value", column.name));
+        next_condition(&mut sql);
+        match filter.op {
+            FilterOp::IsNull => { sql.push(&format!("{} IS NULL", ident)); }
+            FilterOp::IsNotNull => { sql.push(&format!("{} IS NOT NULL", ident)); }
+            FilterOp::Like if pg => { sql.push(&format!("{}::text LIKE ", ident)).bind(Some(value()?), None); }
+            FilterOp::Ilike if pg => { sql.push(&format!("{}::text ILIKE ", ident)).bind(Some(value()?), None); }
+            FilterOp::Like => { sql.push(&format!("{} LIKE ", ident)).bind(Some(value()?), None); }
+            FilterOp::Ilike => { sql.push(&format!("LOWER({}) LIKE LOWER(", ident)).bind(Some(value()?), None).push(")"); }
+            op => {
+                let operator = match op {
+                    FilterOp::Eq => "=",
+                    FilterOp::Ne => "<>",
+                    FilterOp::Lt => "<",
+                    FilterOp::Lte => "<=",
+                    FilterOp::Gt => ">",
+                    _ => ">=",
+                };
+                sql.push(&format!("{} {} ", ident, operator)).bind(Some(value()?), pg.then_some(column.data_type.as_str()));
+            }
+        }
+    }
+
+    // Orden estable: columnas pedidas y después la clave primaria
+    let mut order: Vec<(String, bool)> = Vec::new();
+    for sort in &request.sort {
+        let column = meta.column(&sort.column)?;
+        order.push((column.name.clone(), sort.descending));
+    }
+    let descending = order.first().map(|(_, d)| *d).unwrap_or(false);
+    for pk in &meta.primary_key {
+        if !order.iter().any(|(c, _)| c == pk) {
+            order.push((pk.clone(), descending));
+        }
+    }
+
+    if let Some(after) = &request.after {
+        if meta.primary_key.is_empty() {
+            return Err("Keyset pagination needs a table with a primary key; use offset instead".to_string());
+        }
+        if order.iter().any(|(_, d)| *d != descending) {
+            return Err("Keyset pagination needs all sort columns in the same direction".to_string());
+        }
+        if after.len() != order.len() {
+            return Err("Cursor does not match the sort columns".to_string());
+        }
+        next_condition(&mut sql);
+        let idents: Vec<String> = order.iter().map(|(c, _)| quote_ident(c, db_type)).collect();
+        sql.push(&format!("({}) {} (", idents.join(", "), if descending { "<" } else { ">" }));
+        for (i, ((column, _), value)) in order.iter().zip(after).enumerate() {
+            if i > 0 {
+                sql.push(", ");
+            }
+            let data_type = meta.column(column)?.data_type.clone();
+            sql.bind(json_to_sql_param(value), pg.then_some(data_type.as_str()));
+        }
+        sql.push(")");
+    }
+
+    if !order.is_empty() {
+        let clauses: Vec<String> = order
+            .iter()
+            .map(|(c, d)| format!("{} {}", quote_ident(c, db_type), if *d { "DESC" } else { "ASC" }))
+            .collect();
+        sql.push(" ORDER BY ").push(&clauses.join(", "));
+    }
+
+    // Una fila de más para saber si hay otra página
+    sql.push(&format!(" LIMIT {}", limit + 1));
+    if request.after.is_none() {
+        if let Some(offset) = request.offset.filter(|o| *o > 0) {
+            sql.push(&format!(" OFFSET {}", offset));
+        }
+    }
+
+    Ok((sql, order.into_iter().map(|(c, _)| c).collect()))
+}
+
+/// WHERE clause matching exactly the row with the given primary key
+fn push_key_condition(sql: &mut SqlBuilder, db_type: &DatabaseType, meta: &TableMeta, key: &serde_json::Map<String, serde_json::Value>) -> Result<(), String> {
+    if meta.primary_key.is_empty() {
+        return Err("Table has no primary key; rows cannot be edited safely".to_string());
+    }
+    sql.push(" WHERE ");
+    for (i, pk) in meta.primary_key.iter().enumerate() {
+        let value = key.get(pk).ok_or_else(|| format!("Missing primary key column '{}'", pk))?;
+        if value.is_null() {
+            return Err(format!("Primary key column '{}' cannot be null", pk));
+        }
+        if i > 0 {
+            sql.push(" AND ");
+        }
+        let data_type = meta.column(pk)?.data_type.clone();
+        sql.push(&format!("{} = ", quote_ident(pk, db_type)))
+            .bind(json_to_sql_param(value), (*db_type == DatabaseType::PostgreSQL).then_some(data_type.as_str()));
+    }
+    Ok(())
+}
+
+enum RowMutation<'a> {
+    Insert(&'a serde_json::Map<String, serde_json::Value>),
+    Update(&'a serde_json::Map<String, serde_json::Value>, &'a serde_json::Map<String, serde_json::Value>),
+    Delete(&'a serde_json::Map<String, serde_json::Value>),
+}
+
+/// Build INSERT/UPDATE/DELETE with bound parameters; on PostgreSQL the changed row is returned as JSON
+fn build_row_mutation(db_type: &DatabaseType, meta: &TableMeta, mutation: &RowMutation) -> Result<SqlBuilder, String> {
+    let pg = *db_type == DatabaseType::PostgreSQL;
+    let mut sql = SqlBuilder::new(db_type);
+    if pg {
+        sql.push("WITH changed AS (");
+    }
+
+    match mutation {
+        RowMutation::Insert(values) => {
+            if values.is_empty() {
+                // DEFAULT VALUES solo existe en PostgreSQL
+                let defaults = if pg { "DEFAULT VALUES" } else { "() VALUES ()" };
+                sql.push(&format!("INSERT INTO {} {}", meta.qualified_name, defaults));
+            } else {
+                let columns = values.keys().map(|c| meta.column(c).map(|c| quote_ident(&c.name, db_type))).collect::<Result<Vec<_>, _>>()?;
+                sql.push(&format!("INSERT INTO {} ({}) VALUES (", meta.qualified_name, columns.join(", ")));
+                for (i, (column, value)) in values.iter().enumerate() {
+                    if i > 0 {
+                        sql.push(", ");
+                    }
+                    let data_type = meta.column(column)?.data_type.clone();
+                    sql.bind(json_to_sql_param(value), pg.then_some(data_type.as_str()));
+                }
+                sql.push(")");
+            }
+        }
+        RowMutation::Update(key, changes) => {
+            if changes.is_empty() {
+                return Err("No changes to save".to_string());
+            }
+            sql.push(&format!("UPDATE {} SET ", meta.qualified_name));
+            for (i, (column, value)) in changes.iter().enumerate() {
+                if i > 0 {
+                    sql.push(", ");
+                }
+                let data_type = meta.column(column)?.data_type.clone();
+                sql.push(&format!("{} = ", quote_ident(column, db_type))).bind(json_to_sql_param(value), pg.then_some(data_type.as_str()));
+            }
+            push_key_condition(&mut sql, db_type, meta, key)?;
+        }
+        RowMutation::Delete(key) => {
+            sql.push(&format!("DELETE FROM {}", meta.qualified_name));
+            push_key_condition(&mut sql, db_type, meta, key)?;
+        }
+    }
+
+    if pg {
+        sql.push(" RETURNING *) SELECT row_to_json(changed)::text FROM changed");
+    }
+    Ok(sql)
+}
+
+/// Execute a row mutation in a transaction that is rolled back if it touches more than one row
+async fn apply_row_mutation(conn: &mut SqlConn, db_type: &DatabaseType, meta: &TableMeta, mutation: RowMutation<'_>) -> Result<RowMutationResult, String> {
+    let statement = build_row_mutation(db_type, meta, &mutation)?;
+    let begin = if *db_type == DatabaseType::PostgreSQL { "BEGIN" } else { "START TRANSACTION" };
+    conn.batch(begin).await?;
+
+    let outcome = async {
+        match conn {
+            SqlConn::Postgres(_) => {
+                let rows = conn.query_json_column(&statement).await?;
+                Ok::<_, String>((rows.len() as u64, rows.into_iter().next()))
+            }
+            SqlConn::MySql(_) => {
+                let affected = conn.execute(&statement).await?;
+                let insert_id = match conn {
+                    SqlConn::MySql(c) => c.last_insert_id(),
+                    _ => None,
+                };
+                Ok((affected, insert_id.map(|id| {
+                    let mut row = serde_json::Map::new();
+                    row.insert("__insert_id".to_string(), json!(id));
+                    row
+                })))
+            }
+        }
+    }
+    .await;
+
+    let (affected_rows, row) = match outcome {
+        Ok(result) if result.0 <= 1 => result,
+        Ok((affected, _)) => {
+            let _ = conn.batch("ROLLBACK").await;
+            return Err(format!("Statement would change {} rows; rolled back", affected));
+        }
+        Err(e) => {
+            let _ = conn.batch("ROLLBACK").await;
+            return Err(e);
+        }
+    };
+    conn.batch("COMMIT").await?;
+
+    if !matches!(mutation, RowMutation::Insert(_)) && affected_rows == 0 {
+        return Err("Row not found (it may have been changed or deleted)".to_string());
+    }
+
+    // MySQL no tiene RETURNING: se vuelve a leer la fila por su clave
+    let row = match (db_type, &mutation) {
+        (DatabaseType::PostgreSQL, _) | (_, RowMutation::Delete(_)) => row.filter(|_| !matches!(mutation, RowMutation::Delete(_))),
+        (_, RowMutation::Insert(values)) => {
+            let mut key = (*values).clone();
+            if let (Some(id), [pk]) = (row.as_ref().and_then(|r| r.get("__insert_id")).filter(|id| id.as_u64() != Some(0)), meta.primary_key.as_slice()) {
+                key.entry(pk.clone()).or_insert_with(|| id.clone());
+            }
+            fetch_row_by_key(conn, db_type, meta, &key).await
+        }
+        (_, RowMutation::Update(key, changes)) => {
+            let mut key = (*key).clone();
+            for pk in &meta.primary_key {
+                if let Some(value) = changes.get(pk) {
+                    key.insert(pk.clone(), value.clone());
+                }
+            }
+            fetch_row_by_key(conn, db_type, meta, &key).await
+        }
+    };
+
+    Ok(RowMutationResult { affected_rows, row })
+}
+
+async fn fetch_row_by_key(
+    conn: &mut SqlConn,
+    db_type: &DatabaseType,
+    meta: &TableMeta,
+    key: &serde_json::Map<String, serde_json::Value>,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let mut sql = SqlBuilder::new(db_type);
+    sql.push(&format!("SELECT * FROM {}", meta.qualified_name));
+    push_key_condition(&mut sql, db_type, meta, key).ok()?;
+    conn.query_json(&sql).await.ok()?.into_iter().next()
+}
+
+/// Connect and load table metadata for the table commands
+async fn open_table(docker: &Docker, container_id: &str, table: &TableRef) -> Result<(DbTarget, SqlConn, TableMeta), String> {
+    let target = resolve_db_target(docker, container_id).await?;
+    if !matches!(target.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB) {
+        return Err(format!("The table browser is not available for {}", target.db_type.to_string()));
+    }
+    let database = table.database.clone().filter(|d| !d.is_empty()).unwrap_or_else(|| target.database.clone());
+    let mut conn = SqlConn::connect(docker, &target, Some(&database)).await?;
+    match load_table_meta(&mut conn, &target.db_type, table, &database).await {
+        Ok(meta) => Ok((target, conn, meta)),
+        Err(e) => {
+            conn.close().await;
+            Err(e)
+        }
+    }
+}
+
+/// Get one page of table rows with filters and sorting
+///
+/// Use `offset` for LIMIT/OFFSET pagination or pass the previous page's `next_cursor`
+/// as `after` for keyset pagination (faster on large tables, needs a primary key).
+///
+/// # Arguments
+/// * `container_id` - Container ID or name
+/// * `request` - Table, filters, sort, limit and offset/cursor
+///
+/// # Returns
+/// * `Ok(TablePage)` - Rows as JSON objects plus column types and primary key
+/// * `Err(String)` - Unknown table/column or SQL error
+#[tauri::command]
+async fn browse_table(container_id: String, request: TablePageRequest, state: State<'_, AppState>) -> Result<TablePage, String> {
+    let docker = state.docker();
+    let (target, mut conn, meta) = open_table(&docker, &container_id, &request.table).await?;
+
+    let limit = request.limit.unwrap_or(TABLE_PAGE_DEFAULT_LIMIT).clamp(1, TABLE_PAGE_MAX_LIMIT);
+    let result = match build_table_select(&target.db_type, &meta, &request, limit) {
+        Ok((sql, order)) => conn.query_json(&sql).await.map(|rows| (rows, order)),
+        Err(e) => Err(e),
+    };
+    conn.close().await;
+    let (mut rows, order) = result?;
+
+    let has_more = rows.len() > limit as usize;
+    rows.truncate(limit as usize);
+    let next_cursor = if has_more && !meta.primary_key.is_empty() {
+        rows.last().map(|row| order.iter().map(|c| row.get(c).cloned().unwrap_or(serde_json::Value::Null)).collect())
+    } else {
+        None
+    };
+
+    Ok(TablePage { columns: meta.columns, primary_key: meta.primary_key, rows, has_more, next_cursor })
+}
+
+/// Insert a row; `values` maps column names to values (omitted columns get their default)
+#[tauri::command]
+async fn insert_table_row(
+    container_id: String,
+    table: TableRef,
+    values: serde_json::Map<String, serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<RowMutationResult, String> {
+    let docker = state.docker();
+    let (target, mut conn, meta) = open_table(&docker, &container_id, &table).await?;
+    let result = apply_row_mutation(&mut conn, &target.db_type, &meta, RowMutation::Insert(&values)).await;
+    conn.close().await;
+    result
+}
+
+/// Update the row identified by its primary key
+///
+/// # Arguments
+/// * `key` - Primary key column values of the row
+/// * `changes` - Columns to change and their new values
+#[tauri::command]
+async fn update_table_row(
+    container_id: String,
+    table: TableRef,
+    key: serde_json::Map<String, serde_json::Value>,
+    changes: serde_json::Map<String, serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<RowMutationResult, String> {
+    let docker = state.docker();
+    let (target, mut conn, meta) = open_table(&docker, &container_id, &table).await?;
+    let result = apply_row_mutation(&mut conn, &target.db_type, &meta, RowMutation::Update(&key, &changes)).await;
+    conn.close().await;
+    result
+}
+
+/// Delete the row identified by its primary key
+#[tauri::command]
+async fn delete_table_row(
+    container_id: String,
+    table: TableRef,
+    key: serde_json::Map<String, serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<RowMutationResult, String> {
+    let docker = state.docker();
+    let (target, mut conn, meta) = open_table(&docker, &container_id, &table).await?;
+    let result = apply_row_mutation(&mut conn, &target.db_type, &meta, RowMutation::Delete(&key)).await;
+    conn.close().await;
+    result
+}
+
+// ===== REDIS KEY BROWSER =====
+
+const REDIS_SCAN_DEFAULT_COUNT: u32 = 200;
+const REDIS_VALUE_DEFAULT_COUNT: u32 = 100;
+/// Los strings se muestran truncados a este tamaño
+const REDIS_STRING_PREVIEW_BYTES: u32 = 1024 * 1024;
+const REDIS_BIGGEST_KEYS_MAX_SCAN: u64 = 50_000;
+const REDIS_KEY_TYPES: &[&str] = &["string", "hash", "list", "set", "zset", "stream"];
+
+/// One SCAN page with type, TTL, length and memory of each key (all in one round trip)
+const REDIS_SCAN_SCRIPT: &str = r#"
+local args = {'SCAN', ARGV[1], 'MATCH', ARGV[2], 'COUNT', ARGV[3]}
+if ARGV[4] ~= '' then table.insert(args, 'TYPE'); table.insert(args, ARGV[4]) end
+local reply = redis.call(unpack(args))
+local keys = {}
+for _, key in ipairs(reply[2]) do
+  local t = redis.call('TYPE', key).ok
+  local len
+  if t == 'string' then len = redis.call('STRLEN', key)
+  elseif t == 'hash' then len = redis.call('HLEN', key)
+  elseif t == 'list' then len = redis.call('LLEN', key)
+  elseif t == 'set' then len = redis.call('SCARD', key)
+  elseif t == 'zset' then len = redis.call('ZCARD', key)
+  elseif t == 'stream' then len = redis.call('XLEN', key) end
+  local ok, mem = pcall(redis.call, 'MEMORY', 'USAGE', key)
+  table.insert(keys, {key = key, type = t, pttl = redis.call('PTTL', key), length = len, memory = ok and mem or nil})
+end
+return cjson.encode({cursor = reply[1], keys = keys})
+"#;
+
+/// Value of a key, paged: list/zset by offset, hash/set by scan cursor, stream by last entry ID
+const REDIS_VALUE_SCRIPT: &str = r#"
+local key, cursor, count, max_bytes = ARGV[1], ARGV[2], tonumber(ARGV[3]), tonumber(ARGV[4])
+local t = redis.call('TYPE', key).ok
+local result = {type = t, pttl = redis.call('PTTL', key)}
+if t == 'string' then
+  result.total = redis.call('STRLEN', key)
+  result.value = redis.call('GETRANGE', key, 0, max_bytes - 1)
+elseif t == 'hash' or t == 'set' then
+  local r = redis.call(t == 'hash' and 'HSCAN' or 'SSCAN', key, cursor, 'COUNT', count)
+  result.total = redis.call(t == 'hash' and 'HLEN' or 'SCARD', key)
+  if r[1] ~= '0' then result.next = r[1] end
+  local items = {}
+  if t == 'hash' then
+    for i = 1, #r[2], 2 do table.insert(items, {field = r[2][i], value = r[2][i + 1]}) end
+  else
+    items = r[2]
+  end
+  result.value = items
+elseif t == 'list' or t == 'zset' then
+  local start = tonumber(cursor) or 0
+  if t == 'list' then
+    result.total = redis.call('LLEN', key)
+    result.value = redis.call('LRANGE', key, start, start + count - 1)
+  else
+    result.total = redis.call('ZCARD', key)
+    local r = redis.call('ZRANGE', key, start, start + count - 1, 'WITHSCORES')
+    local items = {}
+    for i = 1, #r, 2 do table.insert(items, {member = r[i], score = r[i + 1]}) end
+    result.value = items
+  end
+  if start + count < result.total then result.next = tostring(start + count) end
+elseif t == 'stream' then
+  result.total = redis.call('XLEN', key)
+  local r = redis.call('XRANGE', key, cursor == '0' and '-' or '(' .. cursor, '+', 'COUNT', count)
+  local items = {}
+  for _, entry in ipairs(r) do
+    local fields = {}
+    for i = 1, #entry[2], 2 do fields[entry[2][i]] = entry[2][i + 1] end
+    table.insert(items, {id = entry[1], fields = fields})
+  end
+  if #r == count then result.next = r[#r][1] end
+  result.value = items
+end
+return cjson.encode(result)
+"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedisKeyInfo {
+    pub key: String,
+    pub key_type: String,
+    /// Remaining time to live; `None` when the key does not expire
+    pub ttl_ms: Option<i64>,
+    /// Bytes for strings, number of elements for collections
+    pub length: Option<u64>,
+    /// `MEMORY USAGE` in bytes
+    pub memory_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedisScanPage {
+    /// Pass back as `cursor` to continue; `"0"` when the scan is complete
+    pub cursor: String,
+    pub keys: Vec<RedisKeyInfo>,
+    pub done: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedisValue {
+    pub key: String,
+    pub key_type: String,
+    pub ttl_ms: Option<i64>,
+    /// Length of the string or number of elements
+    pub total: u64,
+    /// String for `string`; array of items for collections
+    /// (`{field, value}` for hash, `{member, score}` for zset, `{id, fields}` for stream)
+    pub value: serde_json::Value,
+    pub truncated: bool,
+    /// Cursor for the next page of a collection
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedisBiggestKeys {
+    pub keys: Vec<RedisKeyInfo>,
+    pub scanned: u64,
+    /// False when the scan stopped at the key limit before covering the whole keyspace
+    pub complete: bool,
+}
+
+/// Edits on a single key; any of them creates the key if it does not exist
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum RedisEdit {
+    SetString { value: String },
+    HashSet { field: String, value: String },
+    HashDelete { field: String },
+    ListPush { value: String, #[serde(default)] head: bool },
+    ListSet { index: i64, value: String },
+    ListRemove { value: String },
+    SetAdd { member: String },
+    SetRemove { member: String },
+    ZsetAdd { member: String, score: f64 },
+    ZsetRemove { member: String },
+    StreamAdd { fields: Vec<(String, String)> },
+    StreamDelete { id: String },
+}
+
+fn redis_edit_args(key: &str, edit: &RedisEdit) -> Result<Vec<String>, String> {
+    let args: Vec<&str> = match edit {
+        // KEEPTTL: editar el valor no quita la expiración
+        RedisEdit::SetString { value } => vec!["SET", key, value, "KEEPTTL"],
+        RedisEdit::HashSet { field, value } => vec!["HSET", key, field, value],
+        RedisEdit::HashDelete { field } => vec!["HDEL", key, field],
+        RedisEdit::ListPush { value, head } => vec![if *head { "LPUSH" } else { "RPUSH" }, key, value],
+        RedisEdit::ListSet { index, value } => return Ok(["LSET", key, &index.to_string(), value].iter().map(|s| s.to_string()).collect()),
+        RedisEdit::ListRemove { value } => vec!["LREM", key, "0", value],
+        RedisEdit::SetAdd { member } => vec!["SADD", key, member],
+        RedisEdit::SetRemove { member } => vec!["SREM", key, member],
+        RedisEdit::ZsetAdd { member, score } => {
+            if !score.is_finite() {
+                return Err("Score must be a finite number".to_string());
+            }
+            return Ok(["ZADD", key, &score.to_string(), member].iter().map(|s| s.to_string()).collect());
+        }
+        RedisEdit::ZsetRemove { member } => vec!["ZREM", key, member],
+        RedisEdit::StreamAdd { fields } => {
+            if fields.is_empty() {
+                return Err("A stream entry needs at least one field".to_string());
+            }
+            let mut args = vec!["XADD".to_string(), key.to_string(), "*".to_string()];
+            for (field, value) in fields {
+                args.push(field.clone());
+                args.push(value.clone());
+            }
+            return Ok(args);
+        }
+        RedisEdit::StreamDelete { id } => vec!["XDEL", key, id],
+    };
+    Ok(args.into_iter().map(|s| s.to_string()).collect())
+}
+
+/// cjson codifica las tablas vacías como `{}` en lugar de `[]`
+fn lua_json_array(value: Option<&serde_json::Value>) -> Vec<serde_json::Value> {
+    match value {
+        Some(serde_json::Value::Array(items)) => items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn ttl_from_pttl(pttl: Option<i64>) -> Option<i64> {
+    pttl.filter(|ms| *ms >= 0)
+}
+
+fn parse_redis_scan_reply(output: &str) -> Result<RedisScanPage, String> {
+    let reply: serde_json::Value = serde_json::from_str(output.trim()).map_err(|e| format!("Invalid SCAN reply: {}", e))?;
+    let cursor = reply.get("cursor").and_then(|c| c.as_str()).unwrap_or("0").to_string();
+    let keys = lua_json_array(reply.get("keys"))
+        .iter()
+        .map(|k| RedisKeyInfo {
+            key: k.get("key").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            key_type: k.get("type").and_then(|v| v.as_str()).unwrap_or("none").to_string(),
+            ttl_ms: ttl_from_pttl(k.get("pttl").and_then(|v| v.as_i64())),
+            length: k.get("length").and_then(|v| v.as_u64()),
+            memory_bytes: k.get("memory").and_then(|v| v.as_u64()),
+        })
+        .collect();
+    Ok(RedisScanPage { done: cursor == "0", cursor, keys })
+}
+
+fn parse_redis_value_reply(key: &str, output: &str) -> Result<RedisValue, String> {
+    let reply: serde_json::Value = serde_json::from_str(output.trim()).map_err(|e| format!("Invalid value reply: {}", e))?;
+    let key_type = reply.get("type").and_then(|v| v.as_str()).unwrap_or("none").to_string();
+    if key_type == "none" {
+        return Err(format!("Key '{}' does not exist", key));
+    }
+    let total = reply.get("total").and_then(|v| v.as_u64()).unwrap_or(0);
+    let (value, truncated) = if key_type == "string" {
+        let value = reply.get("value").cloned().unwrap_or(serde_json::Value::Null);
+        let length = value.as_str().map(|s| s.len() as u64).unwrap_or(0);
+        (value, length < total)
+    } else {
+        (serde_json::Value::Array(lua_json_array(reply.get("value"))), false)
+    };
+    Ok(RedisValue {
+        key: key.to_string(),
+        key_type,
+        ttl_ms: ttl_from_pttl(reply.get("pttl").and_then(|v| v.as_i64())),
+        total,
+        value,
+        truncated,
+        next_cursor: reply.get("next").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    })
+}
+
+/// Keep the `limit` keys with the most memory, largest first
+fn keep_biggest_keys(biggest: &mut Vec<RedisKeyInfo>, keys: Vec<RedisKeyInfo>, limit: usize) {
+    biggest.extend(keys);
+    biggest.sort_by_key(|k| std::cmp::Reverse(k.memory_bytes.unwrap_or(0)));
+    biggest.truncate(limit);
+}
+
+async fn resolve_redis_target(docker: &Docker, container_id: &str) -> Result<DbTarget, String> {
+    let target = resolve_db_target(docker, container_id).await?;
+    if target.db_type != DatabaseType::Redis {
+        return Err(format!("The key browser is only available for Redis, not {}", target.db_type.to_string()));
+    }
+    Ok(target)
+}
+
+async fn redis_scan_page(
+    docker: &Docker,
+    target: &DbTarget,
+    db: Option<u32>,
+    cursor: &str,
+    pattern: &str,
+    key_type: &str,
+    count: u32,
+) -> Result<RedisScanPage, String> {
+    let count = count.to_string();
+    let output = run_redis_command_in_db(docker, target, db, &["EVAL", REDIS_SCAN_SCRIPT, "0", cursor, pattern, &count, key_type]).await?;
+    parse_redis_scan_reply(&output)
+}
+
+/// List keys with SCAN, without blocking the server like `KEYS`
+///
+/// A SCAN step can return few or no keys when a pattern is used, so several steps
+/// are run until `count` keys are found or the scan finishes.
+///
+/// # Arguments
+/// * `container_id` - Redis container ID or name
+/// * `db` - Logical database index (default 0)
+/// * `cursor` - Cursor from the previous page (`None` or `"0"` to start)
+/// * `pattern` - Glob pattern (default `*`)
+/// * `key_type` - Only keys of this type (string, hash, list, set, zset, stream)
+/// * `count` - Approximate page size (default 200)
+///
+/// # Returns
+/// * `Ok(RedisScanPage)` - Keys with type, TTL, length and memory usage
+/// * `Err(String)` - Not a Redis container or redis-cli error
+#[tauri::command]
+async fn scan_redis_keys(
+    container_id: String,
+    db: Option<u32>,
+    cursor: Option<String>,
+    pattern: Option<String>,
+    key_type: Option<String>,
+    count: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<RedisScanPage, String> {
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+
+    let key_type = key_type.filter(|t| !t.is_empty()).unwrap_or_default();
+    if !key_type.is_empty() && !REDIS_KEY_TYPES.contains(&key_type.as_str()) {
+        return Err(format!("Unknown key type '{}'", key_type));
+    }
+    let pattern = pattern.filter(|p| !p.is_empty()).unwrap_or_else(|| "*".to_string());
+    let count = count.unwrap_or(REDIS_SCAN_DEFAULT_COUNT).clamp(10, 5000);
+
+    let mut cursor = cursor.filter(|c| !c.is_empty()).unwrap_or_else(|| "0".to_string());
+    let mut keys = Vec::new();
+    for _ in 0..10 {
+        let page = redis_scan_page(&docker, &target, db, &cursor, &pattern, &key_type, count).await?;
+        keys.extend(page.keys);
+        cursor = page.cursor;
+        if cursor == "0" || keys.len() >= count as usize {
+            break;
+        }
+    }
+
+    Ok(RedisScanPage { done: cursor == "0", cursor, keys })
+}
+
+/// Read the value of a key; collections are returned one page at a time
+///
+/// # Arguments
+/// * `cursor` - `next_cursor` of the previous page (start with `None`)
+/// * `count` - Elements per page (default 100)
+#[tauri::command]
+async fn get_redis_value(
+    container_id: String,
+    db: Option<u32>,
+    key: String,
+    cursor: Option<String>,
+    count: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<RedisValue, String> {
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+
+    let cursor = cursor.filter(|c| !c.is_empty()).unwrap_or_else(|| "0".to_string());
+    let count = count.unwrap_or(REDIS_VALUE_DEFAULT_COUNT).clamp(1, 10_000).to_string();
+    let max_bytes = REDIS_STRING_PREVIEW_BYTES.to_string();
+    let output = run_redis_command_in_db(&docker, &target, db, &["EVAL", REDIS_VALUE_SCRIPT, "0", &key, &cursor, &count, &max_bytes]).await?;
+    parse_redis_value_reply(&key, &output)
+}
+
+/// Apply an edit to a key (set a string, add/remove hash fields, list/set/zset members or stream entries)
+///
+/// # Returns
+/// * `Ok(String)` - Redis reply (e.g. `OK`, number of elements changed or new stream ID)
+/// * `Err(String)` - Wrong type for the key or redis-cli error
+#[tauri::command]
+async fn edit_redis_key(container_id: String, db: Option<u32>, key: String, edit: RedisEdit, state: State<'_, AppState>) -> Result<String, String> {
+    if key.is_empty() {
+        return Err("Key name cannot be empty".to_string());
+    }
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+    let args = redis_edit_args(&key, &edit)?;
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    Ok(run_redis_command_in_db(&docker, &target, db, &args).await?.trim().to_string())
+}
+
+/// Delete keys with UNLINK (memory is freed in the background)
+///
+/// # Returns
+/// * `Ok(u64)` - Number of keys deleted
+#[tauri::command]
+async fn delete_redis_keys(container_id: String, db: Option<u32>, keys: Vec<String>, state: State<'_, AppState>) -> Result<u64, String> {
+    if keys.is_empty() {
+        return Ok(0);
+    }
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+    println!("🗑️ Borrando {} claves en {}", keys.len(), target.container_name);
+
+    let mut deleted = 0;
+    for chunk in keys.chunks(500) {
+        let mut args = vec!["UNLINK"];
+        args.extend(chunk.iter().map(|k| k.as_str()));
+        let output = run_redis_command_in_db(&docker, &target, db, &args).await?;
+        deleted += output.trim().parse::<u64>().map_err(|_| format!("Unexpected UNLINK reply: {}", output.trim()))?;
+    }
+    Ok(deleted)
+}
+
+/// Set or remove the expiration of a key
+///
+/// # Arguments
+/// * `ttl_secs` - Seconds to live; `None` makes the key persistent
+///
+/// # Returns
+/// * `Ok(bool)` - False when the key does not exist (or had no TTL to remove)
+#[tauri::command]
+async fn set_redis_ttl(container_id: String, db: Option<u32>, key: String, ttl_secs: Option<u64>, state: State<'_, AppState>) -> Result<bool, String> {
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+    let ttl = ttl_secs.map(|t| t.max(1).to_string());
+    let args = match &ttl {
+        Some(ttl) => vec!["EXPIRE", key.as_str(), ttl.as_str()],
+        None => vec!["PERSIST", key.as_str()],
+    };
+    Ok(run_redis_command_in_db(&docker, &target, db, &args).await?.trim() == "1")
+}
+
+/// Report the keys using the most memory, scanning at most `max_keys` keys
+///
+/// # Arguments
+/// * `pattern` - Only keys matching this pattern (default `*`)
+/// * `limit` - Number of keys to return (default 20)
+/// * `max_keys` - Stop after scanning this many keys (default 50000)
+#[tauri::command]
+async fn get_redis_biggest_keys(
+    container_id: String,
+    db: Option<u32>,
+    pattern: Option<String>,
+    limit: Option<usize>,
+    max_keys: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<RedisBiggestKeys, String> {
+    let docker = state.docker();
+    let target = resolve_redis_target(&docker, &container_id).await?;
+    let pattern = pattern.filter(|p| !p.is_empty()).unwrap_or_else(|| "*".to_string());
+    let limit = limit.unwrap_or(20).clamp(1, 1000);
+    let max_keys = max_keys.unwrap_or(REDIS_BIGGEST_KEYS_MAX_SCAN);
+
+    let mut biggest = Vec::new();
+    let mut scanned = 0u64;
+    let mut cursor = "0".to_string();
+    loop {
+        let page = redis_scan_page(&docker, &target, db, &cursor, &pattern, "", 1000).await?;
+        scanned += page.keys.len() as u64;
+        keep_biggest_keys(&mut biggest, page.keys, limit);
+        cursor = page.cursor;
+        if cursor == "0" || scanned >= max_keys {
+            break;
+        }
+    }
+
+    Ok(RedisBiggestKeys { keys: biggest, scanned, complete: cursor == "0" })
+}
+
+// ===== MONGODB EXPLORER =====
+
+const MONGO_FIND_DEFAULT_LIMIT: u32 = 50;
+const MONGO_FIND_MAX_LIMIT: u32 = 1000;
+/// countDocuments se corta aquí para no recorrer colecciones enormes
+const MONGO_COUNT_LIMIT: u64 = 100_000;
+
+/// Extended JSON helpers shared by the explorer scripts. mongosh has `EJSON`; the legacy
+/// `mongo` shell of 4.4 images gets a small reviver/serializer for the common types.
+/// Documents that can be edited go out as canonical EJSON (`canonical`), so NumberLong and
+/// integral doubles keep their type when they come back through `ejson.parse`.
+const MONGO_EJSON_PRELUDE: &str = r#"
+var ejson = (typeof EJSON !== 'undefined') ? {
+  parse: function (s) { return EJSON.parse(s, {relaxed: false}); },
+  stringify: function (v) { return EJSON.stringify(v, {relaxed: true}); },
+  canonical: function (v) { return EJSON.stringify(v, {relaxed: false}); }
+} : {
+  parse: function (s) { return JSON.parse(s, function (k, v) {
+    if (v && typeof v === 'object' && !Array.isArray(v)) {
+      if ('$oid' in v) return ObjectId(v.$oid);
+      if ('$date' in v) return new Date(typeof v.$date === 'object' ? Number(v.$date.$numberLong) : v.$date);
+      if ('$numberLong' in v) return NumberLong(v.$numberLong);
+      if ('$numberInt' in v) return NumberInt(v.$numberInt);
+      if ('$numberDecimal' in v) return NumberDecimal(v.$numberDecimal);
+      if ('$numberDouble' in v) return Number(v.$numberDouble);
+      if ('$regularExpression' in v) return new RegExp(v.$regularExpression.pattern, v.$regularExpression.options);
+    }
+    return v; }); },
+  stringify: function (v) {
+    function conv(x) {
+      if (x === null || typeof x !== 'object') return x;
+      if (x instanceof Date) return {$date: x.toISOString()};
+      if (x instanceof ObjectId) return {$oid: x.str};
+      if (x instanceof NumberLong) return {$numberLong: String(x.toNumber())};
+      if (x instanceof NumberInt) return {$numberInt: String(x.toNumber())};
+      if (x instanceof NumberDecimal) return {$numberDecimal: x.toString().replace(/^NumberDecimal\("|"\)$/g, '')};
+      if (x instanceof BinData) return {$binary: {base64: x.base64(), subType: x.subtype().toString(16)}};
+      if (Array.isArray(x)) return x.map(conv);
+      var o = {}; for (var k in x) { if (x.hasOwnProperty(k)) o[k] = conv(x[k]); } return o;
+    }
+    return JSON.stringify(conv(v)); }
+};
+// El shell antiguo guarda los números sin tipo como double: su salida ya conserva los tipos
+if (!ejson.canonical) { ejson.canonical = ejson.stringify; }
+"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MongoCollectionInfo {
+    pub name: String,
+    /// collection, view or timeseries
+    pub kind: String,
+    pub document_count: Option<u64>,
+    pub size_bytes: Option<u64>,
+    pub storage_bytes: Option<u64>,
+    pub avg_document_bytes: Option<u64>,
+    pub index_count: Option<u64>,
+    pub index_size_bytes: Option<u64>,
+    pub capped: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MongoFindRequest {
+    pub database: String,
+    pub collection: String,
+    /// Extended JSON filter, e.g. `{"status": "open", "_id": {"$oid": "..."}}`
+    pub filter: Option<String>,
+    pub projection: Option<String>,
+    pub sort: Option<String>,
+    pub skip: Option<u64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MongoDocumentPage {
+    /// Documents as canonical Extended JSON (relaxed for aggregation results)
+    pub documents: Vec<serde_json::Value>,
+    pub has_more: bool,
+    /// Documents matching the filter (stops counting at 100000)
+    pub total: u64,
+    pub total_exact: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MongoIndex {
+    pub name: String,
+    pub keys: serde_json::Value,
+    pub unique: bool,
+    pub sparse: bool,
+    pub expire_after_secs: Option<u64>,
+    pub partial_filter: Option<serde_json::Value>,
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MongoIndexOptions {
+    pub name: Option<String>,
+    pub unique: bool,
+    pub sparse: bool,
+    pub expire_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MongoAggregationResult {
+    pub documents: Vec<serde_json::Value>,
+    /// True when the pipeline produced more documents than the limit
+    pub truncated: bool,
+}
+
+/// Check that user input is Extended JSON of the expected shape and return it as a JS string literal
+fn ejson_literal(text: Option<&str>, what: &str, expect_array: bool) -> Result<String, String> {
+    let text = text.map(str::trim).filter(|t| !t.is_empty()).unwrap_or(if expect_array { "[]" } else { "{}" });
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|e| format!("Invalid {} (Extended JSON expected): {}", what, e))?;
+    match (expect_array, &value) {
+        (true, serde_json::Value::Array(_)) | (false, serde_json::Value::Object(_)) => {}
+        (true, _) => return Err(format!("The {} must be a JSON array", what)),
+        (false, _) => return Err(format!("The {} must be a JSON object", what)),
+    }
+    serde_json::to_string(text).map_err(|e| e.to_string())
+}
+
+fn mongo_string_literal(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Wrap a script body with the EJSON helpers and `c` bound to the collection (`d` to the database)
+fn mongo_explorer_script(database: &str, collection: Option<&str>, body: &str) -> String {
+    let collection = collection
+        .map(|c| format!("var c = d.getCollection({});", mongo_string_literal(c)))
+        .unwrap_or_default();
+    format!("{} var d = db.getSiblingDB({}); {} {}", MONGO_EJSON_PRELUDE, mongo_string_literal(database), collection, body)
+}
+
+fn mongo_find_script(request: &MongoFindRequest, limit: u32) -> Result<String, String> {
+    let filter = ejson_literal(request.filter.as_deref(), "filter", false)?;
+    let projection = ejson_literal(request.projection.as_deref(), "projection", false)?;
+    let sort = ejson_literal(request.sort.as_deref(), "sort", false)?;
+    let body = format!(
+        "var f = ejson.parse({filter}); var s = ejson.parse({sort}); \
+         var cur = c.find(f, ejson.parse({projection})); if (Object.keys(s).length) {{ cur = cur.sort(s); }} \
+         var docs = cur.skip({skip}).limit({fetch}).toArray(); \
+         var total = c.countDocuments(f, {{limit: {count_limit}}}); \
+         print(ejson.canonical({{documents: docs, total: total}}));",
+        filter = filter,
+        sort = sort,
+        projection = projection,
+        skip = request.skip.unwrap_or(0),
+        fetch = limit + 1,
+        count_limit = MONGO_COUNT_LIMIT,
+    );
+    Ok(mongo_explorer_script(&request.database, Some(&request.collection), &body))
+}
+
+fn parse_mongo_document_page(output: &str, limit: u32) -> Result<MongoDocumentPage, String> {
+    let reply: serde_json::Value = serde_json::from_str(last_json_line(output)).map_err(|e| format!("Unexpected mongo output: {}", e))?;
+    let mut documents = reply.get("documents").and_then(|d| d.as_array()).cloned().unwrap_or_default();
+    let has_more = documents.len() > limit as usize;
+    documents.truncate(limit as usize);
+    let total = reply.get("total").and_then(json_number_as_u64).unwrap_or(0);
+    Ok(MongoDocumentPage { documents, has_more, total, total_exact: total < MONGO_COUNT_LIMIT })
+}
+
+/// Numbers in EJSON may come as plain numbers or wrapped, e.g. `{"$numberLong": "..."}`
+fn json_number_as_u64(value: &serde_json::Value) -> Option<u64> {
+    let wrapped = || {
+        ["$numberLong", "$numberInt", "$numberDouble"]
+            .iter()
+            .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|f| *f >= 0.0)
+            .map(|f| f as u64)
+    };
+    value.as_u64().or_else(|| value.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64)).or_else(wrapped)
+}
+
+async fn run_mongo_explorer(docker: &Docker, target: &DbTarget, script: &str) -> Result<serde_json::Value, String> {
+    let output = run_engine_script(docker, target, Some("admin"), script).await?;
+    serde_json::from_str(last_json_line(&output)).map_err(|e| format!("Unexpected mongo output: {}", e))
+}
+
+async fn resolve_mongo_target(docker: &Docker, container_id: &str) -> Result<DbTarget, String> {
+    let target = resolve_db_target(docker, container_id).await?;
+    if target.db_type != DatabaseType::MongoDB {
+        return Err(format!("The document explorer is only available for MongoDB, not {}", target.db_type.to_string()));
+    }
+    Ok(target)
+}
+
+/// List the collections of a MongoDB database with their stats
+///
+/// # Arguments
+/// * `container_id` - MongoDB container ID or name
+/// * `database` - Database name (see `list_databases`)
+///
+/// # Returns
+/// * `Ok(Vec<MongoCollectionInfo>)` - Collections and views with counts and sizes
+/// * `Err(String)` - Not a MongoDB container or shell error
+#[tauri::command]
+async fn list_mongo_collections(container_id: String, database: String, state: State<'_, AppState>) -> Result<Vec<MongoCollectionInfo>, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let body = "print(ejson.stringify(d.getCollectionInfos().map(function (info) { \
+        var out = {name: info.name, kind: info.type || 'collection', capped: !!(info.options && info.options.capped)}; \
+        if (out.kind === 'collection' || out.kind === 'timeseries') { try { \
+            var st = d.runCommand({collStats: info.name}); \
+            out.document_count = st.count; out.size_bytes = st.size; out.storage_bytes = st.storageSize; \
+            out.avg_document_bytes = st.avgObjSize; out.index_count = st.nindexes; out.index_size_bytes = st.totalIndexSize; \
+        } catch (e) {} } \
+        return out; })));";
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, None, body)).await?;
+
+    let collections = reply
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|c| {
+            let number = |key: &str| c.get(key).and_then(json_number_as_u64);
+            MongoCollectionInfo {
+                name: c.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                kind: c.get("kind").and_then(|v| v.as_str()).unwrap_or("collection").to_string(),
+                document_count: number("document_count"),
+                size_bytes: number("size_bytes"),
+                storage_bytes: number("storage_bytes"),
+                avg_document_bytes: number("avg_document_bytes"),
+                index_count: number("index_count"),
+                index_size_bytes: number("index_size_bytes"),
+                capped: c.get("capped").and_then(|v| v.as_bool()).unwrap_or(false),
+            }
+        })
+        .collect();
+    Ok(collections)
+}
+
+/// Page through documents with a filter, projection and sort (all Extended JSON)
+///
+/// # Returns
+/// * `Ok(MongoDocumentPage)` - Documents as canonical Extended JSON plus the matching count
+/// * `Err(String)` - Invalid JSON or query error
+#[tauri::command]
+async fn find_mongo_documents(container_id: String, request: MongoFindRequest, state: State<'_, AppState>) -> Result<MongoDocumentPage, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let limit = request.limit.unwrap_or(MONGO_FIND_DEFAULT_LIMIT).clamp(1, MONGO_FIND_MAX_LIMIT);
+    let script = mongo_find_script(&request, limit)?;
+    let output = run_engine_script(&docker, &target, Some("admin"), &script).await?;
+    parse_mongo_document_page(&output, limit)
+}
+
+/// Insert a document given as Extended JSON
+///
+/// # Returns
+/// * `Ok(Value)` - The `_id` of the new document (Extended JSON)
+#[tauri::command]
+async fn insert_mongo_document(
+    container_id: String,
+    database: String,
+    collection: String,
+    document: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let body = format!(
+        "var r = c.insertOne(ejson.parse({})); print(ejson.stringify({{id: r.insertedId}}));",
+        ejson_literal(Some(&document), "document", false)?
+    );
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    Ok(reply.get("id").cloned().unwrap_or(serde_json::Value::Null))
+}
+
+/// Replace the document with the given `_id`
+///
+/// # Arguments
+/// * `id` - `_id` of the document as Extended JSON (e.g. `{"$oid": "..."}`)
+/// * `document` - New content; an `_id` field in it is ignored
+///
+/// # Returns
+/// * `Ok(bool)` - False when no document has that `_id`
+#[tauri::command]
+async fn replace_mongo_document(
+    container_id: String,
+    database: String,
+    collection: String,
+    id: serde_json::Value,
+    document: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let id = mongo_string_literal(&id.to_string());
+    let body = format!(
+        "var doc = ejson.parse({}); delete doc._id; var r = c.replaceOne({{_id: ejson.parse({})}}, doc); \
+         print(JSON.stringify({{matched: Number(r.matchedCount)}}));",
+        ejson_literal(Some(&document), "document", false)?,
+        id
+    );
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    Ok(reply.get("matched").and_then(|v| v.as_u64()).unwrap_or(0) > 0)
+}
+
+/// Delete the document with the given `_id`
+#[tauri::command]
+async fn delete_mongo_document(
+    container_id: String,
+    database: String,
+    collection: String,
+    id: serde_json::Value,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let body = format!(
+        "var r = c.deleteOne({{_id: ejson.parse({})}}); print(JSON.stringify({{deleted: Number(r.deletedCount)}}));",
+        mongo_string_literal(&id.to_string())
+    );
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    Ok(reply.get("deleted").and_then(|v| v.as_u64()).unwrap_or(0) > 0)
+}
+
+/// List the indexes of a collection with their size
+#[tauri::command]
+async fn list_mongo_indexes(container_id: String, database: String, collection: String, state: State<'_, AppState>) -> Result<Vec<MongoIndex>, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let body = "var sizes = {}; try { sizes = d.runCommand({collStats: c.getName()}).indexSizes || {}; } catch (e) {} \
+        print(ejson.stringify(c.getIndexes().map(function (ix) { return {name: ix.name, keys: ix.key, unique: !!ix.unique, \
+            sparse: !!ix.sparse, expire_after_secs: ix.expireAfterSeconds, partial_filter: ix.partialFilterExpression, \
+            size_bytes: sizes[ix.name]}; })));";
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), body)).await?;
+
+    let indexes = reply
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .map(|ix| MongoIndex {
+            name: ix.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            keys: ix.get("keys").cloned().unwrap_or(serde_json::Value::Null),
+            unique: ix.get("unique").and_then(|v| v.as_bool()).unwrap_or(false),
+            sparse: ix.get("sparse").and_then(|v| v.as_bool()).unwrap_or(false),
+            expire_after_secs: ix.get("expire_after_secs").and_then(json_number_as_u64),
+            partial_filter: ix.get("partial_filter").cloned().filter(|v| !v.is_null()),
+            size_bytes: ix.get("size_bytes").and_then(json_number_as_u64),
+        })
+        .collect();
+    Ok(indexes)
+}
+
+/// Create an index
+///
+/// # Arguments
+/// * `keys` - Index spec as JSON, e.g. `{"email": 1}` or `{"title": "text"}`
+/// * `options` - Name, unique, sparse and TTL (`expire_after_secs`)
+///
+/// # Returns
+/// * `Ok(String)` - Name of the created index
+#[tauri::command]
+async fn create_mongo_index(
+    container_id: String,
+    database: String,
+    collection: String,
+    keys: String,
+    options: Option<MongoIndexOptions>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let options = options.unwrap_or_default();
+    let mut index_options = serde_json::Map::new();
+    if let Some(name) = options.name.filter(|n| !n.is_empty()) {
+        index_options.insert("name".to_string(), json!(name));
+    }
+    if options.unique {
+        index_options.insert("unique".to_string(), json!(true));
+    }
+    if options.sparse {
+        index_options.insert("sparse".to_string(), json!(true));
+    }
+    if let Some(ttl) = options.expire_after_secs {
+        index_options.insert("expireAfterSeconds".to_string(), json!(ttl));
+    }
+
+    let body = format!(
+        "var name = c.createIndex(ejson.parse({}), {}); print(JSON.stringify({{name: String(name)}}));",
+        ejson_literal(Some(&keys), "index keys", false)?,
+        serde_json::Value::Object(index_options)
+    );
+    println!("📇 Creando índice en {}.{} ({})", database, collection, target.container_name);
+    let reply = run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    Ok(reply.get("name").and_then(|v| v.as_str()).unwrap_or_default().to_string())
+}
+
+/// Drop an index by name (the `_id_` index cannot be dropped)
+#[tauri::command]
+async fn drop_mongo_index(container_id: String, database: String, collection: String, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    if name == "_id_" {
+        return Err("The _id index cannot be dropped".to_string());
+    }
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let body = format!("c.dropIndex({}); print(JSON.stringify({{ok: 1}}));", mongo_string_literal(&name));
+    run_mongo_explorer(&docker, &target, &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    Ok(())
+}
+
+/// Run an aggregation pipeline and return up to `limit` result documents
+///
+/// # Arguments
+/// * `pipeline` - Array of stages as Extended JSON
+/// * `limit` - Maximum documents to return (default 50, max 1000)
+#[tauri::command]
+async fn run_mongo_aggregation(
+    container_id: String,
+    database: String,
+    collection: String,
+    pipeline: String,
+    limit: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<MongoAggregationResult, String> {
+    let docker = state.docker();
+    let target = resolve_mongo_target(&docker, &container_id).await?;
+    let limit = limit.unwrap_or(MONGO_FIND_DEFAULT_LIMIT).clamp(1, MONGO_FIND_MAX_LIMIT);
+    // Se itera el cursor en lugar de añadir $limit, que no es válido tras $out/$merge
+    let body = format!(
+        "var cur = c.aggregate(ejson.parse({}), {{allowDiskUse: true}}); var docs = []; \
+         while (docs.length < {} && cur.hasNext()) {{ docs.push(cur.next()); }} \
+         print(ejson.stringify({{documents: docs, total: 0}}));",
+        ejson_literal(Some(&pipeline), "pipeline", true)?,
+        limit + 1
+    );
+    let output = run_engine_script(&docker, &target, Some("admin"), &mongo_explorer_script(&database, Some(&collection), &body)).await?;
+    let page = parse_mongo_document_page(&output, limit)?;
+    Ok(MongoAggregationResult { documents: page.documents, truncated: page.has_more })
+}
+
+// ===== QUERY HISTORY & SAVED QUERIES =====
+
+const QUERY_HISTORY_LIMIT: usize = 5000;
+const QUERY_LIBRARY_EXPORT_VERSION: u32 = 1;
+
+/// One executed statement
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QueryHistoryEntry {
+    pub id: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub db_type: String,
+    pub database: String,
+    pub statement: String,
+    pub executed_at: String,
+    pub duration_ms: u64,
+    pub row_count: Option<u64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QueryHistoryFilter {
+    /// Case-insensitive text searched in the statement and error
+    pub text: Option<String>,
+    /// Container ID or name
+    pub container: Option<String>,
+    pub database: Option<String>,
+    pub success: Option<bool>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// A `{{name}}` placeholder of a saved query
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct QueryParameter {
+    pub name: String,
+    pub description: Option<String>,
+    pub default: Option<String>,
+}
+
+/// A named snippet. Without `container` and `db_type` it is available everywhere.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SavedQuery {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub statement: String,
+    pub tags: Vec<String>,
+    pub parameters: Vec<QueryParameter>,
+    /// Only for this container (name)
+    pub container: Option<String>,
+    /// Only for this engine (postgresql, mysql, ...)
+    pub db_type: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl SavedQuery {
+    fn in_scope(&self, container: Option<&str>, db_type: Option<&str>) -> bool {
+        let container_ok = match (&self.container, container) {
+            (Some(scope), Some(c)) => scope == c,
+            (Some(_), None) => true,
+            (None, _) => true,
+        };
+        let engine_ok = match (&self.db_type, db_type) {
+            (Some(scope), Some(t)) => scope.eq_ignore_ascii_case(t),
+            _ => true,
+        };
+        container_ok && engine_ok
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SavedQueryFilter {
+    pub text: Option<String>,
+    pub tag: Option<String>,
+    /// Container name: returns global queries plus the ones scoped to it
+    pub container: Option<String>,
+    pub db_type: Option<String>,
+}
+
+/// File format used to share query libraries
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct QueryLibraryExport {
+    pub version: u32,
+    pub exported_at: String,
+    pub queries: Vec<SavedQuery>,
+}
+
+/// History kept in memory plus the number of lines of `history.jsonl`,
+/// which only gets appended to and is compacted once it doubles the limit
+#[derive(Default)]
+struct QueryHistoryLog {
+    entries: std::collections::VecDeque<QueryHistoryEntry>,
+    lines_on_disk: usize,
+}
+
+pub struct QueryLibraryState {
+    dir: std::path::PathBuf,
+    history: std::sync::Mutex<QueryHistoryLog>,
+    saved: std::sync::Mutex<Vec<SavedQuery>>,
+}
+
+impl QueryLibraryState {
+    pub fn load(dir: std::path::PathBuf) -> Self {
+        let (history, torn) = Self::read_history(&dir.join("history.jsonl"));
+        let saved: Vec<SavedQuery> = read_json_file(&dir.join("saved.json")).unwrap_or_default();
+        println!("[Queries] Loaded {} saved queries", saved.len());
+
+        let library = QueryLibraryState {
+            dir,
+            history: std::sync::Mutex::new(history),
+            saved: std::sync::Mutex::new(saved),
+        };
+        // Sin reescribir, la siguiente entrada se pegaría a la línea cortada
+        if torn {
+            if let Err(e) = library.update_history(|_| ()) {
+                eprintln!("[Queries] {}", e);
+            }
+        }
+        library
+    }
+
+    /// Returns the log and whether the file ends in a partially written line
+    fn read_history(path: &std::path::Path) -> (QueryHistoryLog, bool) {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut log = QueryHistoryLog::default();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            log.lines_on_disk += 1;
+            // Una línea a medio escribir (cierre brusco) se descarta sin perder el resto
+            match serde_json::from_str(line) {
+                Ok(entry) => log.entries.push_back(entry),
+                Err(e) => eprintln!("[Queries] Skipping unreadable history line: {}", e),
+            }
+        }
+        while log.entries.len() > QUERY_HISTORY_LIMIT {
+            log.entries.pop_front();
+        }
+        (log, !content.is_empty() && !content.ends_with('\n'))
+    }
+
+    fn history_path(&self) -> std::path::PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    fn write_history(&self, entries: &std::collections::VecDeque<QueryHistoryEntry>) -> Result<(), String> {
+        let mut content = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut content, entry).map_err(|e| format!("Failed to serialize query history: {}", e))?;
+            content.push(b'\n');
+        }
+        write_file_atomic(&self.history_path(), &content)
+    }
+
+    /// Add an executed statement to the history (errors writing the file are only logged)
+    fn record(&self, mut entry: QueryHistoryEntry) {
+        use std::io::Write;
+
+        if entry.id.is_empty() {
+            entry.id = unique_id("q");
+        }
+        if entry.executed_at.is_empty() {
+            entry.executed_at = chrono::Utc::now().to_rfc3339();
+        }
+        let mut log = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let appended = serde_json::to_string(&entry).map_err(|e| e.to_string()).and_then(|line| {
+            fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(self.history_path()).map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())
+        });
+        log.entries.push_back(entry);
+        while log.entries.len() > QUERY_HISTORY_LIMIT {
+            log.entries.pop_front();
+        }
+        if let Err(e) = appended {
+            eprintln!("[Queries] Failed to append to query history: {}", e);
+            return;
+        }
+        log.lines_on_disk += 1;
+
+        if log.lines_on_disk > QUERY_HISTORY_LIMIT * 2 {
+            match self.write_history(&log.entries) {
+                Ok(()) => log.lines_on_disk = log.entries.len(),
+                Err(e) => eprintln!("[Queries] {}", e),
+            }
+        }
+    }
+
+    fn history(&self) -> std::collections::VecDeque<QueryHistoryEntry> {
+        self.history.lock().unwrap_or_else(|e| e.into_inner()).entries.clone()
+    }
+
+    /// Modify the history and rewrite the file while holding the lock
+    fn update_history<R>(&self, f: impl FnOnce(&mut std::collections::VecDeque<QueryHistoryEntry>) -> R) -> Result<R, String> {
+        let mut log = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = log.entries.clone();
+        let result = f(&mut entries);
+        self.write_history(&entries)?;
+        log.lines_on_disk = entries.len();
+        log.entries = entries;
+        Ok(result)
+    }
+
+    fn saved(&self) -> Vec<SavedQuery> {
+        self.saved.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Modify the saved queries and persist them while holding the lock, so
+    /// concurrent saves can't overwrite each other. Nothing changes if `f` or the write fails.
+    fn update_saved<R>(&self, f: impl FnOnce(&mut Vec<SavedQuery>) -> Result<R, String>) -> Result<R, String> {
+        let mut saved = self.saved.lock().unwrap_or_else(|e| e.into_inner());
+        let mut updated = saved.clone();
+        let result = f(&mut updated)?;
+        write_json_file(&self.dir.join("saved.json"), &updated)?;
+        *saved = updated;
+        Ok(result)
+    }
+}
+
+/// Rows reported by psql: the `(N rows)` footer or the command tag (`INSERT 0 N`, `UPDATE N`, ...)
+fn psql_row_count(output: &str) -> Option<u64> {
+    output.lines().rev().map(str::trim).find_map(|line| {
+        if let Some(inner) = line.strip_prefix('(').and_then(|l| l.strip_suffix(')')) {
+            return inner.split_whitespace().next().and_then(|n| n.parse().ok()).filter(|_| inner.contains("row"));
+        }
+        let mut words = line.split_whitespace();
+        match words.next()? {
+            "INSERT" | "UPDATE" | "DELETE" | "SELECT" | "COPY" | "MERGE" | "MOVE" | "FETCH" => words.last()?.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+fn filter_query_history(history: &std::collections::VecDeque<QueryHistoryEntry>, filter: &QueryHistoryFilter) -> Vec<QueryHistoryEntry> {
+    let text = filter.text.as_deref().map(str::to_lowercase).filter(|t| !t.is_empty());
+    history
+        .iter()
+        .rev()
+        .filter(|e| {
+            filter.container.as_deref().is_none_or(|c| e.container_id.starts_with(c) || e.container_name == c)
+                && filter.database.as_deref().is_none_or(|d| e.database == d)
+                && filter.success.is_none_or(|s| e.success == s)
+                && text.as_deref().is_none_or(|t| {
+                    e.statement.to_lowercase().contains(t) || e.error.as_deref().is_some_and(|err| err.to_lowercase().contains(t))
+                })
+        })
+        .skip(filter.offset.unwrap_or(0))
+        .take(filter.limit.unwrap_or(100))
+        .cloned()
+        .collect()
+}
+
+/// Replace `{{name}}` placeholders with the given values or the parameter defaults
+fn render_query_template(query: &SavedQuery, values: &HashMap<String, String>) -> Result<String, String> {
+    let mut statement = query.statement.clone();
+    for parameter in &query.parameters {
+        let value = values
+            .get(&parameter.name)
+            .or(parameter.default.as_ref())
+            .ok_or_else(|| format!("Missing value for parameter '{}'", parameter.name))?;
+        statement = statement.replace(&format!("{{{{{}}}}}", parameter.name), value);
+    }
+    Ok(statement)
+}
+
+fn normalize_saved_query(mut query: SavedQuery, now: &str) -> Result<SavedQuery, String> {
+    if query.name.trim().is_empty() {
+        return Err("Saved query name cannot be empty".to_string());
+    }
+    if query.statement.trim().is_empty() {
+        return Err("Saved query statement cannot be empty".to_string());
+    }
+    query.name = query.name.trim().to_string();
+    query.container = query.container.filter(|c| !c.trim().is_empty());
+    query.db_type = query.db_type.filter(|t| !t.trim().is_empty()).map(|t| t.to_lowercase());
+    query.tags = query.tags.iter().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()).collect();
+    query.tags.sort();
+    query.tags.dedup();
+    query.parameters.retain(|p| !p.name.trim().is_empty());
+    if query.created_at.is_empty() {
+        query.created_at = now.to_string();
+    }
+    query.updated_at = now.to_string();
+    Ok(query)
+}
+
+/// Search the query history, newest first
+///
+/// # Arguments
+/// * `filter` - Text, container, database and success filters plus paging (default 100 entries)
+///
+/// # Returns
+/// * `Ok(Vec<QueryHistoryEntry>)` - Matching statements
+#[tauri::command]
+async fn search_query_history(filter: Option<QueryHistoryFilter>, library: State<'_, QueryLibraryState>) -> Result<Vec<QueryHistoryEntry>, String> {
+    Ok(filter_query_history(&library.history(), &filter.unwrap_or_default()))
+}
+
+/// Clear the query history, for one container (ID or name) or entirely
+///
+/// # Returns
+/// * `Ok(usize)` - Number of entries removed
+#[tauri::command]
+async fn clear_query_history(container: Option<String>, library: State<'_, QueryLibraryState>) -> Result<usize, String> {
+    library.update_history(|history| {
+        let before = history.len();
+        match container.filter(|c| !c.is_empty()) {
+            Some(c) => history.retain(|e| !(e.container_id.starts_with(&c) || e.container_name == c)),
+            None => history.clear(),
+        }
+        before - history.len()
+    })
+}
+
+/// List saved queries, optionally only those available for a container/engine
+///
+/// # Arguments
+/// * `filter` - Text (name, description, statement), tag and scope filters
+#[tauri::command]
+async fn list_saved_queries(filter: Option<SavedQueryFilter>, library: State<'_, QueryLibraryState>) -> Result<Vec<SavedQuery>, String> {
+    let filter = filter.unwrap_or_default();
+    let text = filter.text.as_deref().map(str::to_lowercase).filter(|t| !t.is_empty());
+    let tag = filter.tag.as_deref().map(str::to_lowercase).filter(|t| !t.is_empty());
+
+    let mut queries: Vec<SavedQuery> = library
+        .saved()
+        .into_iter()
+        .filter(|q| q.in_scope(filter.container.as_deref(), filter.db_type.as_deref()))
+        .filter(|q| tag.as_ref().is_none_or(|t| q.tags.contains(t)))
+        .filter(|q| {
+            text.as_deref().is_none_or(|t| {
+                q.name.to_lowercase().contains(t)
+                    || q.statement.to_lowercase().contains(t)
+                    || q.description.as_deref().is_some_and(|d| d.to_lowercase().contains(t))
+            })
+        })
+        .collect();
+    queries.sort_by_key(|q| q.name.to_lowercase());
+    Ok(queries)
+}
+
+/// Create or update a saved query
+///
+/// # Arguments
+/// * `query` - The query; an empty `id` creates a new one
+///
+/// # Returns
+/// * `Ok(SavedQuery)` - The stored query with its ID and timestamps
+#[tauri::command]
+async fn save_query(query: SavedQuery, library: State<'_, QueryLibraryState>) -> Result<SavedQuery, String> {
+    let mut query = normalize_saved_query(query, &chrono::Utc::now().to_rfc3339())?;
+    if query.id.is_empty() {
+        query.id = unique_id("sq");
+    }
+
+    library.update_saved(|saved| {
+        match saved.iter_mut().find(|q| q.id == query.id) {
+            Some(existing) => {
+                query.created_at = existing.created_at.clone();
+                *existing = query.clone();
+            }
+            None => saved.push(query.clone()),
+        }
+        Ok(query)
+    })
+}
+
+/// Delete a saved query
+#[tauri::command]
+async fn delete_saved_query(query_id: String, library: State<'_, QueryLibraryState>) -> Result<String, String> {
+    library.update_saved(|saved| {
+        let before = saved.len();
+        saved.retain(|q| q.id != query_id);
+        if saved.len() == before {
+            return Err(format!("Saved query {} not found", query_id));
+        }
+        Ok(format!("Saved query {} deleted", query_id))
+    })
+}
+
+/// Fill in the `{{parameter}}` placeholders of a saved query
+///
+/// # Returns
+/// * `Ok(String)` - Statement ready to run
+/// * `Err(String)` - Unknown query or a parameter without value or default
+#[tauri::command]
+async fn render_saved_query(query_id: String, values: HashMap<String, String>, library: State<'_, QueryLibraryState>) -> Result<String, String> {
+    let query = library
+        .saved()
+        .into_iter()
+        .find(|q| q.id == query_id)
+        .ok_or_else(|| format!("Saved query {} not found", query_id))?;
+    render_query_template(&query, &values)
+}
+
+/// Export saved queries to a JSON file to share with others
+///
+/// # Arguments
+/// * `path` - Destination file
+/// * `query_ids` - Queries to export (all when omitted)
+///
+/// # Returns
+/// * `Ok(usize)` - Number of queries exported
+#[tauri::command]
+async fn export_saved_queries(path: String, query_ids: Option<Vec<String>>, library: State<'_, QueryLibraryState>) -> Result<usize, String> {
+    let queries: Vec<SavedQuery> = library
+        .saved()
+        .into_iter()
+        .filter(|q| query_ids.as_ref().is_none_or(|ids| ids.contains(&q.id)))
+        .collect();
+    let export = QueryLibraryExport {
+        version: QUERY_LIBRARY_EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        queries,
+    };
+    write_json_file(std::path::Path::new(&path), &export)?;
+    println!("📤 Exportadas {} consultas a {}", export.queries.len(), path);
+    Ok(export.queries.len())
+}
+
+/// Import saved queries from a file created by `export_saved_queries`
+///
+/// Queries with the same name and scope as an existing one replace it; the rest are added.
+///
+/// # Returns
+/// * `Ok(usize)` - Number of queries imported
+#[tauri::command]
+async fn import_saved_queries(path: String, library: State<'_, QueryLibraryState>) -> Result<usize, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let export: QueryLibraryExport = serde_json::from_str(&content).map_err(|e| format!("Invalid query library file: {}", e))?;
+    if export.version > QUERY_LIBRARY_EXPORT_VERSION {
+        return Err(format!("Query library version {} is not supported", export.version));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    library.update_saved(|saved| {
+        let mut imported = 0;
+        for query in export.queries {
+            let mut query = normalize_saved_query(query, &now)?;
+            match saved.iter_mut().find(|q| q.name == query.name && q.container == query.container && q.db_type == query.db_type) {
+                Some(existing) => {
+                    query.id = existing.id.clone();
+                    query.created_at = existing.created_at.clone();
+                    *existing = query;
+                }
+                None => {
+                    query.id = unique_id("sq");
+                    saved.push(query);
+                }
+            }
+            imported += 1;
+        }
+        Ok(imported)
+    })
+}
+
+// ===== QUERY CANCELLATION =====
+
+const DEFAULT_QUERY_TIMEOUT_SECS: u64 = 300;
+/// Tiempo que se espera tras interrumpir una consulta antes de matar el cliente
+const QUERY_CANCEL_GRACE_SECS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryOutcome {
+    Completed,
+    Cancelled,
+    TimedOut,
+}
+
+impl QueryOutcome {
+    fn message(&self, timeout_secs: u64) -> Option<String> {
+        match self {
+            QueryOutcome::Completed => None,
+            QueryOutcome::Cancelled => Some("query cancelled by user".to_string()),
+            QueryOutcome::TimedOut => Some(format!("query timed out after {} seconds", timeout_secs)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunningQueryInfo {
+    pub query_id: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub db_type: String,
+    pub statement: String,
+    pub started_at: String,
+    pub timeout_secs: u64,
+}
+
+struct RunningQuery {
+    info: RunningQueryInfo,
+    cancel: Arc<tokio::sync::Notify>,
+}
+
+/// Keeps a query registered while it runs; dropping it (also on an early `?`) removes the entry
+struct RunningQueryGuard<'a> {
+    running: &'a RunningQueriesState,
+    query_id: String,
+    cancel: Arc<tokio::sync::Notify>,
+}
+
+impl Drop for RunningQueryGuard<'_> {
+    fn drop(&mut self) {
+        self.running.remove(&self.query_id);
+    }
+}
+
+/// Queries currently executing, by query ID, so they can be listed and cancelled
+#[derive(Default)]
+pub struct RunningQueriesState {
+    queries: std::sync::Mutex<HashMap<String, RunningQuery>>,
+}
+
+impl RunningQueriesState {
+    /// Register a query; it stays listed until the returned guard is dropped
+    fn register(&self, query_id: &str, target: &DbTarget, statement: &str, timeout_secs: u64) -> Result<RunningQueryGuard<'_>, String> {
+        let mut queries = self.queries.lock().unwrap_or_else(|e| e.into_inner());
+        if queries.contains_key(query_id) {
+            return Err(format!("Query {} is already running", query_id));
+        }
+        let cancel = Arc::new(tokio::sync::Notify::new());
+        queries.insert(
+            query_id.to_string(),
+            RunningQuery {
+                info: RunningQueryInfo {
+                    query_id: query_id.to_string(),
+                    container_id: target.container_id.clone(),
+                    container_name: target.container_name.clone(),
+                    db_type: target.db_type.to_string(),
+                    statement: statement.chars().take(500).collect(),
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                    timeout_secs,
+                },
+                cancel: cancel.clone(),
+            },
+        );
+        Ok(RunningQueryGuard { running: self, query_id: query_id.to_string(), cancel })
+    }
+
+    fn remove(&self, query_id: &str) {
+        self.queries.lock().unwrap_or_else(|e| e.into_inner()).remove(query_id);
+    }
+
+    /// Ask a running query to stop; false if no query has that ID
+    fn cancel(&self, query_id: &str) -> bool {
+        match self.queries.lock().unwrap_or_else(|e| e.into_inner()).get(query_id) {
+            Some(query) => {
+                // notify_one guarda el permiso aunque nadie esté esperando todavía
+                query.cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn list(&self) -> Vec<RunningQueryInfo> {
+        let mut list: Vec<RunningQueryInfo> = self
+            .queries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(|q| q.info.clone())
+            .collect();
+        list.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        list
+    }
+}
+
+/// Output of a tracked query; `combined` keeps stdout and stderr in arrival order
+#[derive(Debug, Clone)]
+struct TrackedQueryOutput {
+    output: ExecOutput,
+    combined: String,
+    outcome: QueryOutcome,
+    duration_ms: u64,
+}
+
+fn validate_query_id(query_id: &str) -> Result<(), String> {
+    let valid = !query_id.is_empty()
+        && query_id.len() <= 64
+        && query_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid query ID '{}': use up to 64 letters, digits, '-' or '_'", query_id))
+    }
+}
+
+fn new_query_id() -> String {
+    format!("q{}", chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default())
+}
+
+/// Tag that identifies the query's connection on the server (application name)
+fn query_tag(query_id: &str) -> String {
+    format!("ddm-{}", query_id)
+}
+
+/// Env that marks the client process (found later through /proc) and its server connection
+fn query_tracking_env(db_type: &DatabaseType, query_id: &str) -> Vec<String> {
+    let mut env = vec![format!("DDM_QUERY_ID={}", query_id)];
+    if *db_type == DatabaseType::PostgreSQL {
+        env.push(format!("PGAPPNAME={}", query_tag(query_id)));
+    }
+    env
+}
+
+/// Cancel the statement on the server with pg_cancel_backend
+///
+/// Client processes only run tracked queries on PostgreSQL (`exec_sql`); the other engines
+/// go through `SqlConn`, which cancels with the driver.
+async fn cancel_engine_statement(docker: &Docker, target: &DbTarget, query_id: &str) -> Result<(), String> {
+    if target.db_type != DatabaseType::PostgreSQL {
+        return Ok(());
+    }
+    let sql = format!("SELECT pg_cancel_backend(pid) FROM pg_stat_activity WHERE application_name = '{}'", query_tag(query_id));
+    run_engine_script(docker, target, None, &sql).await.map(|_| ())
+}
+
+/// Send a signal to the client process of a query, found by its DDM_QUERY_ID env var
+async fn signal_query_process(docker: &Docker, container_id: &str, query_id: &str, signal: &str) -> Result<(), String> {
+    let script = format!(
+        "for p in /proc/[0-9]*; do if tr '\\0' '\\n' < \"$p/environ\" 2>/dev/null | grep -qx 'DDM_QUERY_ID={}'; then kill -{} \"${{p#/proc/}}\" 2>/dev/null; fi; done; true",
+        query_id, signal
+    );
+    exec_in_container(docker, container_id, vec!["sh".to_string(), "-c".to_string(), script], Vec::new(), 10)
+        .await?
+        .into_result()
+        .map(|_| ())
+}
+
+/// Stop a running query: first on the server, then interrupting the client
+async fn interrupt_query(docker: &Docker, target: &DbTarget, query_id: &str) {
+    if let Err(e) = cancel_engine_statement(docker, target, query_id).await {
+        eprintln!("[Queries] Server-side cancel of {} failed: {}", query_id, e);
+    }
+    if let Err(e) = signal_query_process(docker, &target.container_id, query_id, "INT").await {
+        eprintln!("[Queries] Failed to interrupt client of {}: {}", query_id, e);
+    }
+}
+
+/// Run a client command in the container as a cancellable query with a timeout.
+/// On cancel or timeout the output collected so far is kept.
+#[allow(clippy::too_many_arguments)]
+async fn run_tracked_query(
+    docker: &Docker,
+    running: &RunningQueriesState,
+    target: &DbTarget,
+    query_id: &str,
+    statement: &str,
+    cmd: Vec<String>,
+    env: Vec<String>,
+    timeout_secs: u64,
+) -> Result<TrackedQueryOutput, String> {
+    let running_query = running.register(query_id, target, statement, timeout_secs)?;
+    drive_tracked_query(docker, target, query_id, cmd, env, timeout_secs, &running_query.cancel).await
+}
+
+async fn drive_tracked_query(
+    docker: &Docker,
+    target: &DbTarget,
+    query_id: &str,
+    cmd: Vec<String>,
+    env: Vec<String>,
+    timeout_secs: u64,
+    cancel: &tokio::sync::Notify,
+) -> Result<TrackedQueryOutput, String> {
+    let started = std::time::Instant::now();
+    let exec = docker
+        .create_exec(
+            &target.container_id,
+            CreateExecOptions {
+                cmd: Some(cmd),
+                env: Some(env),
+                attach_stdout: Some(true),
+                attach_stderr: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to create exec: {}", e))?;
+
+    let mut output = ExecOutput::default();
+    let mut combined = String::new();
+    let mut outcome = QueryOutcome::Completed;
+
+    if let StartExecResults::Attached { output: mut stream, .. } = docker
+        .start_exec(&exec.id, None)
+        .await
+        .map_err(|e| format!("Failed to start exec: {}", e))?
+    {
+        let deadline = tokio::time::sleep(std::time::Duration::from_secs(timeout_secs));
+        let grace = tokio::time::sleep(std::time::Duration::from_secs(timeout_secs + QUERY_CANCEL_GRACE_SECS));
+        tokio::pin!(deadline, grace);
+
+        loop {
+            tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(bollard::container::LogOutput::StdErr { message })) => {
+                        let text = String::from_utf8_lossy(&message);
+                        output.stderr.push_str(&text);
+                        combined.push_str(&text);
+                    }
+                    Some(Ok(log)) => {
+                        let text = log.to_string();
+                        output.stdout.push_str(&text);
+                        combined.push_str(&text);
+                    }
+                    Some(Err(e)) => {
+                        eprintln!("[Queries] Exec stream error in {}: {}", query_id, e);
+                        break;
+                    }
+                    None => break,
+                },
+                _ = cancel.notified(), if outcome == QueryOutcome::Completed => {
+                    println!("🛑 Cancelando consulta {} en {}", query_id, target.container_name);
+                    outcome = QueryOutcome::Cancelled;
+                    interrupt_query(docker, target, query_id).await;
+                    grace.as_mut().reset(tokio::time::Instant::now() + std::time::Duration::from_secs(QUERY_CANCEL_GRACE_SECS));
+                }
+                _ = &mut deadline, if outcome == QueryOutcome::Completed => {
+                    println!("⏱️ Consulta {} en {} superó {}s", query_id, target.container_name, timeout_secs);
+                    outcome = QueryOutcome::TimedOut;
+                    interrupt_query(docker, target, query_id).await;
+                    grace.as_mut().reset(tokio::time::Instant::now() + std::time::Duration::from_secs(QUERY_CANCEL_GRACE_SECS));
+                }
+                _ = &mut grace, if outcome != QueryOutcome::Completed => {
+                    // El cliente no terminó tras la interrupción: se mata el proceso
+                    if let Err(e) = signal_query_process(docker, &target.container_id, query_id, "KILL").await {
+                        eprintln!("[Queries] Failed to kill client of {}: {}", query_id, e);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    output.exit_code = docker.inspect_exec(&exec.id).await.ok().and_then(|i| i.exit_code);
+    Ok(TrackedQueryOutput {
+        output,
+        combined,
+        outcome,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Cancel a running query started with a query ID (`exec_sql` and the query runners)
+///
+/// The statement is cancelled on the server (`pg_cancel_backend`, `KILL QUERY`, `killOp`)
+/// and the client process is interrupted; the output collected so far is still returned
+/// by the command that started the query.
+///
+/// # Arguments
+/// * `query_id` - ID passed when starting the query
+///
+/// # Returns
+/// * `Ok(String)` - Confirmation message
+/// * `Err(String)` - No running query with that ID
+#[tauri::command]
+async fn cancel_query(query_id: String, running: State<'_, RunningQueriesState>) -> Result<String, String> {
+    if running.cancel(&query_id) {
+        Ok(format!("Cancelling query {}", query_id))
+    } else {
+        Err(format!("Query {} is not running", query_id))
+    }
+}
+
+/// List the queries currently running, oldest first
+#[tauri::command]
+async fn list_running_queries(running: State<'_, RunningQueriesState>) -> Result<Vec<RunningQueryInfo>, String> {
+    Ok(running.list())
+}
+
+// ===== SQL SESSIONS =====
+
+const SQL_SESSION_DEFAULT_IDLE_SECS: u64 = 15 * 60;
+const SQL_SESSION_REAPER_INTERVAL_SECS: u64 = 60;
+const SQL_SESSION_DEFAULT_MAX_ROWS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionState {
+    #[default]
+    Idle,
+    InTransaction,
+    /// PostgreSQL: a statement failed inside the transaction; only ROLLBACK is accepted
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SqlSessionInfo {
+    pub session_id: String,
+    pub container_id: String,
+    pub container_name: String,
+    pub db_type: String,
+    pub database: String,
+    pub transaction: TransactionState,
+    pub created_at: String,
+    pub last_used_at: String,
+    pub idle_timeout_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionQueryResult {
+    pub query_id: String,
+    pub results: Vec<StatementResult>,
+    pub transaction: TransactionState,
+    pub outcome: QueryOutcome,
+    /// SQL error, cancellation or timeout message
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+struct SqlSessionConn {
+    target: DbTarget,
+    conn: SqlConn,
+}
+
+struct SqlSessionEntry {
+    info: std::sync::Mutex<SqlSessionInfo>,
+    last_used: std::sync::Mutex<std::time::Instant>,
+    conn: Arc<Mutex<SqlSessionConn>>,
+}
+
+impl SqlSessionEntry {
+    fn info(&self) -> SqlSessionInfo {
+        self.info.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn touch(&self, transaction: Option<TransactionState>) -> SqlSessionInfo {
+        *self.last_used.lock().unwrap_or_else(|e| e.into_inner()) = std::time::Instant::now();
+        let mut info = self.info.lock().unwrap_or_else(|e| e.into_inner());
+        info.last_used_at = chrono::Utc::now().to_rfc3339();
+        if let Some(transaction) = transaction {
+            info.transaction = transaction;
+        }
+        info.clone()
+    }
+}
+
+/// Live driver connections kept per editor tab, so transactions and `SET` survive between calls
+#[derive(Default)]
+pub struct SqlSessionsState {
+    sessions: std::sync::Mutex<HashMap<String, Arc<SqlSessionEntry>>>,
+}
+
+impl SqlSessionsState {
+    fn get(&self, session_id: &str) -> Result<Arc<SqlSessionEntry>, String> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| format!("SQL session {} not found (it may have been closed for inactivity)", session_id))
+    }
+
+    fn remove(&self, session_id: &str) -> Option<Arc<SqlSessionEntry>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner()).remove(session_id)
+    }
+
+    /// Sessions idle for longer than their timeout and not running anything
+    fn expired(&self) -> Vec<String> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, entry)| {
+                let idle = entry.last_used.lock().unwrap_or_else(|e| e.into_inner()).elapsed().as_secs();
+                idle >= entry.info().idle_timeout_secs && entry.conn.try_lock().is_ok()
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+/// First two keywords of a statement, upper-cased and skipping leading comments (`START TRANSACTION`, `ROLLBACK TO`, ...)
+fn statement_keywords(statement: &str) -> String {
+    let mut rest = statement.trim_start();
+    loop {
+        if rest.starts_with("--") || rest.starts_with('#') {
+            rest = rest.split_once('\n').map(|(_, r)| r).unwrap_or("").trim_start();
+        } else if rest.starts_with("/*") && !rest.starts_with("/*!") {
+            rest = rest.split_once("*/").map(|(_, r)| r).unwrap_or("").trim_start();
+        } else {
+            break;
+        }
+    }
+    rest.split(|c: char| c.is_whitespace() || c == '(' || c == ';')
+        .filter(|w| !w.is_empty())
+        .take(2)
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Transaction state after running `sql`. Only the first `executed` statements ran;
+/// when that is fewer than the script has, the next one is the statement that failed.
+fn next_transaction_state(db_type: &DatabaseType, current: TransactionState, sql: &str, executed: usize) -> TransactionState {
+    let mysql = matches!(db_type, DatabaseType::MySQL | DatabaseType::MariaDB);
+    let statements = split_sql_statements(sql, db_type);
+    let mut state = current;
+    for statement in statements.iter().take(executed) {
+        let keywords = statement_keywords(&statement.sql);
+        let first = keywords.split(' ').next().unwrap_or_default();
+        state = match first {
+            "BEGIN" | "START" if keywords != "START SLAVE" && keywords != "START REPLICA" => TransactionState::InTransaction,
+            "COMMIT" | "END" | "ABORT" => TransactionState::Idle,
+            "ROLLBACK" if keywords != "ROLLBACK TO" => TransactionState::Idle,
+            // En MySQL el DDL hace commit implícito
+            "CREATE" | "ALTER" | "DROP" | "TRUNCATE" | "RENAME" if mysql => TransactionState::Idle,
+            _ => state,
+        };
+    }
+
+    // En PostgreSQL un error dentro de una transacción la deja abortada; un COMMIT que falla la deshace
+    if let Some(failed) = statements.get(executed).filter(|_| !mysql) {
+        let keywords = statement_keywords(&failed.sql);
+        if matches!(keywords.split(' ').next(), Some("COMMIT" | "END")) {
+            state = TransactionState::Idle;
+        } else if state != TransactionState::Idle {
+            state = TransactionState::Failed;
+        }
+    }
+    state
+}
+
+/// Idle SQL sessions are closed in the background
+fn spawn_sql_session_reaper(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(SQL_SESSION_REAPER_INTERVAL_SECS)).await;
+
+            let sessions = app.state::<SqlSessionsState>();
+            for session_id in sessions.expired() {
+                if let Some(entry) = sessions.remove(&session_id) {
+                    println!("[Sessions] Closing idle SQL session {}", session_id);
+                    close_session_entry(entry).await;
+                    let _ = app.emit("sql-session-closed", json!({ "session_id": session_id, "reason": "idle" }));
+                }
+            }
+        }
+    });
+}
+
+/// Close the connection of a removed session, rolling back an open transaction
+async fn close_session_entry(entry: Arc<SqlSessionEntry>) {
+    {
+        let mut session = entry.conn.lock().await;
+        if entry.info().transaction != TransactionState::Idle {
+            let _ = session.conn.batch("ROLLBACK").await;
+        }
+    }
+    // Con la última referencia se desconecta limpiamente; si no, basta con soltar el cliente
+    if let Ok(entry) = Arc::try_unwrap(entry) {
+        if let Ok(session) = Arc::try_unwrap(entry.conn) {
+            session.into_inner().conn.close().await;
+        }
+    }
+}
+
+/// Result of `run_script_cancellable`: the statements that completed and, if the
+/// script did not finish, the error, cancel or timeout message
+struct ScriptRun {
+    results: Vec<StatementResult>,
+    error: Option<String>,
+    outcome: QueryOutcome,
+    /// The connection is mid-response and must be discarded
+    broken: bool,
+}
+
+/// Run SQL on a driver connection, cancelling it on the server when `cancel` is notified or the
+/// timeout expires. Returns the results so far, how it ended and whether the connection is no longer usable.
+async fn run_script_cancellable(
+    docker: &Docker,
+    target: &DbTarget,
+    conn: &mut SqlConn,
+    sql: &str,
+    max_rows: usize,
+    cancel: &tokio::sync::Notify,
+    timeout_secs: u64,
+) -> ScriptRun {
+    let handle = conn.cancel_handle();
+    let mut results = Vec::new();
+    let (error, outcome, broken) = {
+        let run = conn.run_script(sql, max_rows, &mut results);
+        tokio::pin!(run);
+        let deadline = tokio::time::sleep(std::time::Duration::from_secs(timeout_secs));
+        tokio::pin!(deadline);
+
+        let outcome = tokio::select! {
+            result = &mut run => Err(result.err()),
+            _ = cancel.notified() => Ok(QueryOutcome::Cancelled),
+            _ = &mut deadline => Ok(QueryOutcome::TimedOut),
+        };
+        match outcome {
+            Err(error) => (error, QueryOutcome::Completed, false),
+            Ok(outcome) => {
+                if let Err(e) = handle.cancel(docker, target).await {
+                    eprintln!("[Queries] Failed to cancel statement on {}: {}", target.container_name, e);
+                }
+                let message = outcome.message(timeout_secs).unwrap_or_default();
+                match tokio::time::timeout(std::time::Duration::from_secs(QUERY_CANCEL_GRACE_SECS), &mut run).await {
+                    Ok(_) => (Some(message), outcome, false),
+                    // La conexión quedó a mitad de una respuesta: no se puede reutilizar
+                    Err(_) => (
+                        Some(format!("{}; the connection was closed because the server did not stop the statement", message)),
+                        outcome,
+                        true,
+                    ),
+                }
+            }
+        }
+    };
+    ScriptRun { results, error, outcome, broken }
+}
+
+async fn run_in_session(
+    docker: &Docker,
+    running: &RunningQueriesState,
+    entry: &SqlSessionEntry,
+    sql: &str,
+    query_id: &str,
+    timeout_secs: u64,
+    max_rows: usize,
+) -> Result<(SessionQueryResult, bool), String> {
+    let mut session = entry.conn.lock().await;
+    let target = session.target.clone();
+    let running_query = running.register(query_id, &target, sql, timeout_secs)?;
+    let started = std::time::Instant::now();
+    let run = run_script_cancellable(docker, &target, &mut session.conn, sql, max_rows, &running_query.cancel, timeout_secs).await;
+    drop(running_query);
+    drop(session);
+
+    // Cada sentencia completada deja un resultado: la siguiente es la que falló
+    let current = entry.info().transaction;
+    let executed = if run.error.is_none() { usize::MAX } else { run.results.len() };
+    let transaction = next_transaction_state(&target.db_type, current, sql, executed);
+    entry.touch(Some(transaction));
+
+    Ok((
+        SessionQueryResult {
+            query_id: query_id.to_string(),
+            results: run.results,
+            transaction,
+            outcome: run.outcome,
+            error: run.error,
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+        run.broken,
+    ))
+}
+
+/// Open a persistent SQL session (one per editor tab)
+///
+/// The connection stays open between calls, so transactions and session settings
+/// (`SET search_path`, variables, temporary tables) persist. Sessions are closed
+/// automatically after `idle_timeout_secs` without use (default 15 minutes).
+///
+/// # Arguments
+/// * `container_id` - PostgreSQL, MySQL or MariaDB container
+/// * `database` - Database to connect to (the container's default when omitted)
+/// * `idle_timeout_secs` - Idle time before the session is closed
+///
+/// # Returns
+/// * `Ok(SqlSessionInfo)` - The new session and its ID
+/// * `Err(String)` - Unsupported engine or connection error
+#[tauri::command]
+async fn open_sql_session(
+    container_id: String,
+    database: Option<String>,
+    idle_timeout_secs: Option<u64>,
+    state: State<'_, AppState>,
+    sessions: State<'_, SqlSessionsState>,
+) -> Result<SqlSessionInfo, String> {
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    let database = database.filter(|d| !d.is_empty()).unwrap_or_else(|| target.database.clone());
+    let conn = SqlConn::connect(&docker, &target, Some(&database)).await?;
+
+    let now = chrono::Utc::now();
+    let info = SqlSessionInfo {
+        session_id: unique_id("s"),
+        container_id: target.container_id.clone(),
+        container_name: target.container_name.clone(),
+        db_type: target.db_type.to_string(),
+        database,
+        transaction: TransactionState::Idle,
+        created_at: now.to_rfc3339(),
+        last_used_at: now.to_rfc3339(),
+        idle_timeout_secs: idle_timeout_secs.unwrap_or(SQL_SESSION_DEFAULT_IDLE_SECS).max(60),
+    };
+    println!("🔌 Sesión SQL {} abierta en {} ({})", info.session_id, info.container_name, info.database);
+
+    let entry = SqlSessionEntry {
+        info: std::sync::Mutex::new(info.clone()),
+        last_used: std::sync::Mutex::new(std::time::Instant::now()),
+        conn: Arc::new(Mutex::new(SqlSessionConn { target, conn })),
+    };
+    sessions
+        .sessions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(info.session_id.clone(), Arc::new(entry));
+    Ok(info)
+}
+
+/// Run SQL in a session; statements run one after another in the same connection
+///
+/// SQL errors, cancellation (`cancel_query`) and timeouts are reported in `error`
+/// together with the resulting transaction state.
+///
+/// # Arguments
+/// * `session_id` - Session from `open_sql_session`
+/// * `sql` - One or more statements
+/// * `query_id` - ID to cancel the query with (generated when omitted)
+/// * `timeout_secs` - Query timeout (default 300)
+/// * `max_rows` - Rows kept per result set (default 1000)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn execute_in_session(
+    session_id: String,
+    sql: String,
+    query_id: Option<String>,
+    timeout_secs: Option<u64>,
+    max_rows: Option<usize>,
+    state: State<'_, AppState>,
+    sessions: State<'_, SqlSessionsState>,
+    running: State<'_, RunningQueriesState>,
+    library: State<'_, QueryLibraryState>,
+) -> Result<SessionQueryResult, String> {
+    let query_id = match query_id.filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    let entry = sessions.get(&session_id)?;
+    let timeout_secs = timeout_secs.unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS).max(1);
+    let max_rows = max_rows.unwrap_or(SQL_SESSION_DEFAULT_MAX_ROWS).clamp(1, 100_000);
+
+    let (result, broken) = run_in_session(&state.docker(), &running, &entry, &sql, &query_id, timeout_secs, max_rows).await?;
+    if broken {
+        sessions.remove(&session_id);
+    }
+
+    let info = entry.info();
+    library.record(QueryHistoryEntry {
+        container_id: info.container_id,
+        container_name: info.container_name,
+        db_type: info.db_type,
+        database: info.database,
+        statement: sql,
+        duration_ms: result.duration_ms,
+        row_count: result.results.last().and_then(|r| r.affected_rows),
+        success: result.error.is_none(),
+        error: result.error.clone(),
+        ..Default::default()
+    });
+    Ok(result)
+}
+
+async fn session_transaction_command(sessions: &SqlSessionsState, session_id: &str, statement: &str) -> Result<SqlSessionInfo, String> {
+    let entry = sessions.get(session_id)?;
+    let mut session = entry.conn.lock().await;
+    let db_type = session.target.db_type.clone();
+    let result = session.conn.batch(statement).await;
+    drop(session);
+
+    let executed = if result.is_ok() { usize::MAX } else { 0 };
+    let transaction = next_transaction_state(&db_type, entry.info().transaction, statement, executed);
+    let info = entry.touch(Some(transaction));
+    result.map(|_| info)
+}
+
+/// Start a transaction in a session
+#[tauri::command]
+async fn begin_session_transaction(session_id: String, sessions: State<'_, SqlSessionsState>) -> Result<SqlSessionInfo, String> {
+    let info = sessions.get(&session_id)?.info();
+    if info.transaction != TransactionState::Idle {
+        return Err("A transaction is already open in this session".to_string());
+    }
+    let statement = if info.db_type == DatabaseType::PostgreSQL.to_string() { "BEGIN" } else { "START TRANSACTION" };
+    session_transaction_command(&sessions, &session_id, statement).await
+}
+
+/// Commit the open transaction of a session
+#[tauri::command]
+async fn commit_session_transaction(session_id: String, sessions: State<'_, SqlSessionsState>) -> Result<SqlSessionInfo, String> {
+    session_transaction_command(&sessions, &session_id, "COMMIT").await
+}
+
+/// Roll back the open transaction of a session
+#[tauri::command]
+async fn rollback_session_transaction(session_id: String, sessions: State<'_, SqlSessionsState>) -> Result<SqlSessionInfo, String> {
+    session_transaction_command(&sessions, &session_id, "ROLLBACK").await
+}
+
+/// Get a session with its current transaction state
+#[tauri::command]
+async fn get_sql_session(session_id: String, sessions: State<'_, SqlSessionsState>) -> Result<SqlSessionInfo, String> {
+    Ok(sessions.get(&session_id)?.info())
+}
+
+/// List open SQL sessions
+#[tauri::command]
+async fn list_sql_sessions(sessions: State<'_, SqlSessionsState>) -> Result<Vec<SqlSessionInfo>, String> {
+    let mut list: Vec<SqlSessionInfo> = sessions
+        .sessions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .map(|entry| entry.info())
+        .collect();
+    list.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(list)
+}
+
+/// Close a session; an open transaction is rolled back
+#[tauri::command]
+async fn close_sql_session(session_id: String, sessions: State<'_, SqlSessionsState>) -> Result<String, String> {
+    let entry = sessions
+        .remove(&session_id)
+        .ok_or_else(|| format!("SQL session {} not found", session_id))?;
+    close_session_entry(entry).await;
+    println!("🔌 Sesión SQL {} cerrada", session_id);
+    Ok(format!("Session {} closed", session_id))
+}
+
+// ===== SQL SCRIPT RUNNER =====
+
+/// A statement of a script and the line it starts on
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SqlScriptStatement {
+    pub sql: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ScriptRunOptions {
+    /// Stop at the first failing statement (default) or continue with the next one
+    pub stop_on_error: bool,
+    /// Run the whole script in one transaction, rolled back if it stops on an error
+    pub transaction: bool,
+    pub query_id: Option<String>,
+    /// Timeout for each statement
+    pub timeout_secs: Option<u64>,
+}
+
+impl Default for ScriptRunOptions {
+    fn default() -> Self {
+        ScriptRunOptions { stop_on_error: true, transaction: false, query_id: None, timeout_secs: None }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptStatementStatus {
+    Ok,
+    Error,
+    Skipped,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptStatementResult {
+    pub index: usize,
+    pub line: usize,
+    /// First 200 characters of the statement
+    pub preview: String,
+    pub status: ScriptStatementStatus,
+    pub duration_ms: u64,
+    pub affected_rows: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptRunReport {
+    pub query_id: String,
+    pub statements: Vec<ScriptStatementResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// With a wrapping transaction: whether it was committed
+    pub committed: Option<bool>,
+    pub outcome: QueryOutcome,
+    pub duration_ms: u64,
+}
+
+/// Split a SQL script into statements.
+///
+/// Handles quoted strings and identifiers, `--`/`#`/`/* */` comments, PostgreSQL
+/// dollar-quoting (`$$ ... $$`, `$tag$ ... $tag$`) and the MySQL client's `DELIMITER` command.
+fn split_sql_statements(script: &str, db_type: &DatabaseType) -> Vec<SqlScriptStatement> {
+    let mysql = matches!(db_type, DatabaseType::MySQL | DatabaseType::MariaDB);
+    let chars: Vec<char> = script.chars().collect();
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut has_code = false;
+    let mut start_line = 1;
+    let mut line = 1;
+    let mut delimiter: Vec<char> = vec![';'];
+    let mut i = 0;
+
+    // Copia chars[from..to] al statement actual contando saltos de línea
+    let take = |from: usize, to: usize, current: &mut String, line: &mut usize| {
+        for c in &chars[from..to.min(chars.len())] {
+            if *c == '\n' {
+                *line += 1;
+            }
+            current.push(*c);
+        }
+    };
+    let find_from = |from: usize, pattern: &[char]| (from..chars.len()).find(|&j| chars[j..].starts_with(pattern));
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let mark_code = |has_code: &mut bool, start_line: &mut usize, line: usize| {
+            if !*has_code {
+                *has_code = true;
+                *start_line = line;
+            }
+        };
+
+        // DELIMITER del cliente mysql: solo al principio de una línea y entre sentencias
+        if mysql && !has_code && (i == 0 || chars[i - 1] == '\n') {
+            let end = find_from(i, &['\n']).unwrap_or(chars.len());
+            let text: String = chars[i..end].iter().collect();
+            let trimmed = text.trim();
+            // get(): el texto puede empezar con caracteres multibyte
+            if trimmed.len() > 10 && trimmed.get(..10).is_some_and(|p| p.eq_ignore_ascii_case("DELIMITER ")) {
+                delimiter = trimmed[10..].trim().chars().collect();
+                current.clear();
+                i = end;
+                continue;
+            }
+        }
+
+        if (c == '-' && next == Some('-') && (!mysql || chars.get(i + 2).is_none_or(|c| c.is_whitespace()))) || (mysql && c == '#') {
+            let end = find_from(i, &['\n']).unwrap_or(chars.len());
+            take(i, end, &mut current, &mut line);
+            i = end;
+        } else if c == '/' && next == Some('*') {
+            // `/*! ... */` de MySQL es código; PostgreSQL admite comentarios anidados
+            if mysql && chars.get(i + 2) == Some(&'!') {
+                mark_code(&mut has_code, &mut start_line, line);
+            }
+            let mut depth = 0;
+            let mut j = i;
+            while j < chars.len() {
+                if chars[j] == '/' && chars.get(j + 1) == Some(&'*') && (depth == 0 || !mysql) {
+                    depth += 1;
+                    j += 2;
+                } else if chars[j] == '*' && chars.get(j + 1) == Some(&'/') {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            take(i, j, &mut current, &mut line);
+            i = j;
+        } else if c == '\'' || c == '"' || (mysql && c == '`') {
+            mark_code(&mut has_code, &mut start_line, line);
+            // E'...' de PostgreSQL y los strings de MySQL admiten escapes con barra invertida
+            let backslash = c != '`'
+                && (mysql || (c == '\'' && i > 0 && matches!(chars[i - 1], 'E' | 'e') && (i < 2 || !is_ident(chars[i - 2]))));
+            let mut j = i + 1;
+            while j < chars.len() {
+                if backslash && chars[j] == '\\' {
+                    j += 2;
+                } else if chars[j] == c {
+                    if chars.get(j + 1) == Some(&c) {
+                        j += 2;
+                    } else {
+                        j += 1;
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            take(i, j, &mut current, &mut line);
+            i = j;
+        } else if !mysql && c == '$' && (i == 0 || !is_ident(chars[i - 1])) {
+            // Dollar-quoting: $$ ... $$ o $tag$ ... $tag$
+            let tag_end = (i + 1..chars.len()).find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'));
+            match tag_end.filter(|&j| chars[j] == '$' && !chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+                Some(j) => {
+                    mark_code(&mut has_code, &mut start_line, line);
+                    let tag: Vec<char> = chars[i..=j].to_vec();
+                    let end = find_from(j + 1, &tag).map(|k| k + tag.len()).unwrap_or(chars.len());
+                    take(i, end, &mut current, &mut line);
+                    i = end;
+                }
+                None => {
+                    mark_code(&mut has_code, &mut start_line, line);
+                    current.push(c);
+                    i += 1;
+                }
+            }
+        } else if chars[i..].starts_with(&delimiter) {
+            if has_code {
+                statements.push(SqlScriptStatement { sql: current.trim().to_string(), line: start_line });
+            }
+            current.clear();
+            has_code = false;
+            i += delimiter.len();
+        } else {
+            if !c.is_whitespace() {
+                mark_code(&mut has_code, &mut start_line, line);
+            }
+            take(i, i + 1, &mut current, &mut line);
+            i += 1;
+        }
+    }
+
+    if has_code {
+        statements.push(SqlScriptStatement { sql: current.trim().to_string(), line: start_line });
+    }
+    statements
+}
+
+/// Split a `.sql` file without running it (to preview the statements)
+///
+/// # Arguments
+/// * `path` - Script file on the host
+/// * `db_type` - Engine dialect (postgresql, mysql, mariadb)
+#[tauri::command]
+async fn split_sql_script(path: String, db_type: String) -> Result<Vec<SqlScriptStatement>, String> {
+    let db_type = DatabaseType::from_label(&db_type).ok_or_else(|| format!("Unknown database type '{}'", db_type))?;
+    let script = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(split_sql_statements(&script, &db_type))
+}
+
+/// Run a `.sql` file statement by statement and report each one
+///
+/// Statements run in order over one driver connection. Progress is emitted as
+/// `sql-script-progress` events; the script can be stopped with `cancel_query`.
+/// With `transaction` and `stop_on_error: false`, PostgreSQL uses a savepoint per
+/// statement so the failures do not abort the transaction. MySQL commits DDL implicitly.
+///
+/// # Arguments
+/// * `container_id` - PostgreSQL, MySQL or MariaDB container
+/// * `path` - Script file on the host
+/// * `database` - Database to run in (the container's default when omitted)
+/// * `options` - Stop on error, wrapping transaction, query ID and statement timeout
+///
+/// # Returns
+/// * `Ok(ScriptRunReport)` - Status, timing and rows affected of each statement
+/// * `Err(String)` - File, connection or transaction error
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn run_sql_script(
+    app: tauri::AppHandle,
+    container_id: String,
+    path: String,
+    database: Option<String>,
+    options: Option<ScriptRunOptions>,
+    state: State<'_, AppState>,
+    running: State<'_, RunningQueriesState>,
+    library: State<'_, QueryLibraryState>,
+) -> Result<ScriptRunReport, String> {
+    let options = options.unwrap_or_default();
+    let query_id = match options.query_id.clone().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    let timeout_secs = options.timeout_secs.unwrap_or(DEFAULT_QUERY_TIMEOUT_SECS).max(1);
+
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    let script = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let statements = split_sql_statements(&script, &target.db_type);
+    if statements.is_empty() {
+        return Err(format!("{} contains no SQL statements", path));
+    }
+
+    let database = database.filter(|d| !d.is_empty()).unwrap_or_else(|| target.database.clone());
+    let mut conn = SqlConn::connect(&docker, &target, Some(&database)).await?;
+    let pg = target.db_type == DatabaseType::PostgreSQL;
+    let savepoints = pg && options.transaction && !options.stop_on_error;
+    if options.transaction {
+        if let Err(e) = conn.batch(if pg { "BEGIN" } else { "START TRANSACTION" }).await {
+            conn.close().await;
+            return Err(e);
+        }
+    }
+
+    println!("📜 Ejecutando {} sentencias de {} en {}", statements.len(), path, target.container_name);
+    let running_query = running.register(&query_id, &target, &format!("-- script {}", path), timeout_secs)?;
+    let started = std::time::Instant::now();
+    let mut results = Vec::with_capacity(statements.len());
+    let mut outcome = QueryOutcome::Completed;
+    let mut stopped = false;
+    let mut broken = false;
+
+    for (index, statement) in statements.iter().enumerate() {
+        let preview: String = statement.sql.chars().take(200).collect();
+        if stopped {
+            results.push(ScriptStatementResult {
+                index,
+                line: statement.line,
+                preview,
+                status: ScriptStatementStatus::Skipped,
+                duration_ms: 0,
+                affected_rows: None,
+                error: None,
+            });
+            continue;
+        }
+
+        if savepoints {
+            let _ = conn.batch("SAVEPOINT ddm_script").await;
+        }
+        let statement_started = std::time::Instant::now();
+        let run = run_script_cancellable(&docker, &target, &mut conn, &statement.sql, 1, &running_query.cancel, timeout_secs).await;
+        let (statement_outcome, statement_broken) = (run.outcome, run.broken);
+
+        let (status, affected_rows, error) = match run.error {
+            None => {
+                if savepoints {
+                    let _ = conn.batch("RELEASE SAVEPOINT ddm_script").await;
+                }
+                (ScriptStatementStatus::Ok, run.results.last().and_then(|r| r.affected_rows), None)
+            }
+            Some(e) => {
+                if savepoints && !statement_broken {
+                    let _ = conn.batch("ROLLBACK TO SAVEPOINT ddm_script").await;
+                }
+                if statement_outcome != QueryOutcome::Completed {
+                    outcome = statement_outcome;
+                    stopped = true;
+                    (ScriptStatementStatus::Cancelled, None, Some(e))
+                } else {
+                    stopped = options.stop_on_error;
+                    (ScriptStatementStatus::Error, None, Some(e))
+                }
+            }
+        };
+        broken = statement_broken;
+        stopped |= broken;
+
+        let _ = app.emit(
+            "sql-script-progress",
+            json!({ "query_id": query_id, "index": index, "total": statements.len(), "status": status }),
+        );
+        results.push(ScriptStatementResult {
+            index,
+            line: statement.line,
+            preview,
+            status,
+            duration_ms: statement_started.elapsed().as_millis() as u64,
+            affected_rows,
+            error,
+        });
+    }
+    drop(running_query);
+
+    let failed = results.iter().filter(|r| matches!(r.status, ScriptStatementStatus::Error | ScriptStatementStatus::Cancelled)).count();
+    let committed = if options.transaction && !broken {
+        let commit = failed == 0 || (!options.stop_on_error && outcome == QueryOutcome::Completed);
+        match conn.batch(if commit { "COMMIT" } else { "ROLLBACK" }).await {
+            Ok(()) => Some(commit),
+            Err(e) => {
+                eprintln!("[Scripts] Failed to finish transaction: {}", e);
+                Some(false)
+            }
+        }
+    } else if options.transaction {
+        Some(false)
+    } else {
+        None
+    };
+    if !broken {
+        conn.close().await;
+    }
+
+    let report = ScriptRunReport {
+        query_id,
+        succeeded: results.iter().filter(|r| r.status == ScriptStatementStatus::Ok).count(),
+        failed,
+        skipped: results.iter().filter(|r| r.status == ScriptStatementStatus::Skipped).count(),
+        statements: results,
+        committed,
+        outcome,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+
+    library.record(QueryHistoryEntry {
+        container_id: target.container_id.clone(),
+        container_name: target.container_name.clone(),
+        db_type: target.db_type.to_string(),
+        database,
+        statement: format!("-- script {} ({} statements)", path, report.statements.len()),
+        duration_ms: report.duration_ms,
+        row_count: Some(report.statements.iter().filter_map(|s| s.affected_rows).sum()),
+        success: report.failed == 0 && report.outcome == QueryOutcome::Completed,
+        error: report.statements.iter().find_map(|s| s.error.clone()),
+        ..Default::default()
+    });
+    Ok(report)
+}
+
+// ===== DATA EXPORT =====
+
+const PARQUET_ROW_GROUP_SIZE: usize = 10_000;
+const EXPORT_PROGRESS_INTERVAL_MS: u128 = 500;
+const EXPORT_DEFAULT_ROWS_PER_INSERT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Sql,
+    Parquet,
+}
+
+/// What to export: the result of a query or a whole table
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportSource {
+    Query { sql: String, #[serde(default)] database: Option<String> },
+    Table(TableRef),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CsvOptions {
+    /// Single-character field delimiter (default `,`)
+    pub delimiter: String,
+    pub header: bool,
+    /// Text that stands for NULL (written on export, read back as NULL on import)
+    pub null_text: String,
+}
+
+/// Accepts a single ASCII character or `\t`
+fn csv_delimiter(delimiter: &str) -> Result<u8, String> {
+    match delimiter {
+        "\\t" => Ok(b'\t'),
+        d if d.len() == 1 => Ok(d.as_bytes()[0]),
+        d => Err(format!("CSV delimiter must be a single character, got '{}'", d)),
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: ",".to_string(), header: true, null_text: String::new() }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportRequest {
+    pub source: ExportSource,
+    pub format: ExportFormat,
+    /// Destination file on the host
+    pub path: String,
+    /// ID for progress events and `cancel_query` (generated when omitted)
+    #[serde(default)]
+    pub export_id: Option<String>,
+    #[serde(default)]
+    pub csv: CsvOptions,
+    /// Table name used in the INSERT statements (defaults to the source table or `exported_rows`)
+    #[serde(default)]
+    pub sql_table: Option<String>,
+    #[serde(default)]
+    pub rows_per_insert: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportReport {
+    pub export_id: String,
+    pub path: String,
+    pub rows: u64,
+    pub bytes: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportColumnKind {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+    /// json/jsonb (JSON in MySQL): written as nested JSON in JSON Lines, as text elsewhere
+    Json,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportColumn {
+    pub name: String,
+    pub kind: ExportColumnKind,
+}
+
+fn pg_export_kind(pg_type: &tokio_postgres::types::Type) -> ExportColumnKind {
+    use tokio_postgres::types::Type;
+    match *pg_type {
+        Type::INT2 | Type::INT4 | Type::INT8 | Type::OID => ExportColumnKind::Integer,
+        Type::FLOAT4 | Type::FLOAT8 => ExportColumnKind::Float,
+        Type::BOOL => ExportColumnKind::Boolean,
+        Type::JSON | Type::JSONB => ExportColumnKind::Json,
+        // NUMERIC se exporta como texto para no perder precisión
+        _ => ExportColumnKind::Text,
+    }
+}
+
+fn mysql_export_kind(column_type: mysql_async::consts::ColumnType) -> ExportColumnKind {
+    use mysql_async::consts::ColumnType;
+    match column_type {
+        ColumnType::MYSQL_TYPE_TINY
+        | ColumnType::MYSQL_TYPE_SHORT
+        | ColumnType::MYSQL_TYPE_LONG
+        | ColumnType::MYSQL_TYPE_LONGLONG
+        | ColumnType::MYSQL_TYPE_INT24
+        | ColumnType::MYSQL_TYPE_YEAR => ExportColumnKind::Integer,
+        ColumnType::MYSQL_TYPE_FLOAT | ColumnType::MYSQL_TYPE_DOUBLE => ExportColumnKind::Float,
+        ColumnType::MYSQL_TYPE_JSON => ExportColumnKind::Json,
+        _ => ExportColumnKind::Text,
+    }
+}
+
+/// Result column names made unique (`id`, `id_2`, ...) so rows keyed by name don't lose columns
+fn unique_column_names(names: Vec<String>) -> Vec<String> {
+    let mut taken: std::collections::HashSet<String> = names.iter().cloned().collect();
+    let mut seen = std::collections::HashSet::new();
+    names
+        .iter()
+        .map(|name| {
+            if seen.insert(name) {
+                return name.clone();
+            }
+            let unique = (2..).map(|n| format!("{}_{}", name, n)).find(|c| !taken.contains(c)).unwrap_or_default();
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// Normalize a value to its column kind (text protocols return numbers as strings)
+fn typed_export_value(value: serde_json::Value, kind: ExportColumnKind) -> serde_json::Value {
+    match (kind, &value) {
+        (ExportColumnKind::Integer, serde_json::Value::String(s)) => s.parse::<i64>().map(|n| json!(n)).unwrap_or(value),
+        (ExportColumnKind::Float, serde_json::Value::String(s)) => s.parse::<f64>().map(|n| json!(n)).unwrap_or(value),
+        (ExportColumnKind::Boolean, serde_json::Value::String(s)) => match s.as_str() {
+            "t" | "true" | "1" => json!(true),
+            "f" | "false" | "0" => json!(false),
+            _ => value,
+        },
+        (ExportColumnKind::Json, serde_json::Value::String(s)) => serde_json::from_str(s).unwrap_or(value),
+        _ => value,
+    }
+}
+
+/// Text form of a value for CSV cells and Parquet strings (None for NULL)
+fn export_value_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn export_sql_literal(value: &serde_json::Value, db_type: &DatabaseType) -> String {
+    match value {
+        serde_json::Value::Null => "NULL".to_string(),
+        serde_json::Value::Bool(b) => if *b { "TRUE" } else { "FALSE" }.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::String(s) => quote_sql_literal(s, db_type),
+        other => quote_sql_literal(&other.to_string(), db_type),
+    }
+}
+
+/// Destination of exported rows; rows arrive in column order
+trait ExportSink: Send {
+    fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), String>;
+    fn finish(self: Box<Self>) -> Result<(), String>;
+}
+
+struct CsvSink {
+    writer: csv::Writer<std::io::BufWriter<fs::File>>,
+    null_text: String,
+}
+
+impl ExportSink for CsvSink {
+    fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), String> {
+        let cells = row.iter().map(|v| export_value_text(v).unwrap_or_else(|| self.null_text.clone()));
+        self.writer.write_record(cells).map_err(|e| format!("Failed to write CSV: {}", e))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.writer.flush().map_err(|e| format!("Failed to write CSV: {}", e))
+    }
+}
+
+struct JsonlSink {
+    writer: std::io::BufWriter<fs::File>,
+    columns: Vec<String>,
+}
+
+impl ExportSink for JsonlSink {
+    fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), String> {
+        use std::io::Write;
+        let object: serde_json::Map<String, serde_json::Value> = self.columns.iter().cloned().zip(row).collect();
+        serde_json::to_writer(&mut self.writer, &object).map_err(|e| format!("Failed to write JSON: {}", e))?;
+        self.writer.write_all(b"\n").map_err(|e| format!("Failed to write JSON: {}", e))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        use std::io::Write;
+        self.writer.flush().map_err(|e| format!("Failed to write JSON: {}", e))
+    }
+}
+
+struct SqlInsertSink {
+    writer: std::io::BufWriter<fs::File>,
+    db_type: DatabaseType,
+    insert_prefix: String,
+    rows_per_insert: usize,
+    pending: Vec<String>,
+}
+
+impl SqlInsertSink {
+    fn flush_pending(&mut self) -> Result<(), String> {
+        use std::io::Write;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        writeln!(self.writer, "{}\n{};", self.insert_prefix, self.pending.join(",\n")).map_err(|e| format!("Failed to write SQL: {}", e))?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+impl ExportSink for SqlInsertSink {
+    fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), String> {
+        let values: Vec<String> = row.iter().map(|v| export_sql_literal(v, &self.db_type)).collect();
+        self.pending.push(format!("({})", values.join(", ")));
+        if self.pending.len() >= self.rows_per_insert {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        use std::io::Write;
+        self.flush_pending()?;
+        self.writer.flush().map_err(|e| format!("Failed to write SQL: {}", e))
+    }
+}
+
+/// Parquet file written one row group at a time, so memory stays bounded
+struct ParquetSink {
+    writer: parquet::file::writer::SerializedFileWriter<fs::File>,
+    columns: Vec<ExportColumn>,
+    buffer: Vec<Vec<serde_json::Value>>,
+}
+
+impl ParquetSink {
+    fn create(file: fs::File, columns: &[ExportColumn]) -> Result<Self, String> {
+        use parquet::basic::{Compression, LogicalType, Repetition, Type as PhysicalType};
+        use parquet::schema::types::Type;
+
+        let fields = columns
+            .iter()
+            .map(|c| {
+                let (physical, logical) = match c.kind {
+                    ExportColumnKind::Integer => (PhysicalType::INT64, None),
+                    ExportColumnKind::Float => (PhysicalType::DOUBLE, None),
+                    ExportColumnKind::Boolean => (PhysicalType::BOOLEAN, None),
+                    ExportColumnKind::Text | ExportColumnKind::Json => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                };
+                Type::primitive_type_builder(&c.name, physical)
+                    .with_repetition(Repetition::OPTIONAL)
+                    .with_logical_type(logical)
+                    .build()
+                    .map(Arc::new)
+                    .map_err(|e| format!("Invalid Parquet column '{}': {}", c.name, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = Type::group_type_builder("schema")
+            .with_fields(fields)
+            .build()
+            .map_err(|e| format!("Invalid Parquet schema: {}", e))?;
+        let properties = parquet::file::properties::WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let writer = parquet::file::writer::SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))
+            .map_err(|e| format!("Failed to create Parquet file: {}", e))?;
+
+        Ok(ParquetSink { writer, columns: columns.to_vec(), buffer: Vec::with_capacity(PARQUET_ROW_GROUP_SIZE) })
+    }
+
+    fn flush_row_group(&mut self) -> Result<(), String> {
+        use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int64Type};
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let error = |e: parquet::errors::ParquetError| format!("Failed to write Parquet: {}", e);
+        let mut row_group = self.writer.next_row_group().map_err(error)?;
+
+        for (index, column) in self.columns.iter().enumerate() {
+            let mut writer = row_group.next_column().map_err(error)?.ok_or("Parquet schema has fewer columns than the data")?;
+            let values = self.buffer.iter().map(|row| &row[index]);
+            let levels: Vec<i16> = values.clone().map(|v| if v.is_null() { 0 } else { 1 }).collect();
+            let mismatch = |v: &serde_json::Value| format!("Value {} in column '{}' is not {:?}; export as CSV or JSON Lines instead", v, column.name, column.kind);
+
+            match column.kind {
+                ExportColumnKind::Integer => {
+                    let data = values.filter(|v| !v.is_null()).map(|v| v.as_i64().ok_or_else(|| mismatch(v))).collect::<Result<Vec<_>, _>>()?;
+                    writer.typed::<Int64Type>().write_batch(&data, Some(&levels), None).map_err(error)?;
+                }
+                ExportColumnKind::Float => {
+                    let data = values.filter(|v| !v.is_null()).map(|v| v.as_f64().ok_or_else(|| mismatch(v))).collect::<Result<Vec<_>, _>>()?;
+                    writer.typed::<DoubleType>().write_batch(&data, Some(&levels), None).map_err(error)?;
+                }
+                ExportColumnKind::Boolean => {
+                    let data = values.filter(|v| !v.is_null()).map(|v| v.as_bool().ok_or_else(|| mismatch(v))).collect::<Result<Vec<_>, _>>()?;
+                    writer.typed::<BoolType>().write_batch(&data, Some(&levels), None).map_err(error)?;
+                }
+                ExportColumnKind::Text | ExportColumnKind::Json => {
+                    let data: Vec<ByteArray> = values.filter_map(export_value_text).map(|s| ByteArray::from(s.into_bytes())).collect();
+                    writer.typed::<ByteArrayType>().write_batch(&data, Some(&levels), None).map_err(error)?;
+                }
+            }
+            writer.close().map_err(error)?;
+        }
+
+        row_group.close().map_err(error)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl ExportSink for ParquetSink {
+    fn write_row(&mut self, row: Vec<serde_json::Value>) -> Result<(), String> {
+        self.buffer.push(row);
+        if self.buffer.len() >= PARQUET_ROW_GROUP_SIZE {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.flush_row_group()?;
+        self.writer.close().map_err(|e| format!("Failed to finish Parquet file: {}", e))?;
+        Ok(())
+    }
+}
+
+fn create_export_sink(request: &ExportRequest, columns: &[ExportColumn], db_type: &DatabaseType, default_table: &str) -> Result<Box<dyn ExportSink>, String> {
+    let file = fs::File::create(&request.path).map_err(|e| format!("Failed to create {}: {}", request.path, e))?;
+    let names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+
+    let sink: Box<dyn ExportSink> = match request.format {
+        ExportFormat::Csv => {
+            let mut writer = csv::WriterBuilder::new()
+                .delimiter(csv_delimiter(&request.csv.delimiter)?)
+                .from_writer(std::io::BufWriter::new(file));
+            if request.csv.header {
+                writer.write_record(&names).map_err(|e| format!("Failed to write CSV: {}", e))?;
+            }
+            Box::new(CsvSink { writer, null_text: request.csv.null_text.clone() })
+        }
+        ExportFormat::Jsonl => Box::new(JsonlSink { writer: std::io::BufWriter::new(file), columns: names }),
+        ExportFormat::Sql => {
+            let table = request.sql_table.clone().filter(|t| !t.is_empty()).unwrap_or_else(|| default_table.to_string());
+            let quoted: Vec<String> = names.iter().map(|n| quote_ident(n, db_type)).collect();
+            Box::new(SqlInsertSink {
+                writer: std::io::BufWriter::new(file),
+                db_type: db_type.clone(),
+                insert_prefix: format!("INSERT INTO {} ({}) VALUES", quote_ident(&table, db_type), quoted.join(", ")),
+                rows_per_insert: request.rows_per_insert.unwrap_or(EXPORT_DEFAULT_ROWS_PER_INSERT).max(1),
+                pending: Vec::new(),
+            })
+        }
+        ExportFormat::Parquet => Box::new(ParquetSink::create(file, columns)?),
+    };
+    Ok(sink)
+}
+
+/// Emits `export-progress` events at most every 500 ms and watches for cancellation
+struct ExportProgress<'a> {
+    app: &'a tauri::AppHandle,
+    export_id: String,
+    total_estimate: Option<u64>,
+    rows: u64,
+    last_emit: std::time::Instant,
+}
+
+impl ExportProgress<'_> {
+    fn tick(&mut self) {
+        self.rows += 1;
+        if self.last_emit.elapsed().as_millis() >= EXPORT_PROGRESS_INTERVAL_MS {
+            self.emit();
+        }
+    }
+
+    fn emit(&mut self) {
+        self.last_emit = std::time::Instant::now();
+        let _ = self.app.emit(
+            "export-progress",
+            json!({ "export_id": self.export_id, "rows": self.rows, "total_estimate": self.total_estimate }),
+        );
+    }
+}
+
+/// Rows of a query read from the server one at a time, values in column order
+enum SqlRowStream<'a> {
+    Postgres {
+        rows: std::pin::Pin<Box<tokio_postgres::SimpleQueryStream>>,
+        columns: Vec<ExportColumn>,
+    },
+    MySql {
+        result: mysql_async::QueryResult<'a, 'static, mysql_async::TextProtocol>,
+        columns: Vec<ExportColumn>,
+    },
+}
+
+impl SqlConn {
+    /// Start reading the rows of `sql` without holding the result in memory
+    async fn stream_rows(&mut self, sql: &str) -> Result<SqlRowStream<'_>, String> {
+        match self {
+            SqlConn::Postgres(client) => {
+                // prepare da los tipos (y rechaza varias sentencias); las filas llegan en
+                // formato texto, sin perder precisión en NUMERIC ni colapsar columnas repetidas
+                let statement = client.prepare(sql).await.map_err(format_pg_error)?;
+                let names = unique_column_names(statement.columns().iter().map(|c| c.name().to_string()).collect());
+                let columns = names
+                    .into_iter()
+                    .zip(statement.columns())
+                    .map(|(name, c)| ExportColumn { name, kind: pg_export_kind(c.type_()) })
+                    .collect();
+                let rows = client.simple_query_raw(sql).await.map_err(format_pg_error)?;
+                Ok(SqlRowStream::Postgres { rows: Box::pin(rows), columns })
+            }
+            SqlConn::MySql(conn) => {
+                use mysql_async::prelude::Queryable;
+                let result = conn.query_iter(sql.to_string()).await.map_err(|e| e.to_string())?;
+                let names = unique_column_names(result.columns_ref().iter().map(|c| c.name_str().to_string()).collect());
+                let columns = names
+                    .into_iter()
+                    .zip(result.columns_ref().iter())
+                    .map(|(name, c)| ExportColumn { name, kind: mysql_export_kind(c.column_type()) })
+                    .collect();
+                Ok(SqlRowStream::MySql { result, columns })
+            }
+        }
+    }
+}
+
+impl SqlRowStream<'_> {
+    fn columns(&self) -> &[ExportColumn] {
+        match self {
+            SqlRowStream::Postgres { columns, .. } | SqlRowStream::MySql { columns, .. } => columns,
+        }
+    }
+
+    async fn next_row(&mut self) -> Result<Option<Vec<serde_json::Value>>, String> {
+        match self {
+            SqlRowStream::Postgres { rows, columns } => loop {
+                let row = match rows.next().await {
+                    Some(Ok(tokio_postgres::SimpleQueryMessage::Row(row))) => row,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(format_pg_error(e)),
+                    None => return Ok(None),
+                };
+                return Ok(Some(
+                    columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| typed_export_value(row.get(i).map(|v| json!(v)).unwrap_or(serde_json::Value::Null), c.kind))
+                        .collect(),
+                ));
+            },
+            SqlRowStream::MySql { result, columns } => {
+                let Some(row) = result.next().await.map_err(|e| e.to_string())? else {
+                    return Ok(None);
+                };
+                Ok(Some(
+                    columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| typed_export_value(row.as_ref(i).map(mysql_value_to_json).unwrap_or(serde_json::Value::Null), c.kind))
+                        .collect(),
+                ))
+            }
+        }
+    }
+}
+
+/// Stream the rows of `sql` into the sink without holding the result in memory
+async fn stream_export(
+    conn: &mut SqlConn,
+    sql: &str,
+    request: &ExportRequest,
+    db_type: &DatabaseType,
+    default_table: &str,
+    progress: &mut ExportProgress<'_>,
+) -> Result<(), String> {
+    let mut rows = conn.stream_rows(sql).await?;
+    let mut sink = create_export_sink(request, rows.columns(), db_type, default_table)?;
+    while let Some(values) = rows.next_row().await? {
+        sink.write_row(values)?;
+        progress.tick();
+    }
+    sink.finish()
+}
+
+/// Planner row estimate of a table, for progress reporting
+async fn table_row_estimate(conn: &mut SqlConn, db_type: &DatabaseType, meta: &TableMeta) -> Option<u64> {
+    let mut sql = SqlBuilder::new(db_type);
+    match db_type {
+        DatabaseType::PostgreSQL => {
+            sql.push("SELECT GREATEST(reltuples, 0)::bigint AS estimate FROM pg_class WHERE oid = to_regclass(")
+                .bind(Some(meta.qualified_name.clone()), None)
+                .push(")");
+        }
+        _ => {
+            let (schema, table) = meta.qualified_name.split_once("`.`")?;
+            sql.push("SELECT TABLE_ROWS AS estimate FROM information_schema.TABLES WHERE TABLE_SCHEMA = ")
+                .bind(Some(schema.trim_start_matches('`').replace("``", "`")), None)
+                .push(" AND TABLE_NAME = ")
+                .bind(Some(table.trim_end_matches('`').replace("``", "`")), None);
+        }
+    }
+    let rows = conn.query_json(&sql).await.ok()?;
+    rows.first()?.get("estimate").and_then(json_number_as_u64)
+}
+
+/// Export a query result or a whole table to a file on the host
+///
+/// Rows are streamed from the server to the file, so large tables do not need to fit
+/// in memory. Progress is emitted as `export-progress` events (`rows`, `total_estimate`)
+/// and the export can be stopped with `cancel_query(export_id)`; a cancelled or failed
+/// export removes the partial file.
+///
+/// # Arguments
+/// * `container_id` - PostgreSQL, MySQL or MariaDB container
+/// * `request` - Source (query or table), format (csv, jsonl, sql, parquet), path and format options
+///
+/// # Returns
+/// * `Ok(ExportReport)` - Rows and bytes written
+/// * `Err(String)` - Query, file or cancellation error
+#[tauri::command]
+async fn export_data(
+    app: tauri::AppHandle,
+    container_id: String,
+    request: ExportRequest,
+    state: State<'_, AppState>,
+    running: State<'_, RunningQueriesState>,
+) -> Result<ExportReport, String> {
+    let export_id = match request.export_id.clone().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    if !matches!(target.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB) {
+        return Err(format!("Export is not available for {}", target.db_type.to_string()));
+    }
+
+    let started = std::time::Instant::now();
+    let (mut conn, sql, default_table, total_estimate) = match &request.source {
+        ExportSource::Query { sql, database } => {
+            let sql = sql.trim().trim_end_matches(';').trim().to_string();
+            if sql.is_empty() {
+                return Err("The export query is empty".to_string());
+            }
+            let conn = SqlConn::connect(&docker, &target, database.as_deref()).await?;
+            (conn, sql, "exported_rows".to_string(), None)
+        }
+        ExportSource::Table(table) => {
+            let (_, mut conn, meta) = open_table(&docker, &container_id, table).await?;
+            let estimate = table_row_estimate(&mut conn, &target.db_type, &meta).await;
+            (conn, format!("SELECT * FROM {}", meta.qualified_name), table.table.clone(), estimate)
+        }
+    };
+
+    println!("📤 Exportando {:?} de {} a {}", request.format, target.container_name, request.path);
+    let running_query = running.register(&export_id, &target, &sql, 0)?;
+    let mut progress = ExportProgress {
+        app: &app,
+        export_id: export_id.clone(),
+        total_estimate,
+        rows: 0,
+        last_emit: std::time::Instant::now(),
+    };
+    let handle = conn.cancel_handle();
+    let result = {
+        let export = stream_export(&mut conn, &sql, &request, &target.db_type, &default_table, &mut progress);
+        tokio::pin!(export);
+        // La consulta puede tardar en dar la primera fila: se cancela también mientras se espera
+        tokio::select! {
+            result = &mut export => result,
+            _ = running_query.cancel.notified() => {
+                if let Err(e) = handle.cancel(&docker, &target).await {
+                    eprintln!("[Export] Failed to cancel statement on {}: {}", target.container_name, e);
+                }
+                Err("Export cancelled".to_string())
+            }
+        }
+    };
+    drop(running_query);
+
+    if let Err(e) = result {
+        // Sin leer el resto del resultado la conexión no se puede cerrar limpiamente: se suelta
+        drop(conn);
+        let _ = fs::remove_file(&request.path);
+        return Err(e);
+    }
+    conn.close().await;
+    progress.emit();
+
+    Ok(ExportReport {
+        export_id,
+        path: request.path.clone(),
+        rows: progress.rows,
+        bytes: fs::metadata(&request.path).map(|m| m.len()).unwrap_or(0),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// ===== DATA IMPORT =====
+
+const IMPORT_DEFAULT_BATCH_ROWS: usize = 1000;
+/// The mongo shell script travels as one exec argument, which Linux caps at 128 KiB
+const MONGO_IMPORT_BATCH_BYTES: usize = 96 * 1024;
+const IMPORT_MAX_REPORTED_REJECTS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportRequest {
+    /// Target table (for MongoDB `table` is the collection)
+    #[serde(flatten)]
+    pub table: TableRef,
+    /// Source file on the host
+    pub path: String,
+    pub format: ImportFormat,
+    /// ID for progress events and `cancel_query` (generated when omitted)
+    #[serde(default)]
+    pub import_id: Option<String>,
+    #[serde(default)]
+    pub csv: CsvOptions,
+    /// Create the table with the inferred column types when it does not exist
+    #[serde(default = "default_true")]
+    pub create_table: bool,
+    /// File column -> table column, for files whose headers do not match the table
+    #[serde(default)]
+    pub column_map: HashMap<String, String>,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RejectedRow {
+    /// Line of the file where the row starts, or row number in a transfer (None when the server does not say which row)
+    pub line: Option<u64>,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportReport {
+    pub import_id: String,
+    pub table: String,
+    /// `copy`, `load_data`, `insert` or `insert_many`
+    pub method: String,
+    pub created_table: bool,
+    /// Columns loaded from the file, with their table (or inferred) types
+    pub columns: Vec<TableColumn>,
+    /// File columns with no matching table column
+    pub ignored_columns: Vec<String>,
+    pub rows_read: u64,
+    pub rows_imported: u64,
+    pub rows_rejected: u64,
+    /// First rejected rows with the reason, plus warnings from `LOAD DATA` (capped at 1000)
+    pub rejected: Vec<RejectedRow>,
+    pub duration_ms: u64,
+}
+
+/// Column type inferred from the file, or read from the existing column
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportColumnType {
+    Boolean,
+    Integer,
+    Float,
+    Date,
+    Timestamp,
+    Json,
+    Text,
+}
+
+impl ImportColumnType {
+    fn sql_type(&self, db_type: &DatabaseType) -> &'static str {
+        let pg = *db_type == DatabaseType::PostgreSQL;
+        match self {
+            ImportColumnType::Boolean => "BOOLEAN",
+            ImportColumnType::Integer => "BIGINT",
+            ImportColumnType::Float => if pg { "DOUBLE PRECISION" } else { "DOUBLE" },
+            ImportColumnType::Date => "DATE",
+            ImportColumnType::Timestamp => if pg { "TIMESTAMP" } else { "DATETIME(6)" },
+            ImportColumnType::Json => if pg { "JSONB" } else { "JSON" },
+            ImportColumnType::Text => if pg { "TEXT" } else { "LONGTEXT" },
+        }
+    }
+
+    /// Type to validate values against for an existing column (`format_type` / `COLUMN_TYPE`)
+    fn from_column_type(data_type: &str) -> Self {
+        let t = data_type.to_lowercase();
+        if t.starts_with("bool") || t.starts_with("tinyint(1)") {
+            ImportColumnType::Boolean
+        } else if t.starts_with("interval") || t.starts_with("point") {
+            ImportColumnType::Text
+        } else if ["int", "bigint", "smallint", "tinyint", "mediumint"].iter().any(|p| t.starts_with(p)) {
+            ImportColumnType::Integer
+        } else if ["double", "real", "float", "numeric", "decimal"].iter().any(|p| t.starts_with(p)) {
+            ImportColumnType::Float
+        } else if t == "date" {
+            ImportColumnType::Date
+        } else if t.starts_with("timestamp") || t.starts_with("datetime") {
+            ImportColumnType::Timestamp
+        } else if t == "json" || t == "jsonb" {
+            ImportColumnType::Json
+        } else {
+            ImportColumnType::Text
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            ImportColumnType::Boolean => "boolean",
+            ImportColumnType::Integer => "integer",
+            ImportColumnType::Float => "number",
+            ImportColumnType::Date => "date",
+            ImportColumnType::Timestamp => "timestamp",
+            ImportColumnType::Json => "JSON value",
+            ImportColumnType::Text => "text",
+        }
+    }
+}
+
+fn parse_import_timestamp(text: &str) -> bool {
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .any(|format| chrono::NaiveDateTime::parse_from_str(text, format).is_ok())
+}
+
+fn parse_import_date(text: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok()
+}
+
+/// Type of a single value; None for NULL, which fits any column
+fn infer_import_type(value: &serde_json::Value) -> Option<ImportColumnType> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(_) => Some(ImportColumnType::Boolean),
+        serde_json::Value::Number(n) if n.is_i64() => Some(ImportColumnType::Integer),
+        serde_json::Value::Number(_) => Some(ImportColumnType::Float),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => Some(ImportColumnType::Json),
+        serde_json::Value::String(s) => {
+            let t = s.trim();
+            let numeric = !t.is_empty() && t.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+            // Los ceros a la izquierda (códigos postales, teléfonos) se conservan como texto
+            let leading_zero = t.trim_start_matches(['+', '-']).len() > 1 && t.trim_start_matches(['+', '-']).starts_with('0') && !t.contains('.');
+            Some(if t.eq_ignore_ascii_case("true") || t.eq_ignore_ascii_case("false") {
+                ImportColumnType::Boolean
+            } else if numeric && !leading_zero && t.parse::<i64>().is_ok() {
+                ImportColumnType::Integer
+            } else if numeric && !leading_zero && t.parse::<f64>().is_ok() {
+                ImportColumnType::Float
+            } else if parse_import_date(t) {
+                ImportColumnType::Date
+            } else if parse_import_timestamp(t) {
+                ImportColumnType::Timestamp
+            } else {
+                ImportColumnType::Text
+            })
+        }
+    }
+}
+
+/// Narrowest type that holds both; anything mixed ends up as text
+fn widen_import_type(current: Option<ImportColumnType>, next: ImportColumnType) -> ImportColumnType {
+    use ImportColumnType::*;
+    match (current, next) {
+        (None, next) => next,
+        (Some(current), next) if current == next => current,
+        (Some(Integer), Float) | (Some(Float), Integer) => Float,
+        (Some(Date), Timestamp) | (Some(Timestamp), Date) => Timestamp,
+        _ => Text,
+    }
+}
+
+/// Validate a value for a column and give its text form for COPY / LOAD DATA / INSERT (None for NULL)
+fn import_cell_text(value: &serde_json::Value, column_type: ImportColumnType) -> Result<Option<String>, String> {
+    let text = match value {
+        serde_json::Value::Null => return Ok(None),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let t = text.trim();
+    let invalid = || format!("'{}' is not a valid {}", text, column_type.label());
+    let cell = match column_type {
+        ImportColumnType::Boolean => match t.to_lowercase().as_str() {
+            "true" | "t" | "yes" => "1".to_string(),
+            "false" | "f" | "no" => "0".to_string(),
+            _ if t.parse::<i64>().is_ok() => t.to_string(),
+            _ => return Err(invalid()),
+        },
+        ImportColumnType::Integer if t.parse::<i64>().is_ok() || t.parse::<u64>().is_ok() => t.to_string(),
+        ImportColumnType::Float if t.parse::<f64>().is_ok() => t.to_string(),
+        ImportColumnType::Date if parse_import_date(t) => t.to_string(),
+        ImportColumnType::Timestamp if parse_import_timestamp(t) || parse_import_date(t) => t.to_string(),
+        ImportColumnType::Json if serde_json::from_str::<serde_json::Value>(&text).is_ok() => text,
+        ImportColumnType::Text => text,
+        _ => return Err(invalid()),
+    };
+    Ok(Some(cell))
+}
+
+/// Typed document value for MongoDB: CSV cells follow the inferred column type, JSON values stay as they are
+fn import_document_value(value: serde_json::Value, column_type: ImportColumnType) -> serde_json::Value {
+    let serde_json::Value::String(text) = &value else {
+        return value;
+    };
+    let t = text.trim();
+    match column_type {
+        ImportColumnType::Boolean => json!(t.eq_ignore_ascii_case("true")),
+        ImportColumnType::Integer => t.parse::<i64>().map(|n| json!(n)).unwrap_or(value),
+        ImportColumnType::Float => t.parse::<f64>().map(|n| json!(n)).unwrap_or(value),
+        ImportColumnType::Json => serde_json::from_str(t).unwrap_or(value),
+        _ => value,
+    }
+}
+
+/// Field escaping shared by PostgreSQL `COPY ... FROM STDIN` text format and MySQL `LOAD DATA` defaults
+fn escape_copy_field(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "\\N".to_string();
+    };
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn copy_batch_data(rows: &[(u64, Vec<Option<String>>)]) -> Vec<u8> {
+    let mut data = String::new();
+    for (_, cells) in rows {
+        let fields: Vec<String> = cells.iter().map(|c| escape_copy_field(c.as_deref())).collect();
+        data.push_str(&fields.join("\t"));
+        data.push('\n');
+    }
+    data.into_bytes()
+}
+
+/// Row number (1-based, within the loaded batch) a MySQL warning refers to, e.g. "... at row 3"
+fn mysql_warning_row(message: &str) -> Option<usize> {
+    let lower = message.to_lowercase();
+    let start = lower.find("row ")? + 4;
+    let digits: String = lower[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// One line of the import file keyed by file column; `fields` is Err for rows that cannot be parsed
+struct ImportRecord {
+    line: u64,
+    fields: Result<serde_json::Map<String, serde_json::Value>, String>,
+}
+
+enum ImportReader {
+    Csv {
+        records: csv::StringRecordsIntoIter<fs::File>,
+        headers: Vec<String>,
+        strict: bool,
+        null_text: String,
+    },
+    Jsonl {
+        lines: std::io::Lines<std::io::BufReader<fs::File>>,
+        line: u64,
+    },
+}
+
+impl ImportReader {
+    /// `positional` names the columns of a CSV file without header (generated `column_N` otherwise)
+    fn open(request: &ImportRequest, positional: &[String]) -> Result<Self, String> {
+        let file = fs::File::open(&request.path).map_err(|e| format!("Failed to open {}: {}", request.path, e))?;
+        match request.format {
+            ImportFormat::Csv => {
+                let mut reader = csv::ReaderBuilder::new()
+                    .delimiter(csv_delimiter(&request.csv.delimiter)?)
+                    .has_headers(request.csv.header)
+                    .flexible(true)
+                    .from_reader(file);
+                let headers = if request.csv.header {
+                    let headers = reader.headers().map_err(|e| format!("Failed to read the CSV header: {}", e))?;
+                    headers
+                        .iter()
+                        .enumerate()
+                        .map(|(i, h)| if h.trim().is_empty() { format!("column_{}", i + 1) } else { h.trim().to_string() })
+                        .collect()
+                } else {
+                    positional.to_vec()
+                };
+                Ok(ImportReader::Csv {
+                    records: reader.into_records(),
+                    strict: request.csv.header || !positional.is_empty(),
+                    headers,
+                    null_text: request.csv.null_text.clone(),
+                })
+            }
+            ImportFormat::Jsonl => Ok(ImportReader::Jsonl { lines: std::io::BufRead::lines(std::io::BufReader::new(file)), line: 0 }),
+        }
+    }
+}
+
+impl Iterator for ImportReader {
+    /// Err only for I/O errors that stop the import
+    type Item = Result<ImportRecord, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ImportReader::Csv { records, headers, strict, null_text } => {
+                let record = match records.next()? {
+                    Ok(record) => record,
+                    Err(e) if e.is_io_error() => return Some(Err(format!("Failed to read the CSV file: {}", e))),
+                    Err(e) => {
+                        let line = e.position().map(|p| p.line()).unwrap_or(0);
+                        return Some(Ok(ImportRecord { line, fields: Err(e.to_string()) }));
+                    }
+                };
+                let line = record.position().map(|p| p.line()).unwrap_or(0);
+                if *strict && record.len() != headers.len() {
+                    let reason = format!("Expected {} fields, found {}", headers.len(), record.len());
+                    return Some(Ok(ImportRecord { line, fields: Err(reason) }));
+                }
+                let fields = record
+                    .iter()
+                    .enumerate()
+                    .map(|(i, cell)| {
+                        let name = headers.get(i).cloned().unwrap_or_else(|| format!("column_{}", i + 1));
+                        let value = if cell == null_text { serde_json::Value::Null } else { json!(cell) };
+                        (name, value)
+                    })
+                    .collect();
+                Some(Ok(ImportRecord { line, fields: Ok(fields) }))
+            }
+            ImportReader::Jsonl { lines, line } => loop {
+                let text = match lines.next()? {
+                    Ok(text) => text,
+                    Err(e) => return Some(Err(format!("Failed to read the JSON Lines file: {}", e))),
+                };
+                *line += 1;
+                if text.trim().is_empty() {
+                    continue;
+                }
+                let fields = match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(serde_json::Value::Object(object)) => Ok(object),
+                    Ok(_) => Err("The line is not a JSON object".to_string()),
+                    Err(e) => Err(format!("Invalid JSON: {}", e)),
+                };
+                return Some(Ok(ImportRecord { line: *line, fields }));
+            },
+        }
+    }
+}
+
+/// First pass over the file: columns in order of appearance with their inferred types, and the row count
+fn scan_import_file(request: &ImportRequest, positional: &[String]) -> Result<(Vec<(String, ImportColumnType)>, u64), String> {
+    let mut columns: Vec<(String, Option<ImportColumnType>)> = Vec::new();
+    let mut rows = 0;
+    for record in ImportReader::open(request, positional)? {
+        let record = record?;
+        rows += 1;
+        let Ok(fields) = record.fields else { continue };
+        for (key, value) in &fields {
+            let name = request.column_map.get(key).unwrap_or(key);
+            let index = match columns.iter().position(|(c, _)| c == name) {
+                Some(index) => index,
+                None => {
+                    columns.push((name.clone(), None));
+                    columns.len() - 1
+                }
+            };
+            if let Some(value_type) = infer_import_type(value) {
+                columns[index].1 = Some(widen_import_type(columns[index].1, value_type));
+            }
+        }
+    }
+    let columns = columns.into_iter().map(|(name, t)| (name, t.unwrap_or(ImportColumnType::Text))).collect();
+    Ok((columns, rows))
+}
+
+/// Counters and rejected rows shared by the engine-specific loaders
+struct ImportTally {
+    imported: u64,
+    rejected: u64,
+    rejects: Vec<RejectedRow>,
+}
+
+impl ImportTally {
+    fn reject(&mut self, line: Option<u64>, reason: String) {
+        self.rejected += 1;
+        self.note(line, reason);
+    }
+
+    /// Record a reason without counting a row (counts come from the server)
+    fn note(&mut self, line: Option<u64>, reason: String) {
+        if self.rejects.len() < IMPORT_MAX_REPORTED_REJECTS {
+            self.rejects.push(RejectedRow { line, reason });
+        }
+    }
+}
+
+/// Loads batches of rows into an existing SQL table
+struct SqlImportLoader<'a> {
+    conn: &'a mut SqlConn,
+    db_type: DatabaseType,
+    qualified_name: String,
+    columns: Vec<TableColumn>,
+    /// Columns whose values arrive hex encoded (binary data in a transfer); PostgreSQL gets them as `\x...`
+    hex_columns: Vec<bool>,
+    /// Cleared when the MySQL server refuses `LOAD DATA LOCAL`
+    load_data: bool,
+    method: &'static str,
+}
+
+impl SqlImportLoader<'_> {
+    fn column_list(&self) -> String {
+        self.columns.iter().map(|c| quote_ident(&c.name, &self.db_type)).collect::<Vec<_>>().join(", ")
+    }
+
+    async fn load(&mut self, batch: &[(u64, Vec<Option<String>>)], tally: &mut ImportTally) {
+        if batch.is_empty() {
+            return;
+        }
+        let bulk = match self.conn {
+            SqlConn::Postgres(_) => self.copy_batch(batch).await,
+            SqlConn::MySql(_) if self.load_data => self.load_data_batch(batch, tally).await,
+            SqlConn::MySql(_) => self.insert_batch(batch).await,
+        };
+        match bulk {
+            Ok(rows) => tally.imported += rows,
+            // La carga masiva es todo o nada: se repite fila a fila para saber cuáles fallan
+            Err(_) => {
+                for (line, cells) in batch {
+                    match self.insert_batch(std::slice::from_ref(&(*line, cells.clone()))).await {
+                        Ok(rows) => tally.imported += rows,
+                        Err(e) => tally.reject(Some(*line), e),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn copy_batch(&mut self, batch: &[(u64, Vec<Option<String>>)]) -> Result<u64, String> {
+        use futures_util::SinkExt;
+        let SqlConn::Postgres(client) = &*self.conn else {
+            return Err("COPY is only available on PostgreSQL".to_string());
+        };
+        let statement = format!("COPY {} ({}) FROM STDIN", self.qualified_name, self.column_list());
+        let sink = client.copy_in::<_, bytes::Bytes>(statement.as_str()).await.map_err(format_pg_error)?;
+        futures_util::pin_mut!(sink);
+        sink.send(bytes::Bytes::from(copy_batch_data(batch))).await.map_err(format_pg_error)?;
+        sink.as_mut().finish().await.map_err(format_pg_error)
+    }
+
+    /// `LOAD DATA LOCAL` turns bad values and duplicate keys into warnings, reported as rejected rows
+    async fn load_data_batch(&mut self, batch: &[(u64, Vec<Option<String>>)], tally: &mut ImportTally) -> Result<u64, String> {
+        use mysql_async::prelude::Queryable;
+        let mut targets = Vec::new();
+        let mut assignments = Vec::new();
+        for (i, column) in self.columns.iter().enumerate() {
+            let name = quote_ident(&column.name, &self.db_type);
+            if self.hex_columns.get(i).copied().unwrap_or(false) {
+                targets.push(format!("@hex{}", i));
+                assignments.push(format!("{} = UNHEX(@hex{})", name, i));
+            } else {
+                targets.push(name);
+            }
+        }
+        let set = if assignments.is_empty() { String::new() } else { format!(" SET {}", assignments.join(", ")) };
+        let SqlConn::MySql(conn) = &mut *self.conn else {
+            return Err("LOAD DATA is only available on MySQL".to_string());
+        };
+        let data = bytes::Bytes::from(copy_batch_data(batch));
+        conn.set_infile_handler(async move { Ok(futures_util::StreamExt::boxed(futures_util::stream::iter(vec![Ok(data)]))) });
+        // Formato por defecto de LOAD DATA: campos con tabulador, escapes con `\` y NULL como `\N`
+        let statement = format!(
+            "LOAD DATA LOCAL INFILE 'ddm-import' INTO TABLE {} CHARACTER SET utf8mb4 ({}){}",
+            self.qualified_name,
+            targets.join(", "),
+            set
+        );
+
+        match conn.query_drop(statement).await {
+            Ok(()) => {}
+            // 3948/1148: local_infile desactivado en el servidor, se sigue con INSERT
+            Err(mysql_async::Error::Server(e)) if e.code == 3948 || e.code == 1148 => {
+                println!("📥 LOAD DATA LOCAL no permitido ({}), usando INSERT", e.message);
+                self.load_data = false;
+                self.method = "insert";
+                return self.insert_batch(batch).await;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+        let loaded = conn.affected_rows();
+        if conn.get_warnings() > 0 {
+            let warnings: Vec<mysql_async::Row> = conn.query("SHOW WARNINGS").await.map_err(|e| e.to_string())?;
+            for warning in warnings.iter().map(mysql_row_to_json) {
+                let message = warning.get("Message").and_then(|m| m.as_str()).unwrap_or_default().to_string();
+                let line = mysql_warning_row(&message).and_then(|row| batch.get(row.wrapping_sub(1))).map(|(line, _)| *line);
+                tally.note(line, message);
+            }
+        }
+        tally.rejected += (batch.len() as u64).saturating_sub(loaded);
+        Ok(loaded)
+    }
+
+    async fn insert_batch(&mut self, batch: &[(u64, Vec<Option<String>>)]) -> Result<u64, String> {
+        let mut sql = SqlBuilder::new(&self.db_type);
+        sql.push(&format!("INSERT INTO {} ({}) VALUES ", self.qualified_name, self.column_list()));
+        for (row, (_, cells)) in batch.iter().enumerate() {
+            sql.push(if row == 0 { "(" } else { ", (" });
+            for (i, (cell, column)) in cells.iter().zip(&self.columns).enumerate() {
+                if i > 0 {
+                    sql.push(", ");
+                }
+                if self.db_type != DatabaseType::PostgreSQL && self.hex_columns.get(i).copied().unwrap_or(false) {
+                    sql.push("UNHEX(").bind(cell.clone(), None).push(")");
+                } else {
+                    sql.bind(cell.clone(), Some(&column.data_type));
+                }
+            }
+            sql.push(")");
+        }
+        self.conn.execute(&sql).await
+    }
+}
+
+fn mongo_insert_many_script(database: &str, collection: &str, documents: &[String]) -> String {
+    let body = format!(
+        "var docs = ejson.parse({}); var errors = []; \
+         try {{ c.insertMany(docs, {{ordered: false}}); }} catch (e) {{ \
+           var we = e.writeErrors || (e.getWriteErrors ? e.getWriteErrors() : null); if (!we) {{ throw e; }} \
+           if (!Array.isArray(we)) {{ we = [we]; }} \
+           errors = we.map(function (w) {{ return {{index: w.index, message: w.errmsg || String(w)}}; }}); }} \
+         print(JSON.stringify({{inserted: docs.length - errors.length, errors: errors}}));",
+        mongo_string_literal(&format!("[{}]", documents.join(",")))
+    );
+    mongo_explorer_script(database, Some(collection), &body)
+}
+
+async fn load_mongo_batch(docker: &Docker, target: &DbTarget, database: &str, collection: &str, batch: &[(u64, String)], tally: &mut ImportTally) {
+    if batch.is_empty() {
+        return;
+    }
+    let documents: Vec<String> = batch.iter().map(|(_, d)| d.clone()).collect();
+    let reply = match run_mongo_explorer(docker, target, &mongo_insert_many_script(database, collection, &documents)).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            for (line, _) in batch {
+                tally.reject(Some(*line), e.clone());
+            }
+            return;
+        }
+    };
+    tally.imported += reply.get("inserted").and_then(json_number_as_u64).unwrap_or(0);
+    for error in reply.get("errors").and_then(|e| e.as_array()).cloned().unwrap_or_default() {
+        let line = error.get("index").and_then(json_number_as_u64).and_then(|i| batch.get(i as usize)).map(|(line, _)| *line);
+        tally.reject(line, error.get("message").and_then(|m| m.as_str()).unwrap_or("Insert failed").to_string());
+    }
+}
+
+fn emit_import_progress(app: &tauri::AppHandle, import_id: &str, rows: u64, total_rows: u64) {
+    let _ = app.emit("import-progress", json!({ "import_id": import_id, "rows": rows, "total_rows": total_rows }));
+}
+
+fn import_cancelled(cancel: &tokio::sync::Notify, rows: u64) -> Result<(), String> {
+    use futures_util::FutureExt;
+    if cancel.notified().now_or_never().is_some() {
+        return Err(format!("Import cancelled after {} rows; rows already loaded were kept", rows));
+    }
+    Ok(())
+}
+
+async fn import_into_mongo(
+    app: &tauri::AppHandle,
+    docker: &Docker,
+    target: &DbTarget,
+    request: &ImportRequest,
+    import_id: &str,
+    cancel: &tokio::sync::Notify,
+) -> Result<(ImportReport, ImportTally), String> {
+    let database = request
+        .table
+        .database
+        .clone()
+        .filter(|d| !d.is_empty())
+        .or_else(|| Some(target.database.clone()).filter(|d| !d.is_empty()))
+        .ok_or("A database is required to import into MongoDB")?;
+    let (columns, total_rows) = scan_import_file(request, &[])?;
+    let types: HashMap<String, ImportColumnType> = columns.iter().cloned().collect();
+    let batch_size = request.batch_size.unwrap_or(IMPORT_DEFAULT_BATCH_ROWS).max(1);
+
+    let mut tally = ImportTally { imported: 0, rejected: 0, rejects: Vec::new() };
+    let mut batch: Vec<(u64, String)> = Vec::new();
+    let mut batch_bytes = 0;
+    let mut rows_read = 0;
+    for record in ImportReader::open(request, &[])? {
+        let record = record?;
+        rows_read += 1;
+        let fields = match record.fields {
+            Ok(fields) => fields,
+            Err(reason) => {
+                tally.reject(Some(record.line), reason);
+                continue;
+            }
+        };
+        let document: serde_json::Map<String, serde_json::Value> = fields
+            .into_iter()
+            .map(|(key, value)| {
+                let name = request.column_map.get(&key).cloned().unwrap_or(key);
+                let value = match request.format {
+                    ImportFormat::Csv => import_document_value(value, types.get(&name).copied().unwrap_or(ImportColumnType::Text)),
+                    ImportFormat::Jsonl => value,
+                };
+                (name, value)
+            })
+            .collect();
+        let document = serde_json::Value::Object(document).to_string();
+        let size = mongo_string_literal(&document).len();
+        if size > MONGO_IMPORT_BATCH_BYTES {
+            tally.reject(Some(record.line), format!("Document of {} bytes is too large to import through the shell", size));
+            continue;
+        }
+        if batch.len() >= batch_size || batch_bytes + size > MONGO_IMPORT_BATCH_BYTES {
+            load_mongo_batch(docker, target, &database, &request.table.table, &batch, &mut tally).await;
+            batch.clear();
+            batch_bytes = 0;
+            emit_import_progress(app, import_id, rows_read, total_rows);
+            import_cancelled(cancel, tally.imported)?;
+        }
+        batch_bytes += size;
+        batch.push((record.line, document));
+    }
+    load_mongo_batch(docker, target, &database, &request.table.table, &batch, &mut tally).await;
+
+    let report = ImportReport {
+        import_id: import_id.to_string(),
+        table: format!("{}.{}", database, request.table.table),
+        method: "insert_many".to_string(),
+        created_table: false,
+        columns: columns.iter().map(|(name, t)| TableColumn { name: name.clone(), data_type: t.label().to_string() }).collect(),
+        ignored_columns: Vec::new(),
+        rows_read,
+        rows_imported: 0,
+        rows_rejected: 0,
+        rejected: Vec::new(),
+        duration_ms: 0,
+    };
+    Ok((report, tally))
+}
+
+async fn import_into_sql(
+    app: &tauri::AppHandle,
+    conn: &mut SqlConn,
+    target: &DbTarget,
+    request: &ImportRequest,
+    import_id: &str,
+    cancel: &tokio::sync::Notify,
+) -> Result<(ImportReport, ImportTally), String> {
+    let db_type = target.db_type.clone();
+    let database = request.table.database.clone().filter(|d| !d.is_empty()).unwrap_or_else(|| target.database.clone());
+
+    let (meta, created_table) = match load_table_meta(conn, &db_type, &request.table, &database).await {
+        Ok(meta) => (meta, false),
+        Err(e) if !request.create_table => return Err(e),
+        Err(_) => {
+            let (columns, _) = scan_import_file(request, &[])?;
+            if columns.is_empty() {
+                return Err("The file has no columns to create the table from".to_string());
+            }
+            let schema = match db_type {
+                DatabaseType::PostgreSQL => request.table.schema.clone().unwrap_or_else(|| "public".to_string()),
+                _ => database.clone(),
+            };
+            let definitions: Vec<String> = columns
+                .iter()
+                .map(|(name, t)| format!("{} {}", quote_ident(name, &db_type), t.sql_type(&db_type)))
+                .collect();
+            let create = format!(
+                "CREATE TABLE {}.{} ({})",
+                quote_ident(&schema, &db_type),
+                quote_ident(&request.table.table, &db_type),
+                definitions.join(", ")
+            );
+            println!("📥 Creando tabla para la importación: {}", create);
+            conn.batch(&create).await?;
+            (load_table_meta(conn, &db_type, &request.table, &database).await?, true)
+        }
+    };
+
+    // Sin cabecera, las columnas del CSV siguen el orden de la tabla
+    let positional: Vec<String> = meta.columns.iter().map(|c| c.name.clone()).collect();
+    let (file_columns, total_rows) = scan_import_file(request, &positional)?;
+    let columns: Vec<TableColumn> = meta.columns.iter().filter(|c| file_columns.iter().any(|(name, _)| *name == c.name)).cloned().collect();
+    let ignored_columns: Vec<String> = file_columns
+        .iter()
+        .map(|(name, _)| name.clone())
+        .filter(|name| !meta.columns.iter().any(|c| c.name == *name))
+        .collect();
+    if columns.is_empty() {
+        return Err(format!("No column of the file matches a column of {}", meta.qualified_name));
+    }
+    let types: Vec<ImportColumnType> = columns.iter().map(|c| ImportColumnType::from_column_type(&c.data_type)).collect();
+
+    let mysql = db_type != DatabaseType::PostgreSQL;
+    // MySQL admite como mucho 65535 parámetros por sentencia (usados por INSERT)
+    let max_rows = if mysql { (65_535 / columns.len()).max(1) } else { usize::MAX };
+    let batch_size = request.batch_size.unwrap_or(IMPORT_DEFAULT_BATCH_ROWS).clamp(1, max_rows);
+    let mut loader = SqlImportLoader {
+        conn,
+        db_type: db_type.clone(),
+        qualified_name: meta.qualified_name.clone(),
+        columns: columns.clone(),
+        hex_columns: Vec::new(),
+        load_data: mysql,
+        method: if mysql { "load_data" } else { "copy" },
+    };
+
+    let mut tally = ImportTally { imported: 0, rejected: 0, rejects: Vec::new() };
+    let mut batch: Vec<(u64, Vec<Option<String>>)> = Vec::with_capacity(batch_size);
+    let mut rows_read = 0;
+    for record in ImportReader::open(request, &positional)? {
+        let record = record?;
+        rows_read += 1;
+        let mut fields = match record.fields {
+            Ok(fields) => fields,
+            Err(reason) => {
+                tally.reject(Some(record.line), reason);
+                continue;
+            }
+        };
+        let fields: HashMap<String, serde_json::Value> = fields
+            .iter_mut()
+            .map(|(key, value)| (request.column_map.get(key).cloned().unwrap_or_else(|| key.clone()), value.take()))
+            .collect();
+        let cells: Result<Vec<Option<String>>, String> = columns
+            .iter()
+            .zip(&types)
+            .map(|(column, column_type)| {
+                let value = fields.get(&column.name).unwrap_or(&serde_json::Value::Null);
+                import_cell_text(value, *column_type).map_err(|e| format!("Column '{}': {}", column.name, e))
+            })
+            .collect();
+        match cells {
+            Ok(cells) => batch.push((record.line, cells)),
+            Err(reason) => tally.reject(Some(record.line), reason),
+        }
+        if batch.len() >= batch_size {
+            loader.load(&batch, &mut tally).await;
+            batch.clear();
+            emit_import_progress(app, import_id, rows_read, total_rows);
+            import_cancelled(cancel, tally.imported)?;
+        }
+    }
+    loader.load(&batch, &mut tally).await;
+
+    let report = ImportReport {
+        import_id: import_id.to_string(),
+        table: meta.qualified_name.clone(),
+        method: loader.method.to_string(),
+        created_table,
+        columns,
+        ignored_columns,
+        rows_read,
+        rows_imported: 0,
+        rows_rejected: 0,
+        rejected: Vec::new(),
+        duration_ms: 0,
+    };
+    Ok((report, tally))
+}
+
+/// Import a CSV or JSON Lines file from the host into a table or MongoDB collection
+///
+/// Columns are matched by name (CSV header or JSON keys, renamed through `column_map`);
+/// a CSV without header follows the table's column order. When the table does not exist
+/// and `create_table` is set, it is created with types inferred from the whole file.
+/// Rows are loaded in batches with `COPY FROM STDIN` (PostgreSQL), `LOAD DATA LOCAL`
+/// (MySQL/MariaDB, INSERT when the server disables it) or `insertMany` (MongoDB); a batch
+/// the server refuses is retried row by row so only the offending rows are rejected.
+/// Progress is emitted as `import-progress` events and `cancel_query(import_id)` stops
+/// after the current batch.
+///
+/// # Arguments
+/// * `container_id` - PostgreSQL, MySQL, MariaDB or MongoDB container
+/// * `request` - Target table, file path and format, CSV options and column mapping
+///
+/// # Returns
+/// * `Ok(ImportReport)` - Imported and rejected row counts with the reason of each rejection
+/// * `Err(String)` - File, table or connection error
+#[tauri::command]
+async fn import_data(
+    app: tauri::AppHandle,
+    container_id: String,
+    request: ImportRequest,
+    state: State<'_, AppState>,
+    running: State<'_, RunningQueriesState>,
+) -> Result<ImportReport, String> {
+    let import_id = match request.import_id.clone().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    if request.table.table.trim().is_empty() {
+        return Err("A target table is required".to_string());
+    }
+    let docker = state.docker();
+    let target = resolve_db_target(&docker, &container_id).await?;
+    if target.db_type == DatabaseType::Redis {
+        return Err("Import is not available for Redis".to_string());
+    }
+
+    let started = std::time::Instant::now();
+    println!("📥 Importando {} en {} ({})", request.path, request.table.table, target.container_name);
+    let running_query = running.register(&import_id, &target, &format!("import {}", request.path), 0)?;
+    let result = match target.db_type {
+        DatabaseType::MongoDB => import_into_mongo(&app, &docker, &target, &request, &import_id, &running_query.cancel).await,
+        _ => match SqlConn::connect(&docker, &target, request.table.database.as_deref()).await {
+            Ok(mut conn) => {
+                let result = import_into_sql(&app, &mut conn, &target, &request, &import_id, &running_query.cancel).await;
+                conn.close().await;
+                result
+            }
+            Err(e) => Err(e),
+        },
+    };
+    drop(running_query);
+
+    let (mut report, tally) = result?;
+    report.rows_imported = tally.imported;
+    report.rows_rejected = tally.rejected;
+    report.rejected = tally.rejects;
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    emit_import_progress(&app, &import_id, report.rows_read, report.rows_read);
+    println!("📥 Importación terminada: {} filas, {} rechazadas", report.rows_imported, report.rows_rejected);
+    Ok(report)
+}
+
+// ===== CROSS-ENGINE TRANSFER =====
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferIfExists {
+    /// Skip the table with an error (default)
+    #[default]
+    Fail,
+    /// Drop and recreate the target table
+    Replace,
+    /// Insert into the existing table (columns matched by name)
+    Append,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferRequest {
+    pub source_container_id: String,
+    #[serde(default)]
+    pub source_database: Option<String>,
+    /// PostgreSQL schema of the source tables (default `public`)
+    #[serde(default)]
+    pub source_schema: Option<String>,
+    pub target_container_id: String,
+    #[serde(default)]
+    pub target_database: Option<String>,
+    /// PostgreSQL schema for the new tables (default `public`, created if missing)
+    #[serde(default)]
+    pub target_schema: Option<String>,
+    /// Tables to copy; `*` works as wildcard. Empty copies every table
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub if_exists: TransferIfExists,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// ID for progress events and `cancel_query` (generated when omitted)
+    #[serde(default)]
+    pub transfer_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferColumnMapping {
+    pub name: String,
+    pub source_type: String,
+    pub target_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferTableResult {
+    pub table: String,
+    pub target_table: String,
+    pub created: bool,
+    pub columns: Vec<TransferColumnMapping>,
+    pub rows_copied: u64,
+    pub rows_rejected: u64,
+    pub rejected: Vec<RejectedRow>,
+    /// Why the table was skipped or stopped
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransferReport {
+    pub transfer_id: String,
+    pub source: String,
+    pub target: String,
+    pub tables: Vec<TransferTableResult>,
+    pub rows_copied: u64,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+/// `*` matches any run of characters
+fn table_pattern_matches(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let Some(mut rest) = name.strip_prefix(parts[0]) else {
+        return false;
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(parts[parts.len() - 1])
+}
+
+fn transfer_selects_table(request: &TransferRequest, table: &str) -> bool {
+    (request.include.is_empty() || request.include.iter().any(|p| table_pattern_matches(p, table)))
+        && !request.exclude.iter().any(|p| table_pattern_matches(p, table))
+}
+
+/// Split `numeric(10,2) unsigned` into (`numeric`, Some(`10,2`)); the base keeps any suffix after the parentheses
+fn split_column_type(data_type: &str) -> (String, Option<String>) {
+    let lower = data_type.trim().to_lowercase();
+    match (lower.find('('), lower.find(')')) {
+        (Some(open), Some(close)) if close > open => {
+            let base = format!("{}{}", lower[..open].trim_end(), &lower[close + 1..]);
+            (base, Some(lower[open + 1..close].replace(' ', "")))
+        }
+        _ => (lower, None),
+    }
+}
+
+fn is_binary_column(data_type: &str, db_type: &DatabaseType) -> bool {
+    let (base, _) = split_column_type(data_type);
+    match db_type {
+        DatabaseType::PostgreSQL => base == "bytea",
+        _ => ["binary", "varbinary", "tinyblob", "blob", "mediumblob", "longblob"].iter().any(|t| base.starts_with(t)),
+    }
+}
+
+/// Column type for the target engine; the same engine keeps the type as is
+fn map_transfer_type(data_type: &str, from: &DatabaseType, to: &DatabaseType, in_primary_key: bool) -> String {
+    let pg_source = *from == DatabaseType::PostgreSQL;
+    let pg_target = *to == DatabaseType::PostgreSQL;
+    if pg_source == pg_target {
+        return data_type.to_string();
+    }
+    let (base, args) = split_column_type(data_type);
+    let with_args = |name: &str| args.as_ref().map(|a| format!("{}({})", name, a)).unwrap_or_else(|| name.to_string());
+
+    if pg_source {
+        // MySQL no indexa columnas TEXT sin longitud, así que las de la clave primaria pasan a VARCHAR
+        let text = if in_primary_key { "VARCHAR(255)" } else { "LONGTEXT" };
+        if base.ends_with("[]") {
+            return "JSON".to_string();
+        }
+        let precision = args.as_ref().and_then(|a| a.parse::<u32>().ok()).unwrap_or(6).min(6);
+        match base.as_str() {
+            "boolean" => "TINYINT(1)".to_string(),
+            "smallint" => "SMALLINT".to_string(),
+            "integer" => "INT".to_string(),
+            "bigint" => "BIGINT".to_string(),
+            "numeric" => args.map(|a| format!("DECIMAL({})", a)).unwrap_or_else(|| "DECIMAL(65,30)".to_string()),
+            "real" => "FLOAT".to_string(),
+            "double precision" => "DOUBLE".to_string(),
+            "character varying" if args.is_some() => with_args("VARCHAR"),
+            "character" => with_args("CHAR"),
+            "date" => "DATE".to_string(),
+            "json" | "jsonb" => "JSON".to_string(),
+            "uuid" => "CHAR(36)".to_string(),
+            "bytea" => "LONGBLOB".to_string(),
+            b if b.starts_with("timestamp") => format!("DATETIME({})", precision),
+            b if b.starts_with("time") => format!("TIME({})", precision),
+            b if b.starts_with("interval") => "VARCHAR(64)".to_string(),
+            _ => text.to_string(),
+        }
+    } else {
+        let unsigned = base.contains("unsigned");
+        let base = base.replace("unsigned", "").replace("zerofill", "").trim().to_string();
+        match base.as_str() {
+            "tinyint" if args.as_deref() == Some("1") => "BOOLEAN".to_string(),
+            "tinyint" | "smallint" if unsigned => "INTEGER".to_string(),
+            "tinyint" | "smallint" | "year" => "SMALLINT".to_string(),
+            "mediumint" => "INTEGER".to_string(),
+            "int" | "integer" if unsigned => "BIGINT".to_string(),
+            "int" | "integer" => "INTEGER".to_string(),
+            "bigint" if unsigned => "NUMERIC(20)".to_string(),
+            "bigint" | "bit" => "BIGINT".to_string(),
+            "decimal" | "numeric" => with_args("NUMERIC"),
+            "float" => "REAL".to_string(),
+            "double" | "real" => "DOUBLE PRECISION".to_string(),
+            "char" => with_args("CHAR"),
+            "varchar" => with_args("VARCHAR"),
+            "date" => "DATE".to_string(),
+            "datetime" | "timestamp" => with_args("TIMESTAMP"),
+            "time" => "TIME".to_string(),
+            "json" => "JSONB".to_string(),
+            _ if is_binary_column(data_type, from) => "BYTEA".to_string(),
+            _ => "TEXT".to_string(),
+        }
+    }
+}
+
+/// Expression that reads a source column in a form the target engine can load
+fn transfer_select_expr(column: &TableColumn, from: &DatabaseType, to: &DatabaseType) -> String {
+    let name = quote_ident(&column.name, from);
+    let (base, _) = split_column_type(&column.data_type);
+    let expr = if is_binary_column(&column.data_type, from) {
+        match from {
+            DatabaseType::PostgreSQL => format!("encode({}, 'hex')", name),
+            _ => format!("HEX({})", name),
+        }
+    } else if *from == DatabaseType::PostgreSQL && *to != DatabaseType::PostgreSQL && base == "timestamp with time zone" {
+        format!("({} AT TIME ZONE 'UTC')::text", name)
+    } else if *from == DatabaseType::PostgreSQL && *to != DatabaseType::PostgreSQL && column.data_type.ends_with("[]") {
+        // Los arrays van a columnas JSON en MySQL
+        format!("array_to_json({})::text", name)
+    } else if *from == DatabaseType::PostgreSQL && base != "boolean" {
+        // Todo lo demás viaja en su forma de texto: numeric sin perder precisión y arrays,
+        // rangos, tipos compuestos o enums como literales que PostgreSQL vuelve a leer
+        format!("{}::text", name)
+    } else if *from != DatabaseType::PostgreSQL && base == "bit" {
+        format!("({} + 0)", name)
+    } else {
+        return name;
+    };
+    format!("{} AS {}", expr, name)
+}
+
+fn transfer_cell(value: &serde_json::Value, hex: bool, to: &DatabaseType) -> Option<String> {
+    match value {
+        serde_json::Value::Bool(b) if *to != DatabaseType::PostgreSQL => Some(if *b { "1" } else { "0" }.to_string()),
+        serde_json::Value::String(s) if hex && *to == DatabaseType::PostgreSQL => Some(format!("\\x{}", s)),
+        other => json_to_sql_param(other),
+    }
+}
+
+async fn list_transfer_tables(conn: &mut SqlConn, db_type: &DatabaseType, schema: &str) -> Result<Vec<String>, String> {
+    let mut sql = SqlBuilder::new(db_type);
+    sql.push("SELECT table_name AS name FROM information_schema.tables WHERE table_schema = ")
+        .bind(Some(schema.to_string()), None)
+        .push(" AND table_type = 'BASE TABLE' ORDER BY table_name");
+    Ok(conn
+        .query_json(&sql)
+        .await?
+        .iter()
+        .filter_map(|row| row.get("name").and_then(|n| n.as_str()).map(|n| n.to_string()))
+        .collect())
+}
+
+async fn not_null_columns(conn: &mut SqlConn, db_type: &DatabaseType, schema: &str, table: &str) -> Result<Vec<String>, String> {
+    let mut sql = SqlBuilder::new(db_type);
+    sql.push("SELECT column_name AS name FROM information_schema.columns WHERE table_schema = ")
+        .bind(Some(schema.to_string()), None)
+        .push(" AND table_name = ")
+        .bind(Some(table.to_string()), None)
+        .push(" AND is_nullable = 'NO'");
+    Ok(conn
+        .query_json(&sql)
+        .await?
+        .iter()
+        .filter_map(|row| row.get("name").and_then(|n| n.as_str()).map(|n| n.to_string()))
+        .collect())
+}
+
+/// Both ends of a transfer with the settings shared by every table
+struct TransferContext<'a> {
+    app: &'a tauri::AppHandle,
+    request: &'a TransferRequest,
+    transfer_id: String,
+    source: DbTarget,
+    source_database: String,
+    source_schema: String,
+    target: DbTarget,
+    target_database: String,
+    target_schema: String,
+    cancel: Arc<tokio::sync::Notify>,
+    cancelled: bool,
+}
+
+impl TransferContext<'_> {
+    fn emit_progress(&self, table: &str, tables_done: usize, table_count: usize, rows: u64, total_estimate: Option<u64>) {
+        let _ = self.app.emit(
+            "transfer-progress",
+            json!({
+                "transfer_id": self.transfer_id,
+                "table": table,
+                "tables_done": tables_done,
+                "table_count": table_count,
+                "rows": rows,
+                "total_estimate": total_estimate,
+            }),
+        );
+    }
+
+    /// information_schema calls the PostgreSQL schema or the MySQL database `table_schema`
+    fn source_schema_or_database(&self) -> String {
+        match self.source.db_type {
+            DatabaseType::PostgreSQL => self.source_schema.clone(),
+            _ => self.source_database.clone(),
+        }
+    }
+
+    fn check_cancel(&mut self) -> Result<(), String> {
+        use futures_util::FutureExt;
+        if self.cancelled || self.cancel.notified().now_or_never().is_some() {
+            self.cancelled = true;
+            return Err("Transfer cancelled".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Create (or reuse) the target table and copy the rows of one source table
+async fn transfer_table(
+    ctx: &mut TransferContext<'_>,
+    source_conn: &mut SqlConn,
+    target_conn: &mut SqlConn,
+    table: &str,
+    result: &mut TransferTableResult,
+    position: (usize, usize),
+) -> Result<(), String> {
+    let from = ctx.source.db_type.clone();
+    let to = ctx.target.db_type.clone();
+    let source_ref = TableRef { database: Some(ctx.source_database.clone()), schema: Some(ctx.source_schema.clone()), table: table.to_string() };
+    let target_ref = TableRef { database: Some(ctx.target_database.clone()), schema: Some(ctx.target_schema.clone()), table: table.to_string() };
+    let source_meta = load_table_meta(source_conn, &from, &source_ref, &ctx.source_database).await?;
+    let existing = load_table_meta(target_conn, &to, &target_ref, &ctx.target_database).await.ok();
+
+    let target_meta = match (existing, ctx.request.if_exists) {
+        (Some(_), TransferIfExists::Fail) => return Err("The target table already exists".to_string()),
+        (Some(meta), TransferIfExists::Append) => meta,
+        (existing, _) => {
+            if let Some(meta) = existing {
+                target_conn.batch(&format!("DROP TABLE {}", meta.qualified_name)).await?;
+            }
+            let not_null = not_null_columns(source_conn, &from, &ctx.source_schema_or_database(), table).await?;
+            let mut definitions: Vec<String> = source_meta
+                .columns
+                .iter()
+                .map(|c| {
+                    let data_type = map_transfer_type(&c.data_type, &from, &to, source_meta.primary_key.contains(&c.name));
+                    let null = if not_null.contains(&c.name) { " NOT NULL" } else { "" };
+                    format!("{} {}{}", quote_ident(&c.name, &to), data_type, null)
+                })
+                .collect();
+            if !source_meta.primary_key.is_empty() {
+                let key: Vec<String> = source_meta.primary_key.iter().map(|c| quote_ident(c, &to)).collect();
+                definitions.push(format!("PRIMARY KEY ({})", key.join(", ")));
+            }
+            let target_container = match to {
+                DatabaseType::PostgreSQL => &ctx.target_schema,
+                _ => &ctx.target_database,
+            };
+            let qualified = format!("{}.{}", quote_ident(target_container, &to), quote_ident(table, &to));
+            target_conn.batch(&format!("CREATE TABLE {} ({})", qualified, definitions.join(", "))).await?;
+            result.created = true;
+            load_table_meta(target_conn, &to, &target_ref, &ctx.target_database).await?
+        }
+    };
+    result.target_table = target_meta.qualified_name.clone();
+
+    // Solo las columnas que existen en ambos lados, en el orden de la tabla de origen
+    let pairs: Vec<(&TableColumn, &TableColumn)> = source_meta
+        .columns
+        .iter()
+        .filter_map(|s| target_meta.columns.iter().find(|t| t.name == s.name).map(|t| (s, t)))
+        .collect();
+    if pairs.is_empty() {
+        return Err("No column of the source table exists in the target table".to_string());
+    }
+    result.columns = pairs
+        .iter()
+        .map(|(s, t)| TransferColumnMapping { name: s.name.clone(), source_type: s.data_type.clone(), target_type: t.data_type.clone() })
+        .collect();
+    let hex_columns: Vec<bool> = pairs.iter().map(|(s, _)| is_binary_column(&s.data_type, &from)).collect();
+
+    let total_estimate = table_row_estimate(source_conn, &from, &source_meta).await;
+    let select: Vec<String> = pairs.iter().map(|(s, _)| transfer_select_expr(s, &from, &to)).collect();
+    let sql = format!("SELECT {} FROM {}", select.join(", "), source_meta.qualified_name);
+
+    let mysql = to != DatabaseType::PostgreSQL;
+    let max_rows = if mysql { (65_535 / pairs.len()).max(1) } else { usize::MAX };
+    let batch_size = ctx.request.batch_size.unwrap_or(IMPORT_DEFAULT_BATCH_ROWS).clamp(1, max_rows);
+    let mut loader = SqlImportLoader {
+        conn: target_conn,
+        db_type: to.clone(),
+        qualified_name: target_meta.qualified_name.clone(),
+        columns: pairs.iter().map(|(_, t)| (*t).clone()).collect(),
+        hex_columns: hex_columns.clone(),
+        load_data: mysql,
+        method: if mysql { "load_data" } else { "copy" },
+    };
+
+    let mut tally = ImportTally { imported: 0, rejected: 0, rejects: Vec::new() };
+    let mut rows = source_conn.stream_rows(&sql).await?;
+    let mut batch: Vec<(u64, Vec<Option<String>>)> = Vec::with_capacity(batch_size.min(IMPORT_DEFAULT_BATCH_ROWS));
+    let mut row_number = 0;
+    let outcome = loop {
+        let values = match rows.next_row().await {
+            Ok(Some(values)) => values,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        };
+        row_number += 1;
+        let cells = values.iter().zip(&hex_columns).map(|(v, hex)| transfer_cell(v, *hex, &to)).collect();
+        batch.push((row_number, cells));
+        if batch.len() >= batch_size {
+            loader.load(&batch, &mut tally).await;
+            batch.clear();
+            ctx.emit_progress(table, position.0, position.1, row_number, total_estimate);
+            if let Err(e) = ctx.check_cancel() {
+                break Err(e);
+            }
+        }
+    };
+    if outcome.is_ok() {
+        loader.load(&batch, &mut tally).await;
+    }
+    drop(rows);
+
+    result.rows_copied = tally.imported;
+    result.rows_rejected = tally.rejected;
+    result.rejected = tally.rejects;
+    outcome
+}
+
+/// Copy tables and rows from one managed database container to another
+///
+/// Works between PostgreSQL, MySQL and MariaDB in any direction. Column types are mapped
+/// between engines (e.g. `tinyint(1)` ↔ `boolean`, `jsonb` → `JSON`, `bytea` ↔ `LONGBLOB`)
+/// and tables are created with their NOT NULL constraints and primary key; defaults,
+/// secondary indexes and foreign keys are not copied. Rows are streamed from the source and
+/// bulk loaded with `COPY` or `LOAD DATA LOCAL`, and rows the target refuses are reported
+/// per table. Progress is emitted as `transfer-progress` events and `cancel_query(transfer_id)`
+/// stops after the current batch.
+///
+/// # Arguments
+/// * `request` - Source and target containers/databases, include/exclude lists and what to do with existing tables
+///
+/// # Returns
+/// * `Ok(TransferReport)` - Result of each table (copied and rejected rows, type mapping, errors)
+/// * `Err(String)` - Unsupported engine or connection error
+#[tauri::command]
+async fn transfer_data(
+    app: tauri::AppHandle,
+    request: TransferRequest,
+    state: State<'_, AppState>,
+    running: State<'_, RunningQueriesState>,
+) -> Result<TransferReport, String> {
+    let transfer_id = match request.transfer_id.clone().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    let docker = state.docker();
+    let source = resolve_db_target(&docker, &request.source_container_id).await?;
+    let target = resolve_db_target(&docker, &request.target_container_id).await?;
+    for end in [&source, &target] {
+        if !matches!(end.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB) {
+            return Err(format!("Data transfer is not available for {} ({})", end.db_type.to_string(), end.container_name));
+        }
+    }
+    let pick = |value: &Option<String>, default: &str| value.clone().filter(|v| !v.is_empty()).unwrap_or_else(|| default.to_string());
+    let source_database = pick(&request.source_database, &source.database);
+    let target_database = pick(&request.target_database, &target.database);
+    let source_schema = pick(&request.source_schema, "public");
+    let target_schema = pick(&request.target_schema, "public");
+    if source.container_id == target.container_id && source_database == target_database && source_schema == target_schema {
+        return Err("Source and target are the same database".to_string());
+    }
+
+    let started = std::time::Instant::now();
+    let mut source_conn = SqlConn::connect(&docker, &source, Some(&source_database)).await?;
+    let mut target_conn = match SqlConn::connect(&docker, &target, Some(&target_database)).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            source_conn.close().await;
+            return Err(e);
+        }
+    };
+    let running_query = running.register(&transfer_id, &source, &format!("transfer to {}", target.container_name), 0)?;
+    let mut ctx = TransferContext {
+        app: &app,
+        request: &request,
+        transfer_id: transfer_id.clone(),
+        source: source.clone(),
+        source_database,
+        source_schema,
+        target: target.clone(),
+        target_database,
+        target_schema,
+        cancel: running_query.cancel.clone(),
+        cancelled: false,
+    };
+    println!("🔁 Transferencia {} → {}", source.container_name, target.container_name);
+
+    let mut tables = Vec::new();
+    let prepared = async {
+        if target.db_type == DatabaseType::PostgreSQL && ctx.target_schema != "public" {
+            target_conn.batch(&format!("CREATE SCHEMA IF NOT EXISTS {}", quote_ident(&ctx.target_schema, &target.db_type))).await?;
+        }
+        let names = list_transfer_tables(&mut source_conn, &source.db_type, &ctx.source_schema_or_database()).await?;
+        Ok::<Vec<String>, String>(names.into_iter().filter(|t| transfer_selects_table(&request, t)).collect())
+    }
+    .await;
+
+    match prepared {
+        Ok(names) => {
+            for (index, table) in names.iter().enumerate() {
+                let table_started = std::time::Instant::now();
+                let mut result = TransferTableResult {
+                    table: table.clone(),
+                    target_table: String::new(),
+                    created: false,
+                    columns: Vec::new(),
+                    rows_copied: 0,
+                    rows_rejected: 0,
+                    rejected: Vec::new(),
+                    error: None,
+                    duration_ms: 0,
+                };
+                if let Err(e) = transfer_table(&mut ctx, &mut source_conn, &mut target_conn, table, &mut result, (index, names.len())).await {
+                    eprintln!("🔁 Tabla {} no transferida: {}", table, e);
+                    result.error = Some(e);
+                    // Un error a mitad de tabla puede dejar un resultado sin leer: se reconecta
+                    let reconnected = async {
+                        let source_next = SqlConn::connect(&docker, &source, Some(&ctx.source_database)).await?;
+                        let target_next = SqlConn::connect(&docker, &target, Some(&ctx.target_database)).await?;
+                        Ok::<_, String>((source_next, target_next))
+                    }
+                    .await;
+                    match reconnected {
+                        Ok((source_next, target_next)) => {
+                            source_conn = source_next;
+                            target_conn = target_next;
+                        }
+                        Err(e) => {
+                            result.error = result.error.map(|error| format!("{}; stopping, reconnect failed: {}", error, e));
+                            result.duration_ms = table_started.elapsed().as_millis() as u64;
+                            tables.push(result);
+                            break;
+                        }
+                    }
+                }
+                result.duration_ms = table_started.elapsed().as_millis() as u64;
+                ctx.emit_progress(table, index + 1, names.len(), result.rows_copied, None);
+                tables.push(result);
+                if ctx.cancelled {
+                    break;
+                }
+            }
+        }
+        Err(e) => {
+            source_conn.close().await;
+            target_conn.close().await;
+            return Err(e);
+        }
+    }
+    drop(running_query);
+    let cancelled = ctx.cancelled;
+    source_conn.close().await;
+    target_conn.close().await;
+
+    Ok(TransferReport {
+        transfer_id,
+        source: format!("{}/{}", source.container_name, ctx.source_database),
+        target: format!("{}/{}", target.container_name, ctx.target_database),
+        rows_copied: tables.iter().map(|t| t.rows_copied).sum(),
+        tables,
+        cancelled,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+// ===== SCHEMA DIFF =====
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ViewInfo {
+    pub schema: String,
+    pub name: String,
+    /// view or materialized_view
+    pub kind: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RoutineInfo {
+    pub schema: String,
+    pub name: String,
+    /// function or procedure
+    pub kind: String,
+    /// `name(argument types)`, identifies overloads on PostgreSQL
+    pub signature: String,
+    /// Full CREATE statement
+    pub definition: String,
+    /// Result type of a PostgreSQL function, which `CREATE OR REPLACE` cannot change
+    pub result: Option<String>,
+}
+
+/// Primary key, unique, check or exclusion constraint (MySQL/MariaDB: check constraints only)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ConstraintInfo {
+    pub schema: String,
+    pub table: String,
+    pub name: String,
+    pub kind: String,
+    pub definition: String,
+}
+
+/// Schema objects that are not part of the table tree returned by `load_database_schema`
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SchemaObjects {
+    views: Vec<ViewInfo>,
+    routines: Vec<RoutineInfo>,
+    constraints: Vec<ConstraintInfo>,
+}
+
+const PG_SCHEMA_OBJECTS_QUERY: &str = "SELECT json_build_object(\
+    'views', (SELECT coalesce(json_agg(json_build_object('schema', n.nspname, 'name', c.relname, \
+        'kind', CASE c.relkind WHEN 'm' THEN 'materialized_view' ELSE 'view' END, 'definition', pg_get_viewdef(c.oid, true)) \
+        ORDER BY n.nspname, c.relname), '[]') \
+        FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace WHERE c.relkind IN ('v', 'm') \
+        AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp%'), \
+    'routines', (SELECT coalesce(json_agg(json_build_object('schema', n.nspname, 'name', p.proname, \
+        'kind', CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END, \
+        'signature', p.proname || '(' || pg_get_function_identity_arguments(p.oid) || ')', 'definition', pg_get_functiondef(p.oid), \
+        'result', pg_get_function_result(p.oid)) \
+        ORDER BY n.nspname, p.proname), '[]') \
+        FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace WHERE p.prokind IN ('f', 'p') \
+        AND NOT EXISTS (SELECT 1 FROM pg_depend dep WHERE dep.objid = p.oid AND dep.deptype = 'e') \
+        AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp%'), \
+    'constraints', (SELECT coalesce(json_agg(json_build_object('schema', n.nspname, 'table', c.relname, 'name', con.conname, \
+        'kind', CASE con.contype WHEN 'p' THEN 'primary_key' WHEN 'u' THEN 'unique' WHEN 'x' THEN 'exclusion' ELSE 'check' END, \
+        'definition', pg_get_constraintdef(con.oid, true)) ORDER BY n.nspname, c.relname, con.conname), '[]') \
+        FROM pg_constraint con JOIN pg_class c ON c.oid = con.conrelid JOIN pg_namespace n ON n.oid = c.relnamespace \
+        WHERE con.contype IN ('p', 'u', 'c', 'x') \
+        AND n.nspname NOT IN ('pg_catalog', 'information_schema') AND n.nspname NOT LIKE 'pg_toast%' AND n.nspname NOT LIKE 'pg_temp%'))";
+
+/// Views and routines of one MySQL/MariaDB database (one JSON line per query)
+fn mysql_schema_object_queries(database: &str, db_type: &DatabaseType) -> String {
+    let db = quote_sql_literal(database, db_type);
+    format!(
+        "SELECT JSON_ARRAYAGG(JSON_OBJECT('name', TABLE_NAME, 'kind', 'view', 'definition', VIEW_DEFINITION)) \
+            FROM information_schema.VIEWS WHERE TABLE_SCHEMA = {db}; \
+         SELECT JSON_ARRAYAGG(JSON_OBJECT('name', r.ROUTINE_NAME, 'kind', LOWER(r.ROUTINE_TYPE), 'returns', r.DTD_IDENTIFIER, \
+            'body', r.ROUTINE_DEFINITION, 'deterministic', r.IS_DETERMINISTIC, 'data_access', r.SQL_DATA_ACCESS, \
+            'parameters', (SELECT GROUP_CONCAT(CONCAT_WS(' ', IF(r.ROUTINE_TYPE = 'PROCEDURE', p.PARAMETER_MODE, NULL), \
+                CONCAT('`', REPLACE(p.PARAMETER_NAME, '`', '``'), '`'), p.DTD_IDENTIFIER) ORDER BY p.ORDINAL_POSITION SEPARATOR ', ') \
+                FROM information_schema.PARAMETERS p WHERE p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA AND p.SPECIFIC_NAME = r.SPECIFIC_NAME \
+                AND p.ORDINAL_POSITION > 0))) \
+            FROM information_schema.ROUTINES r WHERE r.ROUTINE_SCHEMA = {db};",
+        db = db
+    )
+}
+
+/// CHECK_CONSTRAINTS only exists since MySQL 8.0.16 / MariaDB 10.2, so it runs on its own
+fn mysql_check_constraints_query(database: &str, db_type: &DatabaseType) -> String {
+    let db = quote_sql_literal(database, db_type);
+    format!(
+        "SELECT JSON_ARRAYAGG(JSON_OBJECT('table', tc.TABLE_NAME, 'name', tc.CONSTRAINT_NAME, 'kind', 'check', \
+            'definition', CONCAT('CHECK (', cc.CHECK_CLAUSE, ')'))) \
+            FROM information_schema.TABLE_CONSTRAINTS tc JOIN information_schema.CHECK_CONSTRAINTS cc \
+            ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME \
+            WHERE tc.TABLE_SCHEMA = {db} AND tc.CONSTRAINT_TYPE = 'CHECK'",
+        db = db
+    )
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct MysqlRoutineRow {
+    name: String,
+    kind: String,
+    returns: Option<String>,
+    body: Option<String>,
+    deterministic: String,
+    data_access: Option<String>,
+    parameters: Option<String>,
+}
+
+impl MysqlRoutineRow {
+    fn into_routine(self, db_type: &DatabaseType) -> RoutineInfo {
+        let returns = match (&self.returns, self.kind.as_str()) {
+            (Some(returns), "function") => format!(" RETURNS {}", returns),
+            _ => String::new(),
+        };
+        let deterministic = if self.deterministic == "YES" { "DETERMINISTIC" } else { "NOT DETERMINISTIC" };
+        let definition = format!(
+            "CREATE {} {}({}){}\n{} {}\n{}",
+            self.kind.to_uppercase(),
+            quote_ident(&self.name, db_type),
+            self.parameters.unwrap_or_default(),
+            returns,
+            deterministic,
+            self.data_access.unwrap_or_else(|| "CONTAINS SQL".to_string()),
+            self.body.unwrap_or_default()
+        );
+        RoutineInfo { schema: String::new(), signature: self.name.clone(), name: self.name, kind: self.kind, definition, result: None }
+    }
+}
+
+async fn load_schema_objects(docker: &Docker, target: &DbTarget, database: &str) -> Result<SchemaObjects, String> {
+    match target.db_type {
+        DatabaseType::PostgreSQL => {
+            parse_json_line(last_json_line(&run_engine_script(docker, target, Some(database), PG_SCHEMA_OBJECTS_QUERY).await?))
+        }
+        DatabaseType::MySQL | DatabaseType::MariaDB => {
+            let output = run_engine_script(docker, target, Some(""), &mysql_schema_object_queries(database, &target.db_type)).await?;
+            let lines: Vec<&str> = output.lines().filter(|l| !l.trim().is_empty()).collect();
+            if lines.len() < 2 {
+                return Err(format!("Unexpected catalog output: {}", output.trim()));
+            }
+            let mut views: Vec<ViewInfo> = parse_json_line(lines[0])?;
+            let routines: Vec<MysqlRoutineRow> = parse_json_line(lines[1])?;
+            let constraints: Vec<ConstraintInfo> = run_engine_script(docker, target, Some(""), &mysql_check_constraints_query(database, &target.db_type))
+                .await
+                .ok()
+                .and_then(|output| parse_json_line(last_json_line(&output)).ok())
+                .unwrap_or_default();
+
+            // VIEW_DEFINITION califica cada tabla con la base de datos; sin ella se puede comparar entre bases distintas
+            let prefix = format!("{}.", quote_ident(database, &target.db_type));
+            for view in views.iter_mut() {
+                view.definition = view.definition.replace(&prefix, "");
+            }
+            let routines = routines.into_iter().map(|r| r.into_routine(&target.db_type)).collect();
+            Ok(SchemaObjects { views, routines, constraints })
+        }
+        _ => Err(format!("Schema diff is not available for {}", target.db_type.to_string())),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SchemaDiffRequest {
+    pub source_container_id: String,
+    pub source_database: String,
+    pub target_container_id: String,
+    pub target_database: String,
+    /// PostgreSQL schemas to compare (every user schema when empty)
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    /// Generate DROP statements for objects that only exist in the target
+    #[serde(default = "default_true")]
+    pub include_drops: bool,
+    /// Also write the migration script to this file (to run it with `run_sql_script`)
+    #[serde(default)]
+    pub script_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaChangeKind {
+    /// Only in the source: created in the target
+    Added,
+    /// Only in the target: dropped from it
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaChange {
+    /// table, column, index, foreign_key, constraint, view, function, procedure
+    pub object_type: String,
+    pub schema: String,
+    pub table: Option<String>,
+    pub name: String,
+    pub change: SchemaChangeKind,
+    /// What differs in a modified object, e.g. `type: integer → bigint`
+    pub details: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaDiff {
+    pub source: String,
+    pub target: String,
+    pub db_type: String,
+    pub changes: Vec<SchemaChange>,
+    /// DDL that brings the target in line with the source; review it before running
+    pub ddl: String,
+    /// Statements that can lose data or fail on existing rows
+    pub warnings: Vec<String>,
+    pub script_path: Option<String>,
+}
+
+/// One side of the comparison; MySQL/MariaDB objects get an empty schema so two databases compare by table name
+struct SchemaSnapshot {
+    schemas: Vec<String>,
+    tables: Vec<TableInfo>,
+    objects: SchemaObjects,
+}
+
+fn schema_snapshot(schema: DatabaseSchema, mut objects: SchemaObjects, db_type: &DatabaseType, only: &[String]) -> SchemaSnapshot {
+    let database = schema.name.clone();
+    let keep = |name: &str| only.is_empty() || only.iter().any(|s| s == name);
+    let mut tables: Vec<TableInfo> = schema
+        .schemas
+        .into_iter()
+        .flat_map(|s| s.tables)
+        .filter(|t| t.kind == "table" && (*db_type != DatabaseType::PostgreSQL || keep(&t.schema)))
+        .collect();
+    let mut schemas: Vec<String> = Vec::new();
+
+    if *db_type == DatabaseType::PostgreSQL {
+        objects.views.retain(|v| keep(&v.schema));
+        objects.routines.retain(|r| keep(&r.schema));
+        objects.constraints.retain(|c| keep(&c.schema));
+        for name in tables.iter().map(|t| &t.schema).chain(objects.views.iter().map(|v| &v.schema)).chain(objects.routines.iter().map(|r| &r.schema)) {
+            if !schemas.contains(name) {
+                schemas.push(name.clone());
+            }
+        }
+    } else {
+        for table in tables.iter_mut() {
+            table.schema = String::new();
+            for fk in table.foreign_keys.iter_mut() {
+                if fk.referenced_schema.as_deref() == Some(database.as_str()) {
+                    fk.referenced_schema = None;
+                }
+            }
+        }
+        objects.views.iter_mut().for_each(|v| v.schema = String::new());
+        objects.routines.iter_mut().for_each(|r| r.schema = String::new());
+        objects.constraints.iter_mut().for_each(|c| c.schema = String::new());
+    }
+    SchemaSnapshot { schemas, tables, objects }
+}
+
+/// Sections of the generated script, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MigrationPhase {
+    DropViews,
+    DropForeignKeys,
+    DropConstraints,
+    DropRoutines,
+    DropTables,
+    CreateSchemas,
+    // Antes que las tablas: los valores por defecto y las restricciones pueden usar funciones
+    CreateRoutines,
+    CreateTables,
+    AlterColumns,
+    CreateConstraints,
+    CreateForeignKeys,
+    CreateViews,
+}
+
+impl MigrationPhase {
+    fn title(&self) -> &'static str {
+        match self {
+            MigrationPhase::DropViews => "Drop changed, removed and dependent views",
+            MigrationPhase::DropForeignKeys => "Drop changed and removed foreign keys",
+            MigrationPhase::DropConstraints => "Drop changed and removed indexes and constraints",
+            MigrationPhase::DropRoutines => "Drop changed and removed routines",
+            MigrationPhase::DropTables => "Drop removed tables",
+            MigrationPhase::CreateSchemas => "Create schemas",
+            MigrationPhase::CreateTables => "Create tables",
+            MigrationPhase::AlterColumns => "Alter columns",
+            MigrationPhase::CreateConstraints => "Create indexes and constraints",
+            MigrationPhase::CreateForeignKeys => "Create foreign keys",
+            MigrationPhase::CreateRoutines => "Create routines",
+            MigrationPhase::CreateViews => "Create views",
+        }
+    }
+}
+
+struct MigrationPlan {
+    db_type: DatabaseType,
+    include_drops: bool,
+    changes: Vec<SchemaChange>,
+    warnings: Vec<String>,
+    statements: std::collections::BTreeMap<MigrationPhase, Vec<String>>,
+}
+
+impl MigrationPlan {
+    fn pg(&self) -> bool {
+        self.db_type == DatabaseType::PostgreSQL
+    }
+
+    fn name(&self, schema: &str, name: &str) -> String {
+        if schema.is_empty() {
+            quote_ident(name, &self.db_type)
+        } else {
+            format!("{}.{}", quote_ident(schema, &self.db_type), quote_ident(name, &self.db_type))
+        }
+    }
+
+    fn change(&mut self, object_type: &str, schema: &str, table: Option<&str>, name: &str, change: SchemaChangeKind, details: Vec<String>) {
+        self.changes.push(SchemaChange {
+            object_type: object_type.to_string(),
+            schema: schema.to_string(),
+            table: table.map(|t| t.to_string()),
+            name: name.to_string(),
+            change,
+            details,
+        });
+    }
+
+    fn push(&mut self, phase: MigrationPhase, statement: String) {
+        self.statements.entry(phase).or_default().push(statement);
+    }
+
+    /// Drops of objects that only exist in the target are left out when `include_drops` is off
+    fn push_drop(&mut self, phase: MigrationPhase, statement: String) {
+        if self.include_drops {
+            self.push(phase, statement);
+        }
+    }
+
+    fn column_definition(&self, column: &ColumnInfo) -> String {
+        let mut definition = format!("{} {}", quote_ident(&column.name, &self.db_type), column.data_type);
+        // Las columnas generadas de MySQL exigen su cláusula justo después del tipo
+        if let Some(extra) = &column.extra {
+            definition.push_str(&format!(" {}", extra));
+        }
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(default) = self.default_sql(column.default.as_deref()) {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        definition
+    }
+
+    /// MySQL reports literal defaults without quotes; PostgreSQL and MariaDB give the expression
+    fn default_sql(&self, default: Option<&str>) -> Option<String> {
+        let default = default?;
+        match self.db_type {
+            DatabaseType::MySQL => {
+                let upper = default.to_uppercase();
+                let raw = default.parse::<f64>().is_ok() || upper.starts_with("CURRENT_TIMESTAMP") || upper == "NULL" || default.starts_with('(');
+                Some(if raw { default.to_string() } else { quote_sql_literal(default, &self.db_type) })
+            }
+            DatabaseType::MariaDB if default == "NULL" => None,
+            _ => Some(default.to_string()),
+        }
+    }
+
+    fn script(&self, header: &str) -> String {
+        let mut script = String::from(header);
+        for (phase, statements) in &self.statements {
+            script.push_str(&format!("\n-- {}\n", phase.title()));
+            for statement in statements {
+                script.push_str(statement);
+                script.push('\n');
+            }
+        }
+        script
+    }
+}
+
+fn diff_columns(plan: &mut MigrationPlan, source: &TableInfo, target: &TableInfo) {
+    let table = plan.name(&source.schema, &source.name);
+    for column in &source.columns {
+        let Some(existing) = target.columns.iter().find(|c| c.name == column.name) else {
+            plan.change("column", &source.schema, Some(&source.name), &column.name, SchemaChangeKind::Added, Vec::new());
+            if !column.nullable && column.default.is_none() {
+                plan.warnings.push(format!("Adding NOT NULL column {}.{} without default fails if the table has rows", source.name, column.name));
+            }
+            let statement = format!("ALTER TABLE {} ADD COLUMN {};", table, plan.column_definition(column));
+            plan.push(MigrationPhase::AlterColumns, statement);
+            continue;
+        };
+
+        let mut details = Vec::new();
+        if existing.data_type != column.data_type {
+            details.push(format!("type: {} → {}", existing.data_type, column.data_type));
+        }
+        if existing.nullable != column.nullable {
+            details.push(format!("nullable: {} → {}", existing.nullable, column.nullable));
+        }
+        if existing.default != column.default {
+            details.push(format!(
+                "default: {} → {}",
+                existing.default.as_deref().unwrap_or("none"),
+                column.default.as_deref().unwrap_or("none")
+            ));
+        }
+        if existing.extra != column.extra {
+            details.push(format!(
+                "extra: {} → {}",
+                existing.extra.as_deref().unwrap_or("none"),
+                column.extra.as_deref().unwrap_or("none")
+            ));
+        }
+        if details.is_empty() {
+            continue;
+        }
+        if existing.data_type != column.data_type {
+            plan.warnings.push(format!("Changing the type of {}.{} fails if existing values do not convert", source.name, column.name));
+        }
+        let name = quote_ident(&column.name, &plan.db_type);
+        if plan.pg() {
+            if existing.data_type != column.data_type {
+                let statement = format!("ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{};", table, name, column.data_type, name, column.data_type);
+                plan.push(MigrationPhase::AlterColumns, statement);
+            }
+            if existing.nullable != column.nullable {
+                let action = if column.nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+                plan.push(MigrationPhase::AlterColumns, format!("ALTER TABLE {} ALTER COLUMN {} {};", table, name, action));
+            }
+            if existing.default != column.default {
+                let action = match &column.default {
+                    Some(default) => format!("SET DEFAULT {}", default),
+                    None => "DROP DEFAULT".to_string(),
+                };
+                plan.push(MigrationPhase::AlterColumns, format!("ALTER TABLE {} ALTER COLUMN {} {};", table, name, action));
+            }
+            if existing.extra != column.extra {
+                diff_pg_identity(plan, &table, &source.name, existing, column);
+            }
+        } else {
+            let statement = format!("ALTER TABLE {} MODIFY COLUMN {};", table, plan.column_definition(column));
+            plan.push(MigrationPhase::AlterColumns, statement);
+        }
+        plan.change("column", &source.schema, Some(&source.name), &column.name, SchemaChangeKind::Modified, details);
+    }
+
+    for column in target.columns.iter().filter(|c| !source.columns.iter().any(|s| s.name == c.name)) {
+        plan.change("column", &target.schema, Some(&target.name), &column.name, SchemaChangeKind::Removed, Vec::new());
+        if plan.include_drops {
+            plan.warnings.push(format!("Dropping column {}.{} deletes its data", target.name, column.name));
+        }
+        let statement = format!("ALTER TABLE {} DROP COLUMN {};", table, quote_ident(&column.name, &plan.db_type));
+        plan.push_drop(MigrationPhase::AlterColumns, statement);
+    }
+}
+
+/// Identity changes of a PostgreSQL column; generated columns cannot be altered in place
+fn diff_pg_identity(plan: &mut MigrationPlan, table: &str, table_name: &str, existing: &ColumnInfo, column: &ColumnInfo) {
+    let identity = |c: &ColumnInfo| c.extra.as_deref().filter(|e| e.ends_with("AS IDENTITY")).map(|e| e.to_string());
+    let generated = |c: &ColumnInfo| c.extra.as_deref().is_some_and(|e| e.ends_with("STORED"));
+    if generated(existing) || generated(column) {
+        plan.warnings.push(format!("Generated column {}.{} must be dropped and recreated by hand", table_name, column.name));
+        return;
+    }
+    let name = quote_ident(&column.name, &plan.db_type);
+    let statement = match (identity(existing), identity(column)) {
+        (Some(_), Some(extra)) => {
+            let kind = extra.trim_start_matches("GENERATED ").trim_end_matches(" AS IDENTITY");
+            format!("ALTER TABLE {} ALTER COLUMN {} SET GENERATED {};", table, name, kind)
+        }
+        (Some(_), None) => format!("ALTER TABLE {} ALTER COLUMN {} DROP IDENTITY IF EXISTS;", table, name),
+        (None, Some(extra)) => format!("ALTER TABLE {} ALTER COLUMN {} ADD {};", table, name, extra),
+        (None, None) => return,
+    };
+    plan.push(MigrationPhase::AlterColumns, statement);
+}
+
+fn create_index_sql(plan: &mut MigrationPlan, table: &TableInfo, index: &IndexInfo) -> Option<String> {
+    if plan.pg() {
+        return index.definition.as_ref().map(|d| format!("{};", d));
+    }
+    if index.columns.iter().any(|c| c == "(expression)") {
+        plan.warnings.push(format!("Index {} on {} uses expressions and must be recreated by hand", index.name, table.name));
+        return None;
+    }
+    let columns: Vec<String> = index.columns.iter().map(|c| quote_ident(c, &plan.db_type)).collect();
+    let table_name = plan.name(&table.schema, &table.name);
+    if index.primary {
+        return Some(format!("ALTER TABLE {} ADD PRIMARY KEY ({});", table_name, columns.join(", ")));
+    }
+    let kind = match index.definition.as_deref() {
+        Some("FULLTEXT") => "FULLTEXT ",
+        Some("SPATIAL") => "SPATIAL ",
+        _ if index.unique => "UNIQUE ",
+        _ => "",
+    };
+    Some(format!("CREATE {}INDEX {} ON {} ({});", kind, quote_ident(&index.name, &plan.db_type), table_name, columns.join(", ")))
+}
+
+fn drop_index_sql(plan: &MigrationPlan, table: &TableInfo, index: &IndexInfo) -> String {
+    if plan.pg() {
+        format!("DROP INDEX {};", plan.name(&table.schema, &index.name))
+    } else if index.primary {
+        format!("ALTER TABLE {} DROP PRIMARY KEY;", plan.name(&table.schema, &table.name))
+    } else {
+        format!("DROP INDEX {} ON {};", quote_ident(&index.name, &plan.db_type), plan.name(&table.schema, &table.name))
+    }
+}
+
+fn foreign_key_sql(plan: &MigrationPlan, table: &TableInfo, fk: &ForeignKeyInfo) -> String {
+    let quote_all = |names: &[String]| names.iter().map(|n| quote_ident(n, &plan.db_type)).collect::<Vec<_>>().join(", ");
+    let referenced = plan.name(fk.referenced_schema.as_deref().unwrap_or_default(), &fk.referenced_table);
+    let mut statement = format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+        plan.name(&table.schema, &table.name),
+        quote_ident(&fk.name, &plan.db_type),
+        quote_all(&fk.columns),
+        referenced,
+        quote_all(&fk.referenced_columns)
+    );
+    if let Some(action) = &fk.on_update {
+        statement.push_str(&format!(" ON UPDATE {}", action));
+    }
+    if let Some(action) = &fk.on_delete {
+        statement.push_str(&format!(" ON DELETE {}", action));
+    }
+    statement.push(';');
+    statement
+}
+
+fn same_foreign_key(a: &ForeignKeyInfo, b: &ForeignKeyInfo) -> bool {
+    a.columns == b.columns
+        && a.referenced_schema == b.referenced_schema
+        && a.referenced_table == b.referenced_table
+        && a.referenced_columns == b.referenced_columns
+        && a.on_update == b.on_update
+        && a.on_delete == b.on_delete
+}
+
+/// Indexes and foreign keys of a table present on both sides (or only in the source when `target` is None)
+fn diff_table_keys(plan: &mut MigrationPlan, source: &TableInfo, target: Option<&TableInfo>, constraints: &[String]) {
+    let no_indexes = Vec::new();
+    let no_fks = Vec::new();
+    let (target_indexes, target_fks) = target.map(|t| (&t.indexes, &t.foreign_keys)).unwrap_or((&no_indexes, &no_fks));
+    // En PostgreSQL los índices de claves primarias/únicas se gestionan como restricciones
+    let managed = |index: &&IndexInfo| !constraints.contains(&index.name);
+
+    for index in source.indexes.iter().filter(managed) {
+        let existing = target_indexes.iter().find(|i| i.name == index.name);
+        let same = existing.is_some_and(|e| e.definition == index.definition && e.columns == index.columns && e.unique == index.unique);
+        if same {
+            continue;
+        }
+        if let (Some(existing), Some(table)) = (existing, target) {
+            let statement = drop_index_sql(plan, table, existing);
+            plan.push(MigrationPhase::DropConstraints, statement);
+        }
+        let kind = if existing.is_some() { SchemaChangeKind::Modified } else { SchemaChangeKind::Added };
+        let details = existing.map(|e| vec![format!("{} → {}", e.columns.join(", "), index.columns.join(", "))]).unwrap_or_default();
+        plan.change("index", &source.schema, Some(&source.name), &index.name, kind, details);
+        if index.primary && target.is_none() && !plan.pg() {
+            continue;
+        }
+        if let Some(statement) = create_index_sql(plan, source, index) {
+            plan.push(MigrationPhase::CreateConstraints, statement);
+        }
+    }
+    if let Some(table) = target {
+        for index in table.indexes.iter().filter(managed).filter(|i| !source.indexes.iter().any(|s| s.name == i.name)) {
+            plan.change("index", &table.schema, Some(&table.name), &index.name, SchemaChangeKind::Removed, Vec::new());
+            let statement = drop_index_sql(plan, table, index);
+            plan.push_drop(MigrationPhase::DropConstraints, statement);
+        }
+    }
+
+    for fk in &source.foreign_keys {
+        let existing = target_fks.iter().find(|f| f.name == fk.name);
+        if existing.is_some_and(|e| same_foreign_key(e, fk)) {
+            continue;
+        }
+        if existing.is_some() {
+            let statement = drop_foreign_key_sql(plan, source, &fk.name);
+            plan.push(MigrationPhase::DropForeignKeys, statement);
+        }
+        let kind = if existing.is_some() { SchemaChangeKind::Modified } else { SchemaChangeKind::Added };
+        plan.change("foreign_key", &source.schema, Some(&source.name), &fk.name, kind, Vec::new());
+        let statement = foreign_key_sql(plan, source, fk);
+        plan.push(MigrationPhase::CreateForeignKeys, statement);
+    }
+    if let Some(table) = target {
+        for fk in table.foreign_keys.iter().filter(|f| !source.foreign_keys.iter().any(|s| s.name == f.name)) {
+            plan.change("foreign_key", &table.schema, Some(&table.name), &fk.name, SchemaChangeKind::Removed, Vec::new());
+            let statement = drop_foreign_key_sql(plan, table, &fk.name);
+            plan.push_drop(MigrationPhase::DropForeignKeys, statement);
+        }
+    }
+}
+
+fn drop_foreign_key_sql(plan: &MigrationPlan, table: &TableInfo, name: &str) -> String {
+    let keyword = if plan.pg() { "CONSTRAINT" } else { "FOREIGN KEY" };
+    format!("ALTER TABLE {} DROP {} {};", plan.name(&table.schema, &table.name), keyword, quote_ident(name, &plan.db_type))
+}
+
+/// Sequence used by a `nextval('name'::regclass)` default
+fn pg_default_sequence(default: &str) -> Option<&str> {
+    let rest = default.strip_prefix("nextval('")?;
+    rest.split("'::regclass").next().filter(|s| !s.is_empty())
+}
+
+fn diff_tables(plan: &mut MigrationPlan, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    let constraint_names = |snapshot: &SchemaSnapshot, table: &TableInfo| -> Vec<String> {
+        snapshot
+            .objects
+            .constraints
+            .iter()
+            .filter(|c| c.schema == table.schema && c.table == table.name)
+            .map(|c| c.name.clone())
+            .collect()
+    };
+
+    for schema in source.schemas.iter().filter(|s| !target.schemas.contains(s)) {
+        plan.change("schema", schema, None, schema, SchemaChangeKind::Added, Vec::new());
+        let statement = format!("CREATE SCHEMA IF NOT EXISTS {};", quote_ident(schema, &plan.db_type));
+        plan.push(MigrationPhase::CreateSchemas, statement);
+    }
+
+    for table in &source.tables {
+        let constraints = constraint_names(source, table);
+        match target.tables.iter().find(|t| t.schema == table.schema && t.name == table.name) {
+            Some(existing) => {
+                diff_columns(plan, table, existing);
+                // Las restricciones que solo existen en destino se eliminan con DROP CONSTRAINT, no DROP INDEX
+                let mut constraints = constraints;
+                constraints.extend(constraint_names(target, existing));
+                diff_table_keys(plan, table, Some(existing), &constraints);
+            }
+            None => {
+                plan.change("table", &table.schema, None, &table.name, SchemaChangeKind::Added, Vec::new());
+                if plan.pg() {
+                    for sequence in table.columns.iter().filter_map(|c| c.default.as_deref().and_then(pg_default_sequence)) {
+                        plan.push(MigrationPhase::CreateTables, format!("CREATE SEQUENCE IF NOT EXISTS {};", sequence));
+                    }
+                }
+                let mut columns: Vec<String> = table.columns.iter().map(|c| format!("    {}", plan.column_definition(c))).collect();
+                // Una columna AUTO_INCREMENT debe ser clave al crear la tabla
+                if let Some(primary) = table.indexes.iter().find(|i| i.primary).filter(|_| !plan.pg()) {
+                    let keys: Vec<String> = primary.columns.iter().map(|c| quote_ident(c, &plan.db_type)).collect();
+                    columns.push(format!("    PRIMARY KEY ({})", keys.join(", ")));
+                }
+                let statement = format!("CREATE TABLE {} (\n{}\n);", plan.name(&table.schema, &table.name), columns.join(",\n"));
+                plan.push(MigrationPhase::CreateTables, statement);
+                diff_table_keys(plan, table, None, &constraints);
+            }
+        }
+    }
+
+    let removed: Vec<&TableInfo> = target.tables.iter().filter(|t| !source.tables.iter().any(|s| s.schema == t.schema && s.name == t.name)).collect();
+    for table in &removed {
+        plan.change("table", &table.schema, None, &table.name, SchemaChangeKind::Removed, Vec::new());
+        if plan.include_drops {
+            plan.warnings.push(format!("Dropping table {} deletes its rows", table.name));
+        }
+        // Las claves entre tablas eliminadas impedirían borrarlas en cualquier orden
+        for fk in table.foreign_keys.iter().filter(|fk| {
+            removed.iter().any(|r| r.schema == fk.referenced_schema.as_deref().unwrap_or_default() && r.name == fk.referenced_table)
+        }) {
+            let statement = drop_foreign_key_sql(plan, table, &fk.name);
+            plan.push_drop(MigrationPhase::DropForeignKeys, statement);
+        }
+        let statement = format!("DROP TABLE {};", plan.name(&table.schema, &table.name));
+        plan.push_drop(MigrationPhase::DropTables, statement);
+    }
+}
+
+fn diff_constraints(plan: &mut MigrationPlan, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    let dropped_table = |c: &ConstraintInfo| !source.tables.iter().any(|t| t.schema == c.schema && t.name == c.table);
+    let drop_sql = |plan: &MigrationPlan, c: &ConstraintInfo| {
+        let keyword = if plan.db_type == DatabaseType::MySQL { "CHECK" } else { "CONSTRAINT" };
+        format!("ALTER TABLE {} DROP {} {};", plan.name(&c.schema, &c.table), keyword, quote_ident(&c.name, &plan.db_type))
+    };
+
+    for constraint in &source.objects.constraints {
+        let existing = target
+            .objects
+            .constraints
+            .iter()
+            .find(|c| c.schema == constraint.schema && c.table == constraint.table && c.name == constraint.name);
+        if existing.is_some_and(|e| e.kind == constraint.kind && e.definition == constraint.definition) {
+            continue;
+        }
+        let details = existing.map(|e| vec![format!("{} → {}", e.definition, constraint.definition)]).unwrap_or_default();
+        if let Some(existing) = existing {
+            let statement = drop_sql(plan, existing);
+            plan.push(MigrationPhase::DropConstraints, statement);
+        }
+        let kind = if existing.is_some() { SchemaChangeKind::Modified } else { SchemaChangeKind::Added };
+        plan.change("constraint", &constraint.schema, Some(&constraint.table), &constraint.name, kind, details);
+        let statement = format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+            plan.name(&constraint.schema, &constraint.table),
+            quote_ident(&constraint.name, &plan.db_type),
+            constraint.definition
+        );
+        plan.push(MigrationPhase::CreateConstraints, statement);
+    }
+
+    for constraint in target.objects.constraints.iter().filter(|c| {
+        !source.objects.constraints.iter().any(|s| s.schema == c.schema && s.table == c.table && s.name == c.name)
+    }) {
+        // Las restricciones de tablas eliminadas desaparecen con la tabla
+        if dropped_table(constraint) {
+            continue;
+        }
+        plan.change("constraint", &constraint.schema, Some(&constraint.table), &constraint.name, SchemaChangeKind::Removed, Vec::new());
+        let statement = drop_sql(plan, constraint);
+        plan.push_drop(MigrationPhase::DropConstraints, statement);
+    }
+}
+
+fn diff_routines(plan: &mut MigrationPlan, source: &SchemaSnapshot, target: &SchemaSnapshot) {
+    let drop_sql = |plan: &MigrationPlan, r: &RoutineInfo| {
+        if plan.pg() {
+            let arguments = r.signature.strip_prefix(r.name.as_str()).unwrap_or("()");
+            format!("DROP {} {}{};", r.kind.to_uppercase(), plan.name(&r.schema, &r.name), arguments)
+        } else {
+            format!("DROP {} IF EXISTS {};", r.kind.to_uppercase(), quote_ident(&r.name, &plan.db_type))
+        }
+    };
+    let create_sql = |plan: &MigrationPlan, r: &RoutineInfo| {
+        if plan.pg() {
+            format!("{};", r.definition.trim_end().trim_end_matches(';'))
+        } else {
+            // El cuerpo lleva `;` propios: el bloque DELIMITER lo entienden mysql y el ejecutor de scripts
+            format!("DELIMITER $$\n{}$$\nDELIMITER ;", r.definition.trim_end())
+        }
+    };
+
+    for routine in &source.objects.routines {
+        let existing = target.objects.routines.iter().find(|r| r.schema == routine.schema && r.signature == routine.signature);
+        if existing.is_some_and(|e| e.definition == routine.definition) {
+            continue;
+        }
+        // CREATE OR REPLACE de PostgreSQL sustituye la función salvo si cambia lo que devuelve; MySQL necesita borrarla antes
+        if let Some(existing) = existing.filter(|e| !plan.pg() || e.result != routine.result || e.kind != routine.kind) {
+            let statement = drop_sql(plan, existing);
+            plan.push(MigrationPhase::DropRoutines, statement);
+        }
+        let kind = if existing.is_some() { SchemaChangeKind::Modified } else { SchemaChangeKind::Added };
+        plan.change(&routine.kind, &routine.schema, None, &routine.signature, kind, Vec::new());
+        let statement = create_sql(plan, routine);
+        plan.push(MigrationPhase::CreateRoutines, statement);
+    }
+    for routine in target.objects.routines.iter().filter(|r| {
+        !source.objects.routines.iter().any(|s| s.schema == r.schema && s.signature == r.signature)
+    }) {
+        plan.change(&routine.kind, &routine.schema, None, &routine.signature, SchemaChangeKind::Removed, Vec::new());
+        let statement = drop_sql(plan, routine);
+        plan.push_drop(MigrationPhase::DropRoutines, statement);
+    }
+}
+
+/// `rebuild` drops and recreates every view, also the unchanged ones, because the script alters or drops tables they may read
+fn diff_views(plan: &mut MigrationPlan, source: &SchemaSnapshot, target: &SchemaSnapshot, rebuild: bool) {
+    let keyword = |v: &ViewInfo| if v.kind == "materialized_view" { "MATERIALIZED VIEW" } else { "VIEW" };
+    let drop_sql = |plan: &MigrationPlan, v: &ViewInfo| format!("DROP {} IF EXISTS {};", keyword(v), plan.name(&v.schema, &v.name));
+    let create_sql = |plan: &MigrationPlan, v: &ViewInfo| {
+        format!("CREATE {} {} AS\n{};", keyword(v), plan.name(&v.schema, &v.name), v.definition.trim().trim_end_matches(';'))
+    };
+
+    for view in &source.objects.views {
+        let existing = target.objects.views.iter().find(|v| v.schema == view.schema && v.name == view.name);
+        let same = existing.is_some_and(|e| e.kind == view.kind && e.definition.trim() == view.definition.trim());
+        if same && !rebuild {
+            continue;
+        }
+        if let Some(existing) = existing {
+            let statement = drop_sql(plan, existing);
+            plan.push(MigrationPhase::DropViews, statement);
+        }
+        if !same {
+            let kind = if existing.is_some() { SchemaChangeKind::Modified } else { SchemaChangeKind::Added };
+            plan.change("view", &view.schema, None, &view.name, kind, Vec::new());
+        }
+        let statement = create_sql(plan, view);
+        plan.push(MigrationPhase::CreateViews, statement);
+    }
+    for view in target.objects.views.iter().filter(|v| !source.objects.views.iter().any(|s| s.schema == v.schema && s.name == v.name)) {
+        plan.change("view", &view.schema, None, &view.name, SchemaChangeKind::Removed, Vec::new());
+        let statement = drop_sql(plan, view);
+        if plan.include_drops {
+            plan.push(MigrationPhase::DropViews, statement);
+        } else if rebuild {
+            // Se conserva: se recrea con su propia definición después de los cambios
+            plan.push(MigrationPhase::DropViews, statement);
+            let statement = create_sql(plan, view);
+            plan.push(MigrationPhase::CreateViews, statement);
+        }
+    }
+}
+
+/// Compare two snapshots of the same engine and plan the DDL that turns `target` into `source`
+fn plan_schema_migration(source: &SchemaSnapshot, target: &SchemaSnapshot, db_type: &DatabaseType, include_drops: bool) -> MigrationPlan {
+    let mut plan = MigrationPlan {
+        db_type: db_type.clone(),
+        include_drops,
+        changes: Vec::new(),
+        warnings: Vec::new(),
+        statements: std::collections::BTreeMap::new(),
+    };
+    diff_tables(&mut plan, source, target);
+    diff_constraints(&mut plan, source, target);
+    diff_routines(&mut plan, source, target);
+    // Una vista que lee una tabla alterada o borrada bloquea el cambio (PostgreSQL) o queda inválida (MySQL)
+    let rebuild = [MigrationPhase::AlterColumns, MigrationPhase::DropTables].iter().any(|p| plan.statements.contains_key(p));
+    diff_views(&mut plan, source, target, rebuild);
+    plan
+}
+
+/// Compare the schemas of two databases (in two containers or in the same one)
+///
+/// Tables, columns (type, nullability, default), indexes, foreign keys, constraints,
+/// views and functions/procedures are compared by name. Both databases must use the same
+/// engine family (PostgreSQL, or MySQL/MariaDB). The generated script brings the target in
+/// line with the source and is not executed: review it, then run it with `run_sql_script`
+/// (set `script_path` to have it written to a file).
+///
+/// # Arguments
+/// * `request` - Source and target containers and databases, schemas to compare and whether to drop extra objects
+///
+/// # Returns
+/// * `Ok(SchemaDiff)` - Changes, DDL script and warnings about destructive statements
+/// * `Err(String)` - Different engines, unsupported engine or catalog error
+#[tauri::command]
+async fn diff_schemas(request: SchemaDiffRequest, state: State<'_, AppState>) -> Result<SchemaDiff, String> {
+    let docker = state.docker();
+    let source = resolve_db_target(&docker, &request.source_container_id).await?;
+    let target = resolve_db_target(&docker, &request.target_container_id).await?;
+    let family = |t: &DatabaseType| match t {
+        DatabaseType::MariaDB => DatabaseType::MySQL,
+        other => other.clone(),
+    };
+    if family(&source.db_type) != family(&target.db_type) {
+        return Err(format!(
+            "Cannot compare a {} schema with a {} one",
+            source.db_type.to_string(),
+            target.db_type.to_string()
+        ));
+    }
+    if !matches!(source.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB) {
+        return Err(format!("Schema diff is not available for {}", source.db_type.to_string()));
+    }
+
+    let (source_schema, source_objects, target_schema, target_objects) = tokio::try_join!(
+        load_database_schema(&docker, &source, &request.source_database),
+        load_schema_objects(&docker, &source, &request.source_database),
+        load_database_schema(&docker, &target, &request.target_database),
+        load_schema_objects(&docker, &target, &request.target_database),
+    )?;
+    let source_snapshot = schema_snapshot(source_schema, source_objects, &source.db_type, &request.schemas);
+    let target_snapshot = schema_snapshot(target_schema, target_objects, &target.db_type, &request.schemas);
+    // El script se ejecuta en el destino: se genera con su dialecto
+    let plan = plan_schema_migration(&source_snapshot, &target_snapshot, &target.db_type, request.include_drops);
+
+    let source_name = format!("{}/{}", source.container_name, request.source_database);
+    let target_name = format!("{}/{}", target.container_name, request.target_database);
+    let header = format!(
+        "-- Schema migration generated by Docker DB Manager on {}\n-- Source: {}\n-- Target: {}\n-- Review before running it against the target.\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        source_name,
+        target_name
+    );
+    let ddl = if plan.statements.is_empty() { format!("{}\n-- The schemas are identical\n", header) } else { plan.script(&header) };
+
+    let script_path = request.script_path.clone().filter(|p| !p.is_empty());
+    if let Some(path) = &script_path {
+        fs::write(path, &ddl).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    println!("🧬 Diff de esquema {} → {}: {} cambios", source_name, target_name, plan.changes.len());
+
+    Ok(SchemaDiff {
+        source: source_name,
+        target: target_name,
+        db_type: target.db_type.to_string(),
+        changes: plan.changes,
+        ddl,
+        warnings: plan.warnings,
+        script_path,
+    })
+}
+
+// ===== DATA DIFF =====
+
+const DATA_DIFF_DEFAULT_CHUNK_ROWS: u64 = 10_000;
+const DATA_DIFF_DEFAULT_MAX_ROWS: usize = 100;
+// Filas máximas que se traen de cada lado al bajar al detalle de un bloque
+const DATA_DIFF_MAX_FETCH_ROWS: u64 = 100_000;
+
+/// One side of a data comparison
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DataDiffEndpoint {
+    /// Managed PostgreSQL/MySQL/MariaDB container (default database of the container when empty)
+    Container { container_id: String, database: Option<String> },
+    /// PostgreSQL server outside Docker, e.g. the source of a `migrate_database`
+    Local { config: LocalPostgresConfig, database: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataDiffRequest {
+    pub source: DataDiffEndpoint,
+    pub target: DataDiffEndpoint,
+    /// PostgreSQL schema on both sides (default `public`)
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// Tables to compare (every table of both sides when empty)
+    #[serde(default)]
+    pub tables: Vec<String>,
+    /// Rows per primary key range (default 10000)
+    #[serde(default)]
+    pub chunk_size: Option<u64>,
+    /// Differing rows reported per table (default 100)
+    #[serde(default)]
+    pub max_rows: Option<usize>,
+    /// ID to follow `data-diff-progress` events and to stop it with `cancel_query`
+    #[serde(default)]
+    pub diff_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDiffStatus {
+    Identical,
+    Different,
+    MissingInTarget,
+    MissingInSource,
+    Error,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowDiffKind {
+    MissingInTarget,
+    ExtraInTarget,
+    Changed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RowDifference {
+    pub kind: RowDiffKind,
+    /// Primary key of the row
+    pub key: serde_json::Map<String, serde_json::Value>,
+    /// Columns with different values (changed rows)
+    pub columns: Vec<String>,
+    pub source: Option<serde_json::Map<String, serde_json::Value>>,
+    pub target: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DataDiffCounts {
+    pub missing_in_target: u64,
+    pub extra_in_target: u64,
+    pub changed: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TableDataDiff {
+    pub table: String,
+    pub status: DataDiffStatus,
+    pub source_rows: Option<u64>,
+    pub target_rows: Option<u64>,
+    /// Primary key used for the ranges (empty: compared by a single checksum)
+    pub key: Vec<String>,
+    /// Columns present on both sides, the ones compared
+    pub columns: Vec<String>,
+    /// Columns present on only one side
+    pub ignored_columns: Vec<String>,
+    pub chunks: u64,
+    pub differing_chunks: u64,
+    /// Differing rows found in the chunks that were inspected
+    pub rows: DataDiffCounts,
+    pub differences: Vec<RowDifference>,
+    /// Not every differing chunk was inspected (`max_rows` reached)
+    pub truncated: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl TableDataDiff {
+    fn new(table: &str, status: DataDiffStatus) -> Self {
+        TableDataDiff {
+            table: table.to_string(),
+            status,
+            source_rows: None,
+            target_rows: None,
+            key: Vec::new(),
+            columns: Vec::new(),
+            ignored_columns: Vec::new(),
+            chunks: 0,
+            differing_chunks: 0,
+            rows: DataDiffCounts::default(),
+            differences: Vec::new(),
+            truncated: false,
+            error: None,
+            duration_ms: 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DataDiffReport {
+    pub diff_id: String,
+    pub source: String,
+    pub target: String,
+    /// Every compared table has the same rows on both sides
+    pub identical: bool,
+    pub tables: Vec<TableDataDiff>,
+    /// Stopped with `cancel_query`; `tables` only holds the tables compared before it
+    pub cancelled: bool,
+    pub duration_ms: u64,
+}
+
+/// Connected side of a comparison
+struct DataDiffSide {
+    label: String,
+    db_type: DatabaseType,
+    /// Schema (PostgreSQL) or database (MySQL/MariaDB) the tables live in
+    table_schema: String,
+    database: String,
+    conn: SqlConn,
+}
+
+impl DataDiffSide {
+    async fn open(docker: &Docker, endpoint: &DataDiffEndpoint, schema: &str) -> Result<(DataDiffSide, DbTarget), String> {
+        let (target, conn) = match endpoint {
+            DataDiffEndpoint::Container { container_id, database } => {
+                let target = resolve_db_target(docker, container_id).await?;
+                if !matches!(target.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::MariaDB) {
+                    return Err(format!("Data diff is not available for {} ({})", target.db_type.to_string(), target.container_name));
+                }
+                let database = database.clone().filter(|d| !d.is_empty()).unwrap_or_else(|| target.database.clone());
+                let conn = SqlConn::connect(docker, &target, Some(&database)).await?;
+                (DbTarget { database, ..target }, conn)
+            }
+            DataDiffEndpoint::Local { config, database } => {
+                let conn = SqlConn::connect_local(config, database).await?;
+                // Sin contenedor: solo sirve para registrar la comparación como cancelable
+                let target = DbTarget {
+                    container_id: String::new(),
+                    container_name: format!("{}:{}", config.host, config.port),
+                    db_type: DatabaseType::PostgreSQL,
+                    user: config.user.clone(),
+                    password: config.password.clone(),
+                    database: database.clone(),
+                };
+                (target, conn)
+            }
+        };
+
+        let mut side = DataDiffSide {
+            label: format!("{}/{}", target.container_name, target.database),
+            db_type: target.db_type.clone(),
+            table_schema: if target.db_type == DatabaseType::PostgreSQL { schema.to_string() } else { target.database.clone() },
+            database: target.database.clone(),
+            conn,
+        };
+        // Mismo formato de texto en ambos lados para que los checksums coincidan
+        let setup = match side.db_type {
+            DatabaseType::PostgreSQL => "SET TIME ZONE 'UTC'; SET DateStyle = 'ISO, YMD'; SET extra_float_digits = 1",
+            _ => "SET SESSION time_zone = '+00:00', SESSION group_concat_max_len = 1073741824",
+        };
+        if let Err(e) = side.conn.batch(setup).await {
+            side.conn.close().await;
+            return Err(e);
+        }
+        Ok((side, target))
+    }
+
+    async fn table_meta(&mut self, table: &str) -> Result<TableMeta, String> {
+        let table = TableRef { database: Some(self.database.clone()), schema: Some(self.table_schema.clone()), table: table.to_string() };
+        load_table_meta(&mut self.conn, &self.db_type, &table, &self.database).await
+    }
+}
+
+/// Order-independent checksum of a set of rows: hash every row, sort the hashes and hash them again
+fn data_diff_checksum_sql(db_type: &DatabaseType, columns: &[String]) -> (String, String) {
+    let idents: Vec<String> = columns.iter().map(|c| quote_ident(c, db_type)).collect();
+    match db_type {
+        DatabaseType::PostgreSQL => (
+            format!("md5(ROW({})::text)", idents.join(", ")),
+            "count(*) AS row_count, md5(coalesce(string_agg(h, '' ORDER BY h), '')) AS hash".to_string(),
+        ),
+        _ => {
+            // ISNULL distingue NULL de la cadena vacía, que CONCAT_WS omitiría
+            let parts: Vec<String> = idents.iter().map(|c| format!("ISNULL({c}), {c}", c = c)).collect();
+            (
+                format!("MD5(CONCAT_WS(CHAR(31), {}))", parts.join(", ")),
+                "COUNT(*) AS row_count, MD5(IFNULL(GROUP_CONCAT(h ORDER BY h SEPARATOR ''), '')) AS hash".to_string(),
+            )
+        }
+    }
+}
+
+/// `WHERE (key) > (lower) AND (key) <= (upper)`; a missing bound leaves that side open
+fn push_key_range(
+    sql: &mut SqlBuilder,
+    meta: &TableMeta,
+    key: &[String],
+    lower: Option<&[serde_json::Value]>,
+    upper: Option<&[serde_json::Value]>,
+) -> Result<(), String> {
+    let db_type = sql.db_type.clone();
+    let pg = db_type == DatabaseType::PostgreSQL;
+    let idents: Vec<String> = key.iter().map(|c| quote_ident(c, &db_type)).collect();
+    let mut first = true;
+    for (operator, bound) in [(">", lower), ("<=", upper)] {
+        let Some(values) = bound else { continue };
+        sql.push(if first { " WHERE " } else { " AND " });
+        first = false;
+        sql.push(&format!("({}) {} (", idents.join(", "), operator));
+        for (i, (column, value)) in key.iter().zip(values).enumerate() {
+            if i > 0 {
+                sql.push(", ");
+            }
+            let data_type = meta.column(column)?.data_type.clone();
+            sql.bind(json_to_sql_param(value), pg.then_some(data_type.as_str()));
+        }
+        sql.push(")");
+    }
+    Ok(())
+}
+
+/// Row count and checksum of the rows of one key range
+async fn data_diff_chunk_checksum(
+    side: &mut DataDiffSide,
+    meta: &TableMeta,
+    key: &[String],
+    columns: &[String],
+    range: (Option<&[serde_json::Value]>, Option<&[serde_json::Value]>),
+) -> Result<(u64, String), String> {
+    let (row_hash, aggregate) = data_diff_checksum_sql(&side.db_type, columns);
+    let mut sql = SqlBuilder::new(&side.db_type);
+    sql.push(&format!("SELECT {} FROM (SELECT {} AS h FROM {}", aggregate, row_hash, meta.qualified_name));
+    push_key_range(&mut sql, meta, key, range.0, range.1)?;
+    sql.push(") s");
+    let row = side.conn.query_json(&sql).await?.into_iter().next().unwrap_or_default();
+    let count = row.get("row_count").and_then(|v| v.as_u64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))).unwrap_or(0);
+    let hash = row.get("hash").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    Ok((count, hash))
+}
+
+/// Last key of the chunk that starts after `lower`, or None when fewer than `chunk_size` rows are left
+async fn next_chunk_bound(
+    side: &mut DataDiffSide,
+    meta: &TableMeta,
+    key: &[String],
+    lower: Option<&[serde_json::Value]>,
+    chunk_size: u64,
+) -> Result<Option<Vec<serde_json::Value>>, String> {
+    let idents: Vec<String> = key.iter().map(|c| quote_ident(c, &side.db_type)).collect();
+    let mut sql = SqlBuilder::new(&side.db_type);
+    sql.push(&format!("SELECT {} FROM {}", idents.join(", "), meta.qualified_name));
+    push_key_range(&mut sql, meta, key, lower, None)?;
+    sql.push(&format!(" ORDER BY {} LIMIT 1 OFFSET {}", idents.join(", "), chunk_size - 1));
+    let row = side.conn.query_json(&sql).await?.into_iter().next();
+    Ok(row.map(|row| key.iter().map(|k| row.get(k).cloned().unwrap_or(serde_json::Value::Null)).collect()))
+}
+
+async fn fetch_chunk_rows(
+    side: &mut DataDiffSide,
+    meta: &TableMeta,
+    key: &[String],
+    columns: &[String],
+    range: (Option<&[serde_json::Value]>, Option<&[serde_json::Value]>),
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, String> {
+    let quote = |names: &[String]| names.iter().map(|c| quote_ident(c, &side.db_type)).collect::<Vec<_>>().join(", ");
+    let mut sql = SqlBuilder::new(&side.db_type);
+    sql.push(&format!("SELECT {} FROM {}", quote(columns), meta.qualified_name));
+    push_key_range(&mut sql, meta, key, range.0, range.1)?;
+    sql.push(&format!(" ORDER BY {} LIMIT {}", quote(key), DATA_DIFF_MAX_FETCH_ROWS));
+    side.conn.query_json(&sql).await
+}
+
+/// Match the rows of a chunk by primary key and list what differs
+fn compare_chunk_rows(
+    key: &[String],
+    columns: &[String],
+    source: Vec<serde_json::Map<String, serde_json::Value>>,
+    target: Vec<serde_json::Map<String, serde_json::Value>>,
+) -> Vec<RowDifference> {
+    let key_of = |row: &serde_json::Map<String, serde_json::Value>| -> serde_json::Map<String, serde_json::Value> {
+        key.iter().map(|k| (k.clone(), row.get(k).cloned().unwrap_or(serde_json::Value::Null))).collect()
+    };
+    let key_text = |row: &serde_json::Map<String, serde_json::Value>| serde_json::Value::Object(key_of(row)).to_string();
+
+    let mut remaining: HashMap<String, serde_json::Map<String, serde_json::Value>> = HashMap::new();
+    let mut target_order = Vec::new();
+    for row in target {
+        let text = key_text(&row);
+        target_order.push(text.clone());
+        remaining.insert(text, row);
+    }
+
+    let mut differences = Vec::new();
+    for row in source {
+        match remaining.remove(&key_text(&row)) {
+            Some(other) => {
+                let changed: Vec<String> = columns.iter().filter(|c| row.get(*c) != other.get(*c)).cloned().collect();
+                if !changed.is_empty() {
+                    differences.push(RowDifference { kind: RowDiffKind::Changed, key: key_of(&row), columns: changed, source: Some(row), target: Some(other) });
+                }
+            }
+            None => differences.push(RowDifference { kind: RowDiffKind::MissingInTarget, key: key_of(&row), columns: Vec::new(), source: Some(row), target: None }),
+        }
+    }
+    for text in target_order {
+        if let Some(row) = remaining.remove(&text) {
+            differences.push(RowDifference { kind: RowDiffKind::ExtraInTarget, key: key_of(&row), columns: Vec::new(), source: None, target: Some(row) });
+        }
+    }
+    differences
+}
+
+/// Both sides of a data comparison with the settings shared by every table
+struct DataDiffContext<'a> {
+    app: &'a tauri::AppHandle,
+    diff_id: String,
+    source: DataDiffSide,
+    target: DataDiffSide,
+    chunk_size: u64,
+    max_rows: usize,
+    cancel: Arc<tokio::sync::Notify>,
+    cancelled: bool,
+}
+
+impl DataDiffContext<'_> {
+    fn emit_progress(&self, result: &TableDataDiff, tables_done: usize, table_count: usize) {
+        let _ = self.app.emit(
+            "data-diff-progress",
+            json!({
+                "diff_id": self.diff_id,
+                "table": result.table,
+                "tables_done": tables_done,
+                "table_count": table_count,
+                "chunks": result.chunks,
+                "differing_chunks": result.differing_chunks,
+            }),
+        );
+    }
+
+    fn check_cancel(&mut self) -> Result<(), String> {
+        use futures_util::FutureExt;
+        if self.cancelled || self.cancel.notified().now_or_never().is_some() {
+            self.cancelled = true;
+            return Err("Data diff cancelled".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Compare one table present on both sides: checksums per key range, then the rows of the ranges that differ
+async fn diff_table_data(ctx: &mut DataDiffContext<'_>, table: &str, result: &mut TableDataDiff, position: (usize, usize)) -> Result<(), String> {
+    let source_meta = ctx.source.table_meta(table).await?;
+    let target_meta = ctx.target.table_meta(table).await?;
+
+    let in_target = |name: &String| target_meta.columns.iter().any(|c| &c.name == name);
+    result.columns = source_meta.columns.iter().map(|c| c.name.clone()).filter(in_target).collect();
+    result.ignored_columns = source_meta
+        .columns
+        .iter()
+        .chain(target_meta.columns.iter())
+        .map(|c| c.name.clone())
+        .filter(|c| !result.columns.contains(c))
+        .collect();
+    if source_meta.primary_key.iter().all(in_target) {
+        result.key = source_meta.primary_key.clone();
+    }
+    let key = result.key.clone();
+    let columns = result.columns.clone();
+
+    let mut lower: Option<Vec<serde_json::Value>> = None;
+    let (mut source_rows, mut target_rows) = (0u64, 0u64);
+    loop {
+        ctx.check_cancel()?;
+        // Sin clave primaria no hay rangos: un único checksum de toda la tabla
+        let upper = if key.is_empty() { None } else { next_chunk_bound(&mut ctx.source, &source_meta, &key, lower.as_deref(), ctx.chunk_size).await? };
+        let range = (lower.as_deref(), upper.as_deref());
+        let (source_count, source_hash) = data_diff_chunk_checksum(&mut ctx.source, &source_meta, &key, &columns, range).await?;
+        let (target_count, target_hash) = data_diff_chunk_checksum(&mut ctx.target, &target_meta, &key, &columns, range).await?;
+        source_rows += source_count;
+        target_rows += target_count;
+        result.chunks += 1;
+
+        if source_count != target_count || source_hash != target_hash {
+            result.differing_chunks += 1;
+            if key.is_empty() || result.differences.len() >= ctx.max_rows {
+                result.truncated = !key.is_empty();
+            } else {
+                let source_chunk = fetch_chunk_rows(&mut ctx.source, &source_meta, &key, &columns, range).await?;
+                let target_chunk = fetch_chunk_rows(&mut ctx.target, &target_meta, &key, &columns, range).await?;
+                if source_chunk.len() as u64 >= DATA_DIFF_MAX_FETCH_ROWS || target_chunk.len() as u64 >= DATA_DIFF_MAX_FETCH_ROWS {
+                    result.truncated = true;
+                }
+                for difference in compare_chunk_rows(&key, &columns, source_chunk, target_chunk) {
+                    match difference.kind {
+                        RowDiffKind::MissingInTarget => result.rows.missing_in_target += 1,
+                        RowDiffKind::ExtraInTarget => result.rows.extra_in_target += 1,
+                        RowDiffKind::Changed => result.rows.changed += 1,
+                    }
+                    if result.differences.len() < ctx.max_rows {
+                        result.differences.push(difference);
+                    } else {
+                        result.truncated = true;
+                    }
+                }
+            }
+        }
+        result.source_rows = Some(source_rows);
+        result.target_rows = Some(target_rows);
+        ctx.emit_progress(result, position.0, position.1);
+
+        match upper {
+            Some(bound) => lower = Some(bound),
+            None => break,
+        }
+    }
+
+    result.status = if result.differing_chunks == 0 { DataDiffStatus::Identical } else { DataDiffStatus::Different };
+    Ok(())
+}
+
+/// Compare the rows of two databases, or of some of their tables
+///
+/// Works between two managed containers of the same engine family (PostgreSQL, or
+/// MySQL/MariaDB) and between a local PostgreSQL server and a container, e.g. to check a
+/// `migrate_database`. Each table is split into ranges of `chunk_size` rows along its primary
+/// key; both sides compute a row count and a checksum per range on the server, and only the
+/// ranges that differ are fetched to list the missing, extra and changed rows (up to
+/// `max_rows` per table). Tables without a primary key are compared with a single checksum.
+/// Progress is emitted as `data-diff-progress` events and `cancel_query(diff_id)` stops the
+/// comparison after the current range.
+///
+/// # Arguments
+/// * `request` - Source and target (container or local server), schema, tables and chunk size
+///
+/// # Returns
+/// * `Ok(DataDiffReport)` - Result of each table with its differing rows
+/// * `Err(String)` - Different engines, unsupported engine or connection error
+#[tauri::command]
+async fn diff_data(
+    app: tauri::AppHandle,
+    request: DataDiffRequest,
+    state: State<'_, AppState>,
+    running: State<'_, RunningQueriesState>,
+) -> Result<DataDiffReport, String> {
+    let diff_id = match request.diff_id.clone().filter(|id| !id.is_empty()) {
+        Some(id) => {
+            validate_query_id(&id)?;
+            id
+        }
+        None => new_query_id(),
+    };
+    let docker = state.docker();
+    let schema = request.schema.clone().filter(|s| !s.is_empty()).unwrap_or_else(|| "public".to_string());
+    let (source, source_target) = DataDiffSide::open(&docker, &request.source, &schema).await?;
+    let (target, _) = match DataDiffSide::open(&docker, &request.target, &schema).await {
+        Ok(opened) => opened,
+        Err(e) => {
+            source.conn.close().await;
+            return Err(e);
+        }
+    };
+    let family = |t: &DatabaseType| if *t == DatabaseType::PostgreSQL { "PostgreSQL" } else { "MySQL" };
+    if family(&source.db_type) != family(&target.db_type) {
+        let message = format!("Cannot compare {} data with {} data", source.db_type.to_string(), target.db_type.to_string());
+        source.conn.close().await;
+        target.conn.close().await;
+        return Err(message);
+    }
+    let running_query = match running.register(&diff_id, &source_target, &format!("data diff with {}", target.label), 0) {
+        Ok(running_query) => running_query,
+        Err(e) => {
+            source.conn.close().await;
+            target.conn.close().await;
+            return Err(e);
+        }
+    };
+
+    let started = std::time::Instant::now();
+    let mut ctx = DataDiffContext {
+        app: &app,
+        diff_id: diff_id.clone(),
+        source,
+        target,
+        chunk_size: request.chunk_size.filter(|c| *c > 0).unwrap_or(DATA_DIFF_DEFAULT_CHUNK_ROWS),
+        max_rows: request.max_rows.unwrap_or(DATA_DIFF_DEFAULT_MAX_ROWS),
+        cancel: running_query.cancel.clone(),
+        cancelled: false,
+    };
+    println!("🔍 Comparando datos {} ↔ {}", ctx.source.label, ctx.target.label);
+
+    let listed = async {
+        let source_tables = list_transfer_tables(&mut ctx.source.conn, &ctx.source.db_type, &ctx.source.table_schema).await?;
+        let target_tables = list_transfer_tables(&mut ctx.target.conn, &ctx.target.db_type, &ctx.target.table_schema).await?;
+        Ok::<_, String>((source_tables, target_tables))
+    }
+    .await;
+
+    let mut tables = Vec::new();
+    match listed {
+        Ok((source_tables, target_tables)) => {
+            let mut names: Vec<String> = if request.tables.is_empty() {
+                let mut all = source_tables.clone();
+                all.extend(target_tables.iter().filter(|t| !source_tables.contains(t)).cloned());
+                all
+            } else {
+                request.tables.clone()
+            };
+            let mut seen = std::collections::HashSet::new();
+            names.retain(|name| seen.insert(name.clone()));
+
+            for (index, table) in names.iter().enumerate() {
+                let table_started = std::time::Instant::now();
+                let mut result = match (source_tables.contains(table), target_tables.contains(table)) {
+                    (true, true) => TableDataDiff::new(table, DataDiffStatus::Identical),
+                    (true, false) => TableDataDiff::new(table, DataDiffStatus::MissingInTarget),
+                    (false, true) => TableDataDiff::new(table, DataDiffStatus::MissingInSource),
+                    (false, false) => {
+                        let mut missing = TableDataDiff::new(table, DataDiffStatus::Error);
+                        missing.error = Some(format!("Table '{}' not found on either side", table));
+                        missing
+                    }
+                };
+                if result.status == DataDiffStatus::Identical {
+                    if let Err(e) = diff_table_data(&mut ctx, table, &mut result, (index, names.len())).await {
+                        // La tabla a medio comparar no es un error: se omite del informe, que queda como cancelado
+                        if ctx.cancelled {
+                            break;
+                        }
+                        eprintln!("🔍 Tabla {} no comparada: {}", table, e);
+                        result.status = DataDiffStatus::Error;
+                        result.error = Some(e);
+                    }
+                }
+                result.duration_ms = table_started.elapsed().as_millis() as u64;
+                ctx.emit_progress(&result, index + 1, names.len());
+                tables.push(result);
+                if ctx.cancelled {
+                    break;
+                }
+            }
+        }
+        Err(e) => {
+            ctx.source.conn.close().await;
+            ctx.target.conn.close().await;
+            return Err(e);
+        }
+    }
+
+    drop(running_query);
+    let cancelled = ctx.cancelled;
+    let (source_label, target_label) = (ctx.source.label.clone(), ctx.target.label.clone());
+    ctx.source.conn.close().await;
+    ctx.target.conn.close().await;
+
+    let identical = !cancelled && tables.iter().all(|t| t.status == DataDiffStatus::Identical);
+    println!(
+        "🔍 Comparación {} terminada: {} tablas, {}",
+        diff_id,
+        tables.len(),
+        if identical { "sin diferencias" } else { "con diferencias" }
+    );
+    Ok(DataDiffReport {
+        diff_id,
+        source: source_label,
+        target: target_label,
+        identical,
+        tables,
+        cancelled,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -65,6 +65,15 @@ export class DatabaseService {
     return invoke('get_container_logs', { containerId });
   }
 
+  /**
+   * Get parsed inspect data: ports, mounts, networks, masked env, limits and health
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Object>} Container details
+   */
+  static async getContainerDetails(containerId) {
+    return invoke('get_container_details', { containerId });
+  }
+
   /**
    * Get container stats (CPU, memory, etc)
    */
@@ -72,6 +81,628 @@ export class DatabaseService {
     return invoke('get_container_stats', { containerId });
   }
 
+  /**
+   * Get recorded history of a metric
+   * @param {string} containerId - Container ID
+   * @param {string} metric - ContainerStats field, e.g. 'cpu_percentage'
+   * @param {number} [from] - Unix seconds (default: one hour ago)
+   * @param {number} [to] - Unix seconds (default: now)
+   * @returns {Promise<Object>} { resolution_secs, points: [{ timestamp, value }] }
+   */
+  static async getStatsHistory(containerId, metric, from = null, to = null) {
+    return invoke('get_stats_history', { containerId, metric, from, to });
+  }
+
+  /**
+   * Get engine-level metrics (connections, throughput, cache hit ratio...)
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Object>} DatabaseMetrics
+   */
+  static async getDatabaseMetrics(containerId) {
+    return invoke('get_database_metrics', { containerId });
+  }
+
+  /**
+   * Get container stats and engine metrics together
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Object>} { stats, database, database_error }
+   */
+  static async getContainerMetrics(containerId) {
+    return invoke('get_container_metrics', { containerId });
+  }
+
+  /**
+   * Get Prometheus exporter settings and status
+   * @returns {Promise<Object>} { settings: { enabled, port }, running, url, error }
+   */
+  static async getExporterSettings() {
+    return invoke('get_exporter_settings');
+  }
+
+  /**
+   * Enable/disable the loopback /metrics endpoint or change its port
+   * @param {Object} settings - { enabled, port }
+   */
+  static async updateExporterSettings(settings) {
+    return invoke('update_exporter_settings', { settings });
+  }
+
+  /**
+   * List active sessions (pg_stat_activity, PROCESSLIST, currentOp, CLIENT LIST)
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Array>} Sessions ({ id, user, database, client, state, duration_secs, query, ... })
+   */
+  static async listSessions(containerId) {
+    return invoke('list_sessions', { containerId });
+  }
+
+  /**
+   * Cancel the running query of a session
+   */
+  static async cancelSessionQuery(containerId, sessionId) {
+    return invoke('cancel_session_query', { containerId, sessionId });
+  }
+
+  /**
+   * Kill a session
+   */
+  static async terminateSession(containerId, sessionId) {
+    return invoke('terminate_session', { containerId, sessionId });
+  }
+
+  /**
+   * Get the lock blocking tree (PostgreSQL, MySQL 8, MariaDB)
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Object>} { roots: [{ session, lock_mode, relation, wait_secs, blocked }], waiting_sessions, deadlock }
+   */
+  static async getLockGraph(containerId) {
+    return invoke('get_lock_graph', { containerId });
+  }
+
+  /**
+   * Get top statements (pg_stat_statements / events_statements_summary_by_digest)
+   * @param {string} containerId - Container ID
+   * @param {string} [orderBy] - 'total_time' | 'calls' | 'mean_time' | 'rows'
+   * @param {number} [limit] - Number of statements (default 20)
+   */
+  static async getTopQueries(containerId, orderBy = null, limit = null) {
+    return invoke('get_top_queries', { containerId, orderBy, limit });
+  }
+
+  /**
+   * Reset statement statistics
+   */
+  static async resetQueryStats(containerId) {
+    return invoke('reset_query_stats', { containerId });
+  }
+
+  /**
+   * Enable/disable query insights (recreates PostgreSQL/MariaDB containers)
+   */
+  static async setQueryInsights(containerId, enabled) {
+    return invoke('set_query_insights', { containerId, enabled });
+  }
+
+  /**
+   * Explain a statement and get a normalized plan tree with hotspots
+   * @param {string} containerId - Container ID
+   * @param {string|null} database - Database (null = container default)
+   * @param {string} sql - Statement to explain
+   * @param {boolean} analyze - Execute it (inside a rolled back transaction)
+   * @returns {Promise<Object>} { root, hotspot_count, planning_time_ms, execution_time_ms, raw }
+   */
+  static async explainQuery(containerId, database, sql, analyze = false) {
+    return invoke('explain_query', { containerId, database, sql, analyze });
+  }
+
+  /**
+   * List databases inside a managed container
+   * @param {string} containerId - Container ID
+   * @returns {Promise<Array>} [{ name, size_bytes }]
+   */
+  static async listDatabases(containerId) {
+    return invoke('list_databases', { containerId });
+  }
+
+  /**
+   * Get the schema tree of a database (schemas, tables, columns, indexes, foreign keys)
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   */
+  static async getDatabaseSchema(containerId, database) {
+    return invoke('get_database_schema', { containerId, database });
+  }
+
+  /**
+   * Get one page of table rows
+   * @param {string} containerId - Container ID
+   * @param {Object} request - { database, schema, table, filters: [{ column, op, value }], sort: [{ column, descending }], limit, offset, after }
+   * @returns {Promise<Object>} Page ({ columns, primary_key, rows, has_more, next_cursor })
+   */
+  static async browseTable(containerId, request) {
+    return invoke('browse_table', { containerId, request });
+  }
+
+  /**
+   * Insert a table row
+   * @param {string} containerId - Container ID
+   * @param {Object} table - { database, schema, table }
+   * @param {Object} values - Column values
+   * @returns {Promise<Object>} Result ({ affected_rows, row })
+   */
+  static async insertTableRow(containerId, table, values) {
+    return invoke('insert_table_row', { containerId, table, values });
+  }
+
+  /**
+   * Update a table row by primary key
+   * @param {string} containerId - Container ID
+   * @param {Object} table - { database, schema, table }
+   * @param {Object} key - Primary key values
+   * @param {Object} changes - Columns to change
+   * @returns {Promise<Object>} Result ({ affected_rows, row })
+   */
+  static async updateTableRow(containerId, table, key, changes) {
+    return invoke('update_table_row', { containerId, table, key, changes });
+  }
+
+  /**
+   * Delete a table row by primary key
+   * @param {string} containerId - Container ID
+   * @param {Object} table - { database, schema, table }
+   * @param {Object} key - Primary key values
+   * @returns {Promise<Object>} Result ({ affected_rows })
+   */
+  static async deleteTableRow(containerId, table, key) {
+    return invoke('delete_table_row', { containerId, table, key });
+  }
+
+  /**
+   * Scan Redis keys (paged with a cursor)
+   * @param {string} containerId - Container ID
+   * @param {Object} options - { db, cursor, pattern, keyType, count }
+   * @returns {Promise<Object>} Page ({ cursor, keys: [{ key, key_type, ttl_ms, length, memory_bytes }], done })
+   */
+  static async scanRedisKeys(containerId, { db = null, cursor = null, pattern = null, keyType = null, count = null } = {}) {
+    return invoke('scan_redis_keys', { containerId, db, cursor, pattern, keyType, count });
+  }
+
+  /**
+   * Get the value of a Redis key (collections are paged)
+   * @param {string} containerId - Container ID
+   * @param {string} key - Key name
+   * @param {Object} options - { db, cursor, count }
+   * @returns {Promise<Object>} Value ({ key_type, ttl_ms, total, value, truncated, next_cursor })
+   */
+  static async getRedisValue(containerId, key, { db = null, cursor = null, count = null } = {}) {
+    return invoke('get_redis_value', { containerId, db, key, cursor, count });
+  }
+
+  /**
+   * Edit a Redis key
+   * @param {string} containerId - Container ID
+   * @param {string} key - Key name
+   * @param {Object} edit - Edit with an `op` (set_string, hash_set, hash_delete, list_push, list_set, list_remove, set_add, set_remove, zset_add, zset_remove, stream_add, stream_delete)
+   * @param {number|null} db - Logical database
+   * @returns {Promise<string>} Redis reply
+   */
+  static async editRedisKey(containerId, key, edit, db = null) {
+    return invoke('edit_redis_key', { containerId, db, key, edit });
+  }
+
+  /**
+   * Delete Redis keys
+   * @param {string} containerId - Container ID
+   * @param {Array<string>} keys - Keys to delete
+   * @param {number|null} db - Logical database
+   * @returns {Promise<number>} Number of keys deleted
+   */
+  static async deleteRedisKeys(containerId, keys, db = null) {
+    return invoke('delete_redis_keys', { containerId, db, keys });
+  }
+
+  /**
+   * Set or remove the TTL of a Redis key
+   * @param {string} containerId - Container ID
+   * @param {string} key - Key name
+   * @param {number|null} ttlSecs - Seconds to live, null to persist
+   * @param {number|null} db - Logical database
+   * @returns {Promise<boolean>} Whether the key was changed
+   */
+  static async setRedisTtl(containerId, key, ttlSecs, db = null) {
+    return invoke('set_redis_ttl', { containerId, db, key, ttlSecs });
+  }
+
+  /**
+   * Get the Redis keys using the most memory
+   * @param {string} containerId - Container ID
+   * @param {Object} options - { db, pattern, limit, maxKeys }
+   * @returns {Promise<Object>} Report ({ keys, scanned, complete })
+   */
+  static async getRedisBiggestKeys(containerId, { db = null, pattern = null, limit = null, maxKeys = null } = {}) {
+    return invoke('get_redis_biggest_keys', { containerId, db, pattern, limit, maxKeys });
+  }
+
+  /**
+   * List MongoDB collections with stats
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @returns {Promise<Array>} Collections ({ name, kind, document_count, size_bytes, storage_bytes, avg_document_bytes, index_count, index_size_bytes, capped })
+   */
+  static async listMongoCollections(containerId, database) {
+    return invoke('list_mongo_collections', { containerId, database });
+  }
+
+  /**
+   * Find MongoDB documents
+   * @param {string} containerId - Container ID
+   * @param {Object} request - { database, collection, filter, projection, sort, skip, limit } (filter/projection/sort as Extended JSON text)
+   * @returns {Promise<Object>} Page ({ documents, has_more, total, total_exact })
+   */
+  static async findMongoDocuments(containerId, request) {
+    return invoke('find_mongo_documents', { containerId, request });
+  }
+
+  /**
+   * Insert a MongoDB document
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {string} document - Document as Extended JSON text
+   * @returns {Promise<Object>} Inserted _id
+   */
+  static async insertMongoDocument(containerId, database, collection, document) {
+    return invoke('insert_mongo_document', { containerId, database, collection, document });
+  }
+
+  /**
+   * Replace a MongoDB document by _id
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {*} id - Document _id (Extended JSON value)
+   * @param {string} document - New document as Extended JSON text
+   * @returns {Promise<boolean>} Whether a document was replaced
+   */
+  static async replaceMongoDocument(containerId, database, collection, id, document) {
+    return invoke('replace_mongo_document', { containerId, database, collection, id, document });
+  }
+
+  /**
+   * Delete a MongoDB document by _id
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {*} id - Document _id (Extended JSON value)
+   * @returns {Promise<boolean>} Whether a document was deleted
+   */
+  static async deleteMongoDocument(containerId, database, collection, id) {
+    return invoke('delete_mongo_document', { containerId, database, collection, id });
+  }
+
+  /**
+   * List the indexes of a MongoDB collection
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @returns {Promise<Array>} Indexes ({ name, keys, unique, sparse, expire_after_secs, partial_filter, size_bytes })
+   */
+  static async listMongoIndexes(containerId, database, collection) {
+    return invoke('list_mongo_indexes', { containerId, database, collection });
+  }
+
+  /**
+   * Create a MongoDB index
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {string} keys - Index keys as JSON text
+   * @param {Object|null} options - { name, unique, sparse, expire_after_secs }
+   * @returns {Promise<string>} Index name
+   */
+  static async createMongoIndex(containerId, database, collection, keys, options = null) {
+    return invoke('create_mongo_index', { containerId, database, collection, keys, options });
+  }
+
+  /**
+   * Drop a MongoDB index
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {string} name - Index name
+   * @returns {Promise<void>}
+   */
+  static async dropMongoIndex(containerId, database, collection, name) {
+    return invoke('drop_mongo_index', { containerId, database, collection, name });
+  }
+
+  /**
+   * Run a MongoDB aggregation pipeline
+   * @param {string} containerId - Container ID
+   * @param {string} database - Database name
+   * @param {string} collection - Collection name
+   * @param {string} pipeline - Pipeline as Extended JSON array text
+   * @param {number|null} limit - Maximum documents to return
+   * @returns {Promise<Object>} Result ({ documents, truncated })
+   */
+  static async runMongoAggregation(containerId, database, collection, pipeline, limit = null) {
+    return invoke('run_mongo_aggregation', { containerId, database, collection, pipeline, limit });
+  }
+
+  /**
+   * Search the query history (newest first)
+   * @param {Object|null} filter - { text, container, database, success, limit, offset }
+   * @returns {Promise<Array>} Entries ({ id, container_name, database, statement, executed_at, duration_ms, row_count, success, error })
+   */
+  static async searchQueryHistory(filter = null) {
+    return invoke('search_query_history', { filter });
+  }
+
+  /**
+   * Clear the query history
+   * @param {string|null} container - Only for this container (ID or name)
+   * @returns {Promise<number>} Entries removed
+   */
+  static async clearQueryHistory(container = null) {
+    return invoke('clear_query_history', { container });
+  }
+
+  /**
+   * List saved queries
+   * @param {Object|null} filter - { text, tag, container, db_type }
+   * @returns {Promise<Array>} Saved queries
+   */
+  static async listSavedQueries(filter = null) {
+    return invoke('list_saved_queries', { filter });
+  }
+
+  /**
+   * Create or update a saved query (empty id creates a new one)
+   * @param {Object} query - { id, name, description, statement, tags, parameters, container, db_type }
+   * @returns {Promise<Object>} Stored query
+   */
+  static async saveQuery(query) {
+    return invoke('save_query', { query });
+  }
+
+  /**
+   * Delete a saved query
+   * @param {string} queryId - Query ID
+   * @returns {Promise<string>} Result message
+   */
+  static async deleteSavedQuery(queryId) {
+    return invoke('delete_saved_query', { queryId });
+  }
+
+  /**
+   * Fill in the parameters of a saved query
+   * @param {string} queryId - Query ID
+   * @param {Object} values - Parameter values by name
+   * @returns {Promise<string>} Statement
+   */
+  static async renderSavedQuery(queryId, values = {}) {
+    return invoke('render_saved_query', { queryId, values });
+  }
+
+  /**
+   * Export saved queries to a file
+   * @param {string} path - Destination file
+   * @param {Array<string>|null} queryIds - Queries to export (all when null)
+   * @returns {Promise<number>} Queries exported
+   */
+  static async exportSavedQueries(path, queryIds = null) {
+    return invoke('export_saved_queries', { path, queryIds });
+  }
+
+  /**
+   * Import saved queries from a file
+   * @param {string} path - Library file
+   * @returns {Promise<number>} Queries imported
+   */
+  static async importSavedQueries(path) {
+    return invoke('import_saved_queries', { path });
+  }
+
+  /**
+   * Cancel a running query started with a query ID
+   * @param {string} queryId - Query ID
+   * @returns {Promise<string>} Result message
+   */
+  static async cancelQuery(queryId) {
+    return invoke('cancel_query', { queryId });
+  }
+
+  /**
+   * List the queries currently running
+   * @returns {Promise<Array>} Queries ({ query_id, container_name, db_type, statement, started_at, timeout_secs })
+   */
+  static async listRunningQueries() {
+    return invoke('list_running_queries');
+  }
+
+  /**
+   * Open a persistent SQL session (PostgreSQL, MySQL, MariaDB)
+   * @param {string} containerId - Container ID
+   * @param {string|null} database - Database (container default when null)
+   * @param {number|null} idleTimeoutSecs - Close after this idle time
+   * @returns {Promise<Object>} Session ({ session_id, container_name, db_type, database, transaction, created_at, last_used_at, idle_timeout_secs })
+   */
+  static async openSqlSession(containerId, database = null, idleTimeoutSecs = null) {
+    return invoke('open_sql_session', { containerId, database, idleTimeoutSecs });
+  }
+
+  /**
+   * Run SQL in a session
+   * @param {string} sessionId - Session ID
+   * @param {string} sql - Statements
+   * @param {Object} options - { queryId, timeoutSecs, maxRows }
+   * @returns {Promise<Object>} Result ({ query_id, results: [{ columns, rows, affected_rows, truncated }], transaction, outcome, error, duration_ms })
+   */
+  static async executeInSession(sessionId, sql, { queryId = null, timeoutSecs = null, maxRows = null } = {}) {
+    return invoke('execute_in_session', { sessionId, sql, queryId, timeoutSecs, maxRows });
+  }
+
+  /**
+   * Begin a transaction in a session
+   * @param {string} sessionId - Session ID
+   * @returns {Promise<Object>} Session
+   */
+  static async beginSessionTransaction(sessionId) {
+    return invoke('begin_session_transaction', { sessionId });
+  }
+
+  /**
+   * Commit the transaction of a session
+   * @param {string} sessionId - Session ID
+   * @returns {Promise<Object>} Session
+   */
+  static async commitSessionTransaction(sessionId) {
+    return invoke('commit_session_transaction', { sessionId });
+  }
+
+  /**
+   * Roll back the transaction of a session
+   * @param {string} sessionId - Session ID
+   * @returns {Promise<Object>} Session
+   */
+  static async rollbackSessionTransaction(sessionId) {
+    return invoke('rollback_session_transaction', { sessionId });
+  }
+
+  /**
+   * Get a SQL session
+   * @param {string} sessionId - Session ID
+   * @returns {Promise<Object>} Session
+   */
+  static async getSqlSession(sessionId) {
+    return invoke('get_sql_session', { sessionId });
+  }
+
+  /**
+   * List open SQL sessions
+   * @returns {Promise<Array>} Sessions
+   */
+  static async listSqlSessions() {
+    return invoke('list_sql_sessions');
+  }
+
+  /**
+   * Close a SQL session
+   * @param {string} sessionId - Session ID
+   * @returns {Promise<string>} Result message
+   */
+  static async closeSqlSession(sessionId) {
+    return invoke('close_sql_session', { sessionId });
+  }
+
+  /**
+   * Split a SQL script file into statements (preview)
+   * @param {string} path - Script file
+   * @param {string} dbType - postgresql, mysql or mariadb
+   * @returns {Promise<Array>} Statements ({ sql, line })
+   */
+  static async splitSqlScript(path, dbType) {
+    return invoke('split_sql_script', { path, dbType });
+  }
+
+  /**
+   * Run a SQL script file statement by statement (progress in `sql-script-progress` events)
+   * @param {string} containerId - Container ID
+   * @param {string} path - Script file
+   * @param {string|null} database - Database (container default when null)
+   * @param {Object|null} options - { stop_on_error, transaction, query_id, timeout_secs }
+   * @returns {Promise<Object>} Report ({ query_id, statements, succeeded, failed, skipped, committed, outcome, duration_ms })
+   */
+  static async runSqlScript(containerId, path, database = null, options = null) {
+    return invoke('run_sql_script', { containerId, path, database, options });
+  }
+
+  /**
+   * Export a query result or table to a file (progress in `export-progress` events, cancel with cancelQuery(export_id))
+   * @param {string} containerId - Container ID
+   * @param {Object} request - { source: { type: 'query', sql, database } | { type: 'table', table, schema, database }, format: 'csv'|'jsonl'|'sql'|'parquet', path, export_id, csv: { delimiter, header, null_text }, sql_table, rows_per_insert }
+   * @returns {Promise<Object>} Report ({ export_id, path, rows, bytes, duration_ms })
+   */
+  static async exportData(containerId, request) {
+    return invoke('export_data', { containerId, request });
+  }
+
+  /**
+   * Import a CSV or JSON Lines file into a table or MongoDB collection (progress in `import-progress` events)
+   * @param {string} containerId - Container ID
+   * @param {Object} request - { table, schema, database, path, format: 'csv'|'jsonl', import_id, csv: { delimiter, header, null_text }, create_table, column_map, batch_size }
+   * @returns {Promise<Object>} Report ({ import_id, table, method, created_table, columns, ignored_columns, rows_read, rows_imported, rows_rejected, rejected, duration_ms })
+   */
+  static async importData(containerId, request) {
+    return invoke('import_data', { containerId, request });
+  }
+
+  /**
+   * Copy tables and rows between PostgreSQL/MySQL/MariaDB containers (progress in `transfer-progress` events)
+   * @param {Object} request - { source_container_id, source_database, source_schema, target_container_id, target_database, target_schema, include, exclude, if_exists: 'fail'|'replace'|'append', batch_size, transfer_id }
+   * @returns {Promise<Object>} Report ({ transfer_id, source, target, tables, rows_copied, cancelled, duration_ms })
+   */
+  static async transferData(request) {
+    return invoke('transfer_data', { request });
+  }
+
+  /**
+   * Compare the schemas of two databases of the same engine and generate the DDL that aligns the target with the source
+   * @param {Object} request - { source_container_id, source_database, target_container_id, target_database, schemas, include_drops, script_path }
+   * @returns {Promise<Object>} Diff ({ source, target, db_type, changes, ddl, warnings, script_path })
+   */
+  static async diffSchemas(request) {
+    return invoke('diff_schemas', { request });
+  }
+
+  /**
+   * Compare the rows of two databases by primary key ranges and checksums (progress in `data-diff-progress` events)
+   * @param {Object} request - { source, target, schema, tables, chunk_size, max_rows, diff_id }; each side is { type: 'container', container_id, database } or { type: 'local', config: { host, port, user, password }, database }
+   * @returns {Promise<Object>} Report ({ diff_id, source, target, identical, tables, cancelled, duration_ms })
+   */
+  static async diffData(request) {
+    return invoke('diff_data', { request });
+  }
+
+  /**
+   * List resource alert rules
+   * @returns {Promise<Array>} Rules ({ id, name, enabled, container, metric, threshold, for_minutes, notify })
+   */
+  static async listAlertRules() {
+    return invoke('list_alert_rules');
+  }
+
+  /**
+   * Create or update an alert rule (empty id creates a new one)
+   * @param {Object} rule - Alert rule
+   * @returns {Promise<Object>} Stored rule
+   */
+  static async saveAlertRule(rule) {
+    return invoke('save_alert_rule', { rule });
+  }
+
+  /**
+   * Delete an alert rule
+   */
+  static async deleteAlertRule(ruleId) {
+    return invoke('delete_alert_rule', { ruleId });
+  }
+
+  /**
+   * Get alert history, newest first
+   * @param {number} [limit] - Maximum number of entries
+   */
+  static async getAlertHistory(limit = null) {
+    return invoke('get_alert_history', { limit });
+  }
+
+  /**
+   * Clear alert history
+   */
+  static async clearAlertHistory() {
+    return invoke('clear_alert_history');
+  }
+
   /**
    * Execute SQL query in a container
    */
@@ -0,0 +1,50 @@
+/**
+ * Docker Events
+ * Subscribes to the lifecycle events the backend forwards from Docker's /events stream
+ */
+
+import { invoke } from './tauri.js';
+import { createLogger } from './logger.js';
+
+const logger = createLogger('DockerEvents');
+
+export const DOCKER_EVENT = 'docker-event';
+export const DOCKER_EVENTS_STATUS = 'docker-events-status';
+
+/**
+ * Listen to Docker lifecycle events
+ * @param {Function} onEvent - Called with { kind, action, id, name, detail, attributes, time }
+ * @param {Function} [onStatus] - Called with { connected, message } when the subscription changes
+ * @returns {Promise<Function>} Function that removes both listeners
+ */
+export async function listenDockerEvents(onEvent, onStatus = null) {
+  const listen = window.__TAURI__?.event?.listen;
+  if (!listen) {
+    logger.warn('Tauri event API not available, falling back to polling only');
+    return () => {};
+  }
+
+  const unlistenEvent = await listen(DOCKER_EVENT, ({ payload }) => {
+    logger.debug(`${payload.kind} ${payload.action}`, { id: payload.id, name: payload.name });
+    onEvent(payload);
+  });
+
+  const unlistenStatus = await listen(DOCKER_EVENTS_STATUS, ({ payload }) => {
+    logger.info(payload.connected ? 'Events stream connected' : 'Events stream disconnected', payload);
+    if (onStatus) onStatus(payload);
+  });
+
+  // El backend puede haberse conectado antes de que registráramos el listener
+  if (onStatus) {
+    try {
+      onStatus(await invoke('get_docker_events_status'));
+    } catch (error) {
+      logger.error('Failed to get events status', { error: error.message || error });
+    }
+  }
+
+  return () => {
+    unlistenEvent();
+    unlistenStatus();
+  };
+}
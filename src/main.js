@@ -8,6 +8,8 @@ import { CustomSelect } from './components/CustomSelect.js';
 import { DockerCompose } from './components/DockerCompose.js';
 import { cache } from './lib/utils/cache.js';
 import { polling } from './lib/utils/polling.js';
+import { listenDockerEvents } from './lib/utils/dockerEvents.js';
+import { debounce } from './lib/utils/debounce.js';
 import { VirtualScroll } from './lib/utils/virtualScroll.js';
 import { createLogger } from './lib/utils/logger.js';
 import { setupDevTools } from './lib/dev-tools.js';
@@ -1075,6 +1077,7 @@ async function createDB(e) {
       port: parseInt(document.getElementById('db-port').value, 10),
       version: appState.getComponent('versionSelect') ? appState.getComponent('versionSelect').getValue() : '',
       type: appState.getUI("selectedDbType"),
+      queryInsights: document.getElementById('db-query-insights')?.checked || false,
     };
 
     // Apply template if selected
@@ -1857,6 +1860,60 @@ function setupPolling() {
   console.log('[Setup] Polling initialized:', polling.getStats());
 }
 
+/**
+ * Subscribe to Docker events pushed by the backend.
+ * While the stream is connected, polling only acts as a slow safety net.
+ */
+async function setupDockerEvents() {
+  const settings = getSettings();
+  const refreshInterval = settings.refreshInterval || 30000;
+
+  const refreshContainers = debounce(async () => {
+    cache.invalidate('containers');
+    await loadContainers(false, true);
+    await loadDashboardStats();
+  }, 500);
+
+  const refreshImages = debounce(async () => {
+    cache.invalidate('images');
+    await loadImages(false, true);
+  }, 1000);
+
+  const refreshVolumes = debounce(async () => {
+    if (tabManager.getCurrentTab() === 'volumes') {
+      await loadVolumes();
+    }
+  }, 1000);
+
+  const setPollingInterval = (interval) => {
+    for (const key of ['containers', 'dashboard', 'images']) {
+      const task = polling.intervals.get(key);
+      if (task) {
+        task.interval = key === 'images' ? interval * 2 : interval;
+        polling.startInterval(task);
+      }
+    }
+  };
+
+  await listenDockerEvents(
+    (event) => {
+      if (event.kind === 'container') {
+        refreshContainers();
+        if (event.action === 'destroy') refreshVolumes();
+      } else if (event.kind === 'image') {
+        refreshImages();
+      } else if (event.kind === 'volume') {
+        refreshVolumes();
+      }
+    },
+    (status) => {
+      // Con eventos activos el polling solo cubre lo que no llega por /events
+      setPollingInterval(status.connected ? refreshInterval * 10 : refreshInterval);
+      if (status.connected) refreshContainers();
+    }
+  );
+}
+
 /**
  * Cleanup polling on page unload
  */
@@ -2103,6 +2160,9 @@ window.addEventListener('DOMContentLoaded', async () => {
       // Setup intelligent polling
       setupPolling();
       logger.info('[App] Polling system started');
+
+      // Docker events: refresh views as soon as something changes
+      await setupDockerEvents();
       
       // Update footer time
       updateFooterTime();
@@ -3177,14 +3237,34 @@ function formatBytes(bytes) {
   return parseFloat((bytes / k ** i).toFixed(2)) + ' ' + sizes[i];
 }
 
+/**
+ * Load the last recorded values of a metric from the backend history store
+ * @returns {Promise<number[]>} Up to MAX_HISTORY_POINTS values, oldest first
+ */
+async function loadStatsHistory(containerId, metric) {
+  try {
+    const history = await invoke('get_stats_history', { containerId, metric });
+    return history.points.slice(-MAX_HISTORY_POINTS).map((p) => p.value);
+  } catch (e) {
+    // Contenedor sin historial todavía
+    logger.debug('[Monitoring] No history for container', { containerId, metric, error: e });
+    return [];
+  }
+}
+
 async function openMonitoringModal(containerId, containerName) {
   appState.setModal("currentMonitoringContainer", containerId);
   document.getElementById('monitoring-container-name').textContent = containerName;
   document.getElementById('monitoring-modal').classList.add('active');
   
-  // Reset historiales
-  appState.setMonitoring("cpuHistory", []);
-  appState.setMonitoring("memoryHistory", []);
+  // Sembrar historiales con lo que el backend ya tiene guardado
+  const [cpuHistory, memoryHistory] = await Promise.all([
+    loadStatsHistory(containerId, 'cpu_percentage'),
+    loadStatsHistory(containerId, 'memory_percentage'),
+  ]);
+  const historyLength = Math.min(cpuHistory.length, memoryHistory.length);
+  appState.setMonitoring("cpuHistory", cpuHistory.slice(cpuHistory.length - historyLength));
+  appState.setMonitoring("memoryHistory", memoryHistory.slice(memoryHistory.length - historyLength));
   
   try {
     // Cargar Chart.js
@@ -3818,6 +3898,8 @@ const DEFAULT_SETTINGS = {
   chartType: 'line',
   historyDuration: 60,
   showAlerts: true,
+  exporterEnabled: false,
+  exporterPort: 9464,
   
   // Database
   autoStart: true,
@@ -3859,6 +3941,34 @@ function saveSettingsToStorage(settings) {
   }
 }
 
+// El exporter vive en el backend (arranca con la app); Settings solo lo edita
+async function syncExporterSettings() {
+  if (!invoke) return;
+  try {
+    const status = await invoke('get_exporter_settings');
+    const enabledCheckbox = document.getElementById('setting-exporter-enabled');
+    if (enabledCheckbox) enabledCheckbox.checked = status.settings.enabled;
+    const portInput = document.getElementById('setting-exporter-port');
+    if (portInput) portInput.value = status.settings.port;
+  } catch (e) {
+    console.error('Error loading exporter settings:', e);
+  }
+}
+
+async function applyExporterSettings(settings) {
+  if (!invoke) return;
+  try {
+    const status = await invoke('update_exporter_settings', {
+      settings: { enabled: settings.exporterEnabled, port: settings.exporterPort }
+    });
+    if (status.error) {
+      showNotification(`Prometheus exporter: ${status.error}`, 'error');
+    }
+  } catch (e) {
+    showNotification(`Prometheus exporter: ${e}`, 'error');
+  }
+}
+
 function initializeSettings() {
   const settings = loadSettings();
   
@@ -3996,6 +4106,14 @@ function initializeSettings() {
   
   const lazyLoadCheckbox = document.getElementById('setting-lazy-load');
   if (lazyLoadCheckbox) lazyLoadCheckbox.checked = settings.lazyLoad;
+  
+  const exporterEnabledCheckbox = document.getElementById('setting-exporter-enabled');
+  if (exporterEnabledCheckbox) exporterEnabledCheckbox.checked = settings.exporterEnabled;
+  
+  const exporterPortInput = document.getElementById('setting-exporter-port');
+  if (exporterPortInput) exporterPortInput.value = settings.exporterPort;
+  
+  syncExporterSettings();
 }
 
 function saveSettings() {
@@ -4052,6 +4170,8 @@ function saveSettings() {
   const showSuccessCheckbox = document.getElementById('setting-show-success');
   const autoRefreshCheckbox = document.getElementById('setting-auto-refresh');
   const lazyLoadCheckbox = document.getElementById('setting-lazy-load');
+  const exporterEnabledCheckbox = document.getElementById('setting-exporter-enabled');
+  const exporterPortInput = document.getElementById('setting-exporter-port');
   
   const settings = {
     theme,
@@ -4075,7 +4195,9 @@ function saveSettings() {
     showSuccess: showSuccessCheckbox?.checked !== false,
     autoRefresh: autoRefreshCheckbox?.checked !== false,
     refreshInterval,
-    lazyLoad: lazyLoadCheckbox?.checked !== false
+    lazyLoad: lazyLoadCheckbox?.checked !== false,
+    exporterEnabled: exporterEnabledCheckbox?.checked || false,
+    exporterPort: parseInt(exporterPortInput?.value) || 9464
   };
   
   if (saveSettingsToStorage(settings)) {
@@ -4097,6 +4219,7 @@ function saveSettings() {
     
     // Apply all settings
     applySettings(settings);
+    applyExporterSettings(settings);
     
     showNotification('Settings saved successfully', 'success');
   } else {
@@ -4110,6 +4233,7 @@ function resetSettings() {
   }
   
   if (saveSettingsToStorage(DEFAULT_SETTINGS)) {
+    applyExporterSettings(DEFAULT_SETTINGS);
     initializeSettings();
     showNotification('Settings reset to defaults', 'success');
   } else {